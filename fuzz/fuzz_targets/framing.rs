@@ -0,0 +1,71 @@
+//! Splitting a stream into packets must never panic, regardless of how the data is received.
+//! The stream is optionally preceded by an encryption handshake with arbitrary key data, so the
+//! rest of the data is passed through the decryptors.
+#![no_main]
+
+use libfuzzer_sys::{
+    arbitrary::{self, Arbitrary},
+    fuzz_target,
+};
+use pso2packetlib::{
+    connection::codec::Codec,
+    protocol::{login::EncryptionRequestPacket, Packet, PacketType, ParseLimits},
+    PrivateKey, PublicKey,
+};
+use std::sync::OnceLock;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    packet_type: PacketType,
+    /// Unencrypted key data of the encryption request.
+    key_data: Option<Vec<u8>>,
+    chunks: Vec<Vec<u8>>,
+}
+
+fn server_key() -> &'static rsa::RsaPrivateKey {
+    static KEY: OnceLock<rsa::RsaPrivateKey> = OnceLock::new();
+    KEY.get_or_init(|| rsa::RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap())
+}
+
+fuzz_target!(|input: Input| {
+    let key = server_key();
+    let mut codec = Codec::<Packet>::new(
+        input.packet_type,
+        PrivateKey::Key(key.clone()),
+        PublicKey::None,
+    );
+    codec.set_auto_detect(true);
+    codec.set_limits(ParseLimits {
+        max_packet_size: 0x10000,
+        ..Default::default()
+    });
+    if let Some(key_data) = input.key_data {
+        let mut client = Codec::<Packet>::new(
+            input.packet_type,
+            PrivateKey::None,
+            PublicKey::Key(key.to_public_key()),
+        );
+        let request = Packet::EncryptionRequest(EncryptionRequestPacket {
+            rsa_data: key_data.into(),
+        });
+        // key data that doesn't fit into the RSA block can't be sent
+        let Ok(data) = client.encode(&request) else {
+            return;
+        };
+        if codec.feed(&data).is_err() || codec.decode().is_err() {
+            return;
+        }
+    }
+    for chunk in input.chunks {
+        if codec.feed(&chunk).is_err() {
+            return;
+        }
+        loop {
+            match codec.decode() {
+                Ok(Some(_)) => {}
+                Ok(None) => break,
+                Err(_) => return,
+            }
+        }
+    }
+});
@@ -0,0 +1,29 @@
+//! Parsing arbitrary data must never panic.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pso2packetlib::protocol::{Packet, PacketType, PacketView, ProtocolRW};
+
+const PACKET_TYPES: [PacketType; 6] = [
+    PacketType::NGS,
+    PacketType::NA,
+    PacketType::JP,
+    PacketType::Vita,
+    PacketType::Classic,
+    PacketType::Raw,
+];
+
+fuzz_target!(|data: &[u8]| {
+    for packet_type in PACKET_TYPES {
+        let _ = Packet::read(data, packet_type);
+        for view in PacketView::iter(data, packet_type) {
+            let Ok(view) = view else { break };
+            if let Err(e) = view.parse::<Packet>() {
+                let _ = (e.field_path(), e.hexdump(view.data()));
+            }
+            if let Err(packet) = view.parse_partial::<Packet>() {
+                let _ = packet.remaining();
+            }
+        }
+    }
+});
@@ -0,0 +1,21 @@
+//! Reading corrupted PPAC files must never panic.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pso2packetlib::{
+    ppac::{OutputType, PPACReader},
+    protocol::Packet,
+};
+use std::io::Cursor;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(mut reader) = PPACReader::<_, Packet>::open(Cursor::new(data)) else {
+        return;
+    };
+    reader.set_out_type(OutputType::Both);
+    for packet in reader {
+        if packet.is_err() {
+            break;
+        }
+    }
+});
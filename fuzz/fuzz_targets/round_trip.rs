@@ -0,0 +1,25 @@
+//! Parsing written packets must succeed and writing them again must produce the same data.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pso2packetlib::protocol::{Packet, PacketType, ProtocolRW};
+
+fuzz_target!(|input: (PacketType, Packet)| {
+    let (packet_type, packet) = input;
+    // raw packets are written as is and the empty packet isn't written at all
+    if matches!(packet_type, PacketType::Raw) || matches!(packet, Packet::Raw(_) | Packet::None) {
+        return;
+    }
+    // packets of other packet types share IDs with the packets of this type
+    if !packet.is_supported_on(packet_type) {
+        return;
+    }
+    // the first write normalizes the packet (e.g. truncates fixed length strings)
+    let data = packet.write(packet_type);
+    let packets = Packet::read(&data, packet_type).expect("written packet should be readable");
+    assert_eq!(packets.len(), 1);
+    let data = packets[0].write(packet_type);
+    let packets = Packet::read(&data, packet_type).expect("written packet should be readable");
+    assert_eq!(packets.len(), 1);
+    assert_eq!(packets[0].write(packet_type), data);
+});
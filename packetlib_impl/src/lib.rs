@@ -12,7 +12,9 @@ use syn::{parse_macro_input, DeriveInput};
 // Internal derive.
 #[proc_macro_derive(
     PacketReadWrite,
-    attributes(Id, Seek, SeekAfter, Const_u16, Flags, Magic, OnlyOn, NotOn)
+    attributes(
+        Id, Seek, SeekAfter, Const_u16, Flags, Magic, OnlyOn, NotOn, Since, Until
+    )
 )]
 pub fn packet_read_write_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -22,7 +24,9 @@ pub fn packet_read_write_derive(input: TokenStream) -> TokenStream {
 // Public derive.
 #[proc_macro_derive(
     PacketRW,
-    attributes(Id, Seek, SeekAfter, Const_u16, Flags, Magic, OnlyOn, NotOn)
+    attributes(
+        Id, Seek, SeekAfter, Const_u16, Flags, Magic, OnlyOn, NotOn, Since, Until
+    )
 )]
 pub fn pub_packet_read_write_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -43,6 +47,8 @@ pub fn pub_packet_read_write_derive(input: TokenStream) -> TokenStream {
         ManualRW,
         OnlyOn,
         NotOn,
+        Since,
+        Until,
     )
 )]
 pub fn helper_read_write_derive(input: TokenStream) -> TokenStream {
@@ -64,6 +70,8 @@ pub fn helper_read_write_derive(input: TokenStream) -> TokenStream {
         ManualRW,
         OnlyOn,
         NotOn,
+        Since,
+        Until,
     )
 )]
 pub fn pub_helper_read_write_derive(input: TokenStream) -> TokenStream {
@@ -74,7 +82,9 @@ pub fn pub_helper_read_write_derive(input: TokenStream) -> TokenStream {
 // Internal derive.
 #[proc_macro_derive(
     ProtocolReadWrite,
-    attributes(Id, Empty, Raw, Unknown, NGS, Classic, NA, JP, Vita, Category)
+    attributes(
+        Id, Empty, Raw, Unknown, NGS, Classic, NA, JP, Vita, Category, Since, Until
+    )
 )]
 pub fn protocol_read_write_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -84,7 +94,9 @@ pub fn protocol_read_write_derive(input: TokenStream) -> TokenStream {
 // Public derive.
 #[proc_macro_derive(
     ProtocolRW,
-    attributes(Id, Empty, Raw, Unknown, NGS, Classic, NA, JP, Vita, Category)
+    attributes(
+        Id, Empty, Raw, Unknown, NGS, Classic, NA, JP, Vita, Category, Since, Until
+    )
 )]
 pub fn pub_protocol_read_write_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -26,11 +26,48 @@ pub fn packet_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Result<
 
     let mut read = quote! {};
     let mut write = quote! {};
+    let mut skip = quote! {};
+    let mut accessors = vec![];
+
+    let mut fields_schema = quote! {};
+    let mut fields_len = quote! {};
 
     if let Data::Struct(data) = &ast.data {
-        parse_struct_field(&mut read, &mut write, data)?;
+        parse_struct_field(
+            &mut read,
+            &mut write,
+            true,
+            Some(&mut skip),
+            Some(&mut accessors),
+            data,
+        )?;
+        fields_schema = struct_schema(data)?;
+        fields_len = struct_len(data)?;
     }
 
+    let magic = match xor_sub {
+        Some((xor, sub)) => quote! {Some((#xor, #sub))},
+        None => quote! {None},
+    };
+    // statics can't refer to generic parameters
+    let schema = ast.generics.params.is_empty().then(|| {
+        quote! {
+            fn schema() -> Option<&'static #crate_location::protocol::PacketSchema> {
+                use #crate_location::derive_reexports::*;
+
+                static SCHEMA: OnceLock<PacketSchema> = OnceLock::new();
+                Some(SCHEMA.get_or_init(|| PacketSchema {
+                    name: stringify!(#name),
+                    id: #id,
+                    subid: #subid,
+                    flags: #flags,
+                    magic: #magic,
+                    fields: vec![#fields_schema],
+                }))
+            }
+        }
+    });
+
     let code = quote! {
         #[automatically_derived]
         impl #crate_location::protocol::PacketReadWrite for #name {
@@ -38,10 +75,20 @@ pub fn packet_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Result<
                 reader: &mut (impl std::io::Read + std::io::Seek),
                 flags: &#crate_location::protocol::Flags,
                 packet_type: #crate_location::protocol::PacketType
+            ) -> Result<Self, #crate_location::protocol::PacketError> {
+                Self::read_limited(reader, flags, packet_type, &mut Default::default())
+            }
+            #[allow(unused_variables)]
+            fn read_limited(
+                reader: &mut (impl std::io::Read + std::io::Seek),
+                flags: &#crate_location::protocol::Flags,
+                packet_type: #crate_location::protocol::PacketType,
+                ctx: &mut #crate_location::protocol::ReadContext,
             ) -> Result<Self, #crate_location::protocol::PacketError> {
                 use #crate_location::derive_reexports::*;
                 use #crate_location::protocol::PacketError as Error;
                 let packet_name = stringify!(#name);
+                let build = ctx.build();
 
                 let (xor, sub) = (#xor, #sub);
                 #read
@@ -50,17 +97,141 @@ pub fn packet_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Result<
                 &self,
                 packet_type: #crate_location::protocol::PacketType
             ) -> Result<Vec<u8>, #crate_location::protocol::PacketError> {
+                let mut buf = Vec::with_capacity(self.serialized_len(packet_type));
+                self.write_into(&mut buf, packet_type)?;
+                Ok(buf)
+            }
+            fn write_into(
+                &self,
+                writer: &mut impl std::io::Write,
+                packet_type: #crate_location::protocol::PacketType
+            ) -> Result<usize, #crate_location::protocol::PacketError> {
+                self.write_versioned(
+                    writer,
+                    &#crate_location::protocol::ProtocolVersion::latest(packet_type),
+                )
+            }
+            #[allow(unused_variables)]
+            fn write_versioned(
+                &self,
+                writer: &mut impl std::io::Write,
+                version: &#crate_location::protocol::ProtocolVersion,
+            ) -> Result<usize, #crate_location::protocol::PacketError> {
                 use #crate_location::derive_reexports::*;
                 use #crate_location::protocol::PacketError as Error;
                 let packet_name = stringify!(#name);
+                let packet_type = version.packet_type;
+                let build = version.build;
 
-                let mut buf = PacketHeader::new(#id, #subid, #flags).write(packet_type);
-                let writer = &mut buf;
+                let writer = &mut WriteCounter::new(writer);
+                PacketHeader::new(#id, #subid, #flags).write_into(writer, packet_type)?;
                 let (xor, sub) = (#xor, #sub);
                 #write
-                Ok(buf)
+                Ok(writer.count())
+            }
+            #[allow(unused_variables)]
+            fn serialized_len(&self, packet_type: #crate_location::protocol::PacketType) -> usize {
+                use #crate_location::derive_reexports::*;
+                let build = #crate_location::protocol::ProtocolVersion::LATEST_BUILD;
+
+                let mut len = 4;
+                #fields_len
+                len
+            }
+            fn flags(&self) -> #crate_location::protocol::Flags {
+                use #crate_location::derive_reexports::*;
+
+                #flags
+            }
+            #schema
+        }
+    };
+    // accessor structs can't be generic
+    let fields = ast.generics.params.is_empty().then(|| {
+        let vis = &ast.vis;
+        let fields_name = format_ident!("{}Fields", name);
+        let accessors = accessors.iter().enumerate().map(|(index, accessor)| {
+            let FieldAccessor {
+                name: accessor_name,
+                field_name,
+                field_type,
+                read,
+            } = accessor;
+            let doc = format!("Reads the `{field_name}` field.");
+            quote! {
+                #[doc = #doc]
+                pub fn #accessor_name(
+                    &self,
+                ) -> Result<#field_type, #crate_location::protocol::PacketError> {
+                    self.view.read_with_context(|reader| {
+                        use #crate_location::derive_reexports::*;
+                        use #crate_location::protocol::PacketError as Error;
+                        let packet_name = stringify!(#name);
+                        let version = self.view.version();
+                        let packet_type = version.packet_type;
+                        let build = version.build;
+                        let ctx = &mut #crate_location::protocol::ReadContext::default()
+                            .with_build(build);
+
+                        let (xor, sub) = (#xor, #sub);
+                        Self::skip_fields(reader, #index, packet_type, xor, sub, ctx)?;
+                        #read
+                        Ok(#field_name)
+                    })
+                }
+            }
+        });
+        let doc = format!(
+            "Borrowed field accessors of [`{name}`]. Created by [`PacketView::fields`].\n\n\
+            Each accessor skips the preceding fields and reads only the requested one.\n\n\
+            [`PacketView::fields`]: {}::protocol::PacketView::fields",
+            crate_location
+        );
+        quote! {
+            #[doc = #doc]
+            #[automatically_derived]
+            #[derive(Debug, Clone)]
+            #vis struct #fields_name<'a> {
+                view: #crate_location::protocol::PacketView<'a>,
+            }
+
+            #[automatically_derived]
+            #[allow(dead_code)]
+            impl<'a> #fields_name<'a> {
+                /// Skips the fields preceding the field at `index`.
+                #[allow(unused_variables)]
+                fn skip_fields(
+                    reader: &mut (impl std::io::Read + std::io::Seek),
+                    index: usize,
+                    packet_type: #crate_location::protocol::PacketType,
+                    xor: u32,
+                    sub: u32,
+                    ctx: &mut #crate_location::protocol::ReadContext,
+                ) -> Result<(), #crate_location::protocol::PacketError> {
+                    use #crate_location::derive_reexports::*;
+                    use #crate_location::protocol::PacketError as Error;
+                    let packet_name = stringify!(#name);
+                    let build = ctx.build();
+
+                    #skip
+                    Ok(())
+                }
+                #(#accessors)*
+            }
+
+            #[automatically_derived]
+            impl #crate_location::protocol::PacketFields for #name {
+                type Fields<'a> = #fields_name<'a>;
+
+                fn fields<'a>(view: &#crate_location::protocol::PacketView<'a>) -> Self::Fields<'a> {
+                    #fields_name { view: view.clone() }
+                }
             }
         }
+    });
+    let code = quote! {
+        #code
+        #fields
     };
     Ok(code.into())
 }
@@ -70,6 +241,7 @@ pub fn helper_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Result<
 
     let mut read = quote! {};
     let mut write = quote! {};
+    let mut skip = quote! {};
     let repr_type = get_repr(&ast.attrs)?;
     let is_flags = get_flags_struct(&ast.attrs)?;
     let is_bitflags = get_bitflags_struct(&ast.attrs)?;
@@ -80,27 +252,124 @@ pub fn helper_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Result<
         quote! {pso2packetlib}
     };
 
-    match &ast.data {
+    // only structs contain fields that can allocate
+    let mut is_struct = false;
+    let mut len = quote! {};
+    let kind = match &ast.data {
         Data::Struct(_) if is_bitflags.is_some() => {
             let Some(repr_type) = is_bitflags else {
                 unreachable!()
             };
-            parse_bitflags(&mut read, &mut write, repr_type)?
+            let repr = repr_type.as_str();
+            let size = repr_type.size();
+            len = quote! {len += #size;};
+            parse_bitflags(&mut read, &mut write, repr_type)?;
+            Some(quote! {TypeKind::BitFlags {
+                repr: #repr,
+                flags: Self::all()
+                    .iter_names()
+                    .map(|(name, flag)| FlagSchema {
+                        name,
+                        mask: flag.bits() as u128,
+                    })
+                    .collect(),
+            }})
         }
         Data::Struct(data) if is_flags.is_some() => {
             let Some(repr_type) = is_flags else {
                 unreachable!()
             };
-            parse_flags_struct(&mut read, &mut write, data, repr_type)?
+            let repr = repr_type.as_str();
+            let size = repr_type.size();
+            len = quote! {len += #size;};
+            let mut flags = quote! {};
+            parse_flags_struct(&mut read, &mut write, &mut flags, data, repr_type)?;
+            Some(quote! {TypeKind::Flags {
+                repr: #repr,
+                flags: vec![#flags],
+            }})
         }
-        Data::Struct(data) => parse_struct_field(&mut read, &mut write, data)?,
-        Data::Enum(data) => parse_enum(&mut read, &mut write, data, repr_type)?,
-        _ => {}
-    }
+        Data::Struct(data) => {
+            is_struct = true;
+            parse_struct_field(&mut read, &mut write, false, Some(&mut skip), None, data)?;
+            len = struct_len(data)?;
+            let fields = struct_schema(data)?;
+            Some(quote! {TypeKind::Struct(vec![#fields])})
+        }
+        Data::Enum(data) => {
+            let repr = repr_type.as_str();
+            let size = repr_type.size();
+            len = quote! {len += #size;};
+            let mut variants = quote! {};
+            parse_enum(&mut read, &mut write, &mut variants, data, repr_type)?;
+            Some(quote! {TypeKind::Enum {
+                repr: #repr,
+                variants: vec![#variants],
+            }})
+        }
+        _ => None,
+    };
+    // statics can't refer to generic parameters
+    let schema = kind.filter(|_| ast.generics.params.is_empty()).map(|kind| {
+        quote! {
+            fn schema() -> Option<&'static #crate_location::protocol::TypeSchema> {
+                use #crate_location::derive_reexports::*;
 
-    let gen = quote! {
-        #[automatically_derived]
-        impl #crate_location::protocol::HelperReadWrite for #name {
+                static SCHEMA: OnceLock<TypeSchema> = OnceLock::new();
+                Some(SCHEMA.get_or_init(|| TypeSchema {
+                    name: stringify!(#name),
+                    kind: #kind,
+                }))
+            }
+        }
+    });
+
+    let read = if is_struct {
+        quote! {
+            fn read(
+                reader: &mut (impl std::io::Read + std::io::Seek),
+                packet_type: #crate_location::protocol::PacketType,
+                xor: u32,
+                sub: u32
+            ) -> Result<Self, #crate_location::protocol::PacketError> {
+                Self::read_limited(reader, packet_type, xor, sub, &mut Default::default())
+            }
+            #[allow(unused_variables)]
+            fn read_limited(
+                reader: &mut (impl std::io::Read + std::io::Seek),
+                packet_type: #crate_location::protocol::PacketType,
+                xor: u32,
+                sub: u32,
+                ctx: &mut #crate_location::protocol::ReadContext,
+            ) -> Result<Self, #crate_location::protocol::PacketError> {
+                use #crate_location::derive_reexports::*;
+                use #crate_location::protocol::PacketError as Error;
+                let packet_name = stringify!(#name);
+                let build = ctx.build();
+
+                #read
+            }
+            #[allow(unused_variables)]
+            fn skip(
+                reader: &mut (impl std::io::Read + std::io::Seek),
+                packet_type: #crate_location::protocol::PacketType,
+                xor: u32,
+                sub: u32,
+                ctx: &mut #crate_location::protocol::ReadContext,
+            ) -> Result<(), #crate_location::protocol::PacketError> {
+                use #crate_location::derive_reexports::*;
+                use #crate_location::protocol::PacketError as Error;
+                let packet_name = stringify!(#name);
+                let build = ctx.build();
+                // skip all fields
+                let index = usize::MAX;
+
+                #skip
+                Ok(())
+            }
+        }
+    } else {
+        quote! {
             fn read(
                 reader: &mut (impl std::io::Read + std::io::Seek),
                 packet_type: #crate_location::protocol::PacketType,
@@ -113,6 +382,45 @@ pub fn helper_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Result<
 
                 #read
             }
+        }
+    };
+
+    let write = if is_struct {
+        quote! {
+            fn write(
+                &self,
+                writer: &mut impl std::io::Write,
+                packet_type: #crate_location::protocol::PacketType,
+                xor: u32,
+                sub: u32
+            ) -> Result<(), #crate_location::protocol::PacketError> {
+                self.write_versioned(
+                    writer,
+                    &#crate_location::protocol::ProtocolVersion::latest(packet_type),
+                    xor,
+                    sub,
+                )
+            }
+            #[allow(unused_variables)]
+            fn write_versioned(
+                &self,
+                writer: &mut impl std::io::Write,
+                version: &#crate_location::protocol::ProtocolVersion,
+                xor: u32,
+                sub: u32
+            ) -> Result<(), #crate_location::protocol::PacketError> {
+                use #crate_location::derive_reexports::*;
+                use #crate_location::protocol::PacketError as Error;
+                let packet_name = stringify!(#name);
+                let packet_type = version.packet_type;
+                let build = version.build;
+
+                #write
+                Ok(())
+            }
+        }
+    } else {
+        quote! {
             fn write(
                 &self,
                 writer: &mut impl std::io::Write,
@@ -129,12 +437,31 @@ pub fn helper_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Result<
             }
         }
     };
+
+    let gen = quote! {
+        #[automatically_derived]
+        impl #crate_location::protocol::HelperReadWrite for #name {
+            #read
+            #write
+            #[allow(unused_variables)]
+            fn serialized_len(&self, packet_type: #crate_location::protocol::PacketType) -> usize {
+                use #crate_location::derive_reexports::*;
+                let build = #crate_location::protocol::ProtocolVersion::LATEST_BUILD;
+
+                let mut len = 0;
+                #len
+                len
+            }
+            #schema
+        }
+    };
     Ok(gen.into())
 }
 
 fn parse_enum(
     read: &mut TS2,
     write: &mut TS2,
+    schema: &mut TS2,
     data: &DataEnum,
     repr_type: Size,
 ) -> syn::Result<()> {
@@ -209,6 +536,13 @@ fn parse_enum(
             )?;
         }
 
+        let is_default = settings.is_default;
+        schema.extend(quote! {VariantSchema {
+            name: stringify!(#variant_name),
+            value: #discriminant as u128,
+            is_default: #is_default,
+        },});
+
         if settings.is_default {
             default_token = quote! {_ => Self::#variant_name,};
             discriminant.increase();
@@ -229,6 +563,7 @@ fn parse_enum(
 fn parse_flags_struct(
     read: &mut TS2,
     write: &mut TS2,
+    schema: &mut TS2,
     data: &DataStruct,
     repr: Size,
 ) -> syn::Result<()> {
@@ -300,6 +635,10 @@ fn parse_flags_struct(
                 num += #discriminant;
             }
         });
+        schema.extend(quote! {FlagSchema {
+            name: stringify!(#field_name),
+            mask: #discriminant as u128,
+        },});
         discriminant.skip_flag();
     }
 
@@ -346,7 +685,30 @@ fn parse_bitflags(read: &mut TS2, write: &mut TS2, repr: Size) -> syn::Result<()
     Ok(())
 }
 
-fn parse_struct_field(read: &mut TS2, write: &mut TS2, data: &DataStruct) -> syn::Result<()> {
+/// Read code of a single field, used by the field accessors.
+struct FieldAccessor {
+    /// Name of the accessor.
+    name: Ident,
+    /// Name of the variable the field is read into.
+    field_name: Ident,
+    field_type: Type,
+    read: TS2,
+}
+
+/// Generates the read/write code of the struct fields.
+///
+/// If `trace` is set, the read code records every field in the trace of the read context.
+///
+/// `skip` receives the code skipping the fields up to the field at `index` (the generated code
+/// returns right before the field data).
+fn parse_struct_field(
+    read: &mut TS2,
+    write: &mut TS2,
+    trace: bool,
+    mut skip: Option<&mut TS2>,
+    mut accessors: Option<&mut Vec<FieldAccessor>>,
+    data: &DataStruct,
+) -> syn::Result<()> {
     let mut return_token = quote! {};
 
     // unnamed struct
@@ -355,88 +717,121 @@ fn parse_struct_field(read: &mut TS2, write: &mut TS2, data: &DataStruct) -> syn
             let field_name = format_ident!("unnamed_{}", id);
             return_token.extend(quote! {#field_name,});
 
+            let index = id;
             let id = syn::Index::from(id);
             write.extend(quote! { let #field_name = self.#id;});
 
+            let mut tmp_read = quote! {};
+            let mut tmp_skip = quote! {};
             parse_field_type(
                 &field.ty,
-                read,
+                &mut tmp_read,
                 write,
+                &mut tmp_skip,
                 &field_name,
                 &Settings::default(),
                 false,
             )?;
+            if let Some(skip) = skip.as_deref_mut() {
+                skip.extend(quote! {
+                    if index == #index {
+                        return Ok(());
+                    }
+                    #tmp_skip
+                });
+            }
+            if let Some(accessors) = accessors.as_deref_mut() {
+                accessors.push(FieldAccessor {
+                    name: format_ident!("field_{}", index),
+                    field_name: field_name.clone(),
+                    field_type: field.ty.clone(),
+                    read: tmp_read.clone(),
+                });
+            }
+            match trace {
+                true => read.extend(trace_field(&tmp_read, &field_name)),
+                false => read.extend(tmp_read),
+            }
         }
         read.extend(quote! {Ok(Self(#return_token))});
         return Ok(());
     }
 
-    for field in &data.fields {
+    for (index, field) in data.fields.iter().enumerate() {
         let field_name = field.ident.as_ref().unwrap();
         return_token.extend(quote! {#field_name,});
 
         let mut settings = Settings::default();
+        let mut pre_read = quote! {};
 
         for attr in &field.attrs {
             match &attr.meta {
                 syn::Meta::NameValue(_) => {}
                 syn::Meta::Path(path) => {
                     let attribute_name = path.get_ident().unwrap().to_string();
-                    get_attrs(&mut settings, &attribute_name, None, read, write)?;
+                    get_attrs(&mut settings, &attribute_name, None, &mut pre_read, write)?;
                 }
                 syn::Meta::List(list) => {
                     let attribute_name = list.path.get_ident().unwrap().to_string();
-                    get_attrs(&mut settings, &attribute_name, Some(list), read, write)?;
+                    get_attrs(
+                        &mut settings,
+                        &attribute_name,
+                        Some(list),
+                        &mut pre_read,
+                        write,
+                    )?;
                 }
             }
         }
 
         let mut tmp_read = quote! {};
         let mut tmp_write = quote! {};
+        let mut tmp_skip = quote! {};
 
         parse_field_type(
             &field.ty,
             &mut tmp_read,
             &mut tmp_write,
+            &mut tmp_skip,
             field_name,
             &settings,
             true,
         )?;
-
-        if let Some(data) = settings.only_on {
-            read.extend(quote! {let #field_name = if matches!(packet_type, #data) {
-                #tmp_read
-                #field_name
-            } else {
-                Default::default()
-            };});
-            write.extend(quote! {if matches!(packet_type, #data) {
-                #tmp_write
-            }});
-        } else if let Some(data) = settings.not_on {
-            read.extend(quote! {let #field_name = if !matches!(packet_type, #data) {
-                #tmp_read
-                #field_name
-            } else {
-                Default::default()
-            };});
-            write.extend(quote! {if !matches!(packet_type, #data) {
+        let tmp_traced = match trace {
+            true => trace_field(&tmp_read, field_name),
+            false => tmp_read.clone(),
+        };
+
+        let mut post_read = quote! {};
+        let (field_read, field_traced) = if let Some(condition) = field_condition(&settings) {
+            write.extend(quote! {if #condition {
                 #tmp_write
             }});
+            tmp_skip = quote! {if #condition {
+                #tmp_skip
+            }};
+            (
+                quote! {let #field_name = if #condition {
+                    #tmp_read
+                    #field_name
+                } else {
+                    Default::default()
+                };},
+                quote! {let #field_name = if #condition {
+                    #tmp_traced
+                    #field_name
+                } else {
+                    Default::default()
+                };},
+            )
         } else {
-            read.extend(tmp_read);
-            write.extend(tmp_write)
-        }
+            write.extend(tmp_write);
+            (tmp_read, tmp_traced)
+        };
 
         if settings.seek_after != 0 {
             let seek_after = settings.seek_after;
-            read.extend(quote! {reader.seek(std::io::SeekFrom::Current(#seek_after))
-                .map_err(|e| Error::PaddingError{
-                    packet_name,
-                    field_name: stringify!(#field_name),
-                    error: e,
-                })?;
-            });
+            post_read.extend(skip_padding(seek_after, quote! {stringify!(#field_name)}));
             write.extend(quote! {writer.write_all(&[0u8; #seek_after as usize])
                 .map_err(|e| Error::PaddingError{
                     packet_name,
@@ -445,11 +840,235 @@ fn parse_struct_field(read: &mut TS2, write: &mut TS2, data: &DataStruct) -> syn
                 })?;
             });
         }
+
+        if let Some(skip) = skip.as_deref_mut() {
+            skip.extend(quote! {
+                #pre_read
+                if index == #index {
+                    return Ok(());
+                }
+                #tmp_skip
+                #post_read
+            });
+        }
+        if let Some(accessors) = accessors.as_deref_mut() {
+            accessors.push(FieldAccessor {
+                name: field_name.clone(),
+                field_name: field_name.clone(),
+                field_type: field.ty.clone(),
+                read: field_read.clone(),
+            });
+        }
+        read.extend(pre_read);
+        read.extend(field_traced);
+        read.extend(post_read);
     }
     read.extend(quote! {Ok(Self{#return_token})});
     Ok(())
 }
 
+/// Generates the code skipping `amount` bytes of padding (or seeking back if `amount` is
+/// negative).
+fn skip_padding(amount: i64, field_name: TS2) -> TS2 {
+    let skip = match u64::try_from(amount) {
+        Ok(amount) => quote! {ctx.skip_padding(reader, #amount)},
+        Err(_) => quote! {reader.seek(std::io::SeekFrom::Current(#amount)).map(|_| ())},
+    };
+    quote! {#skip
+        .map_err(|e| Error::PaddingError{
+            packet_name,
+            field_name: #field_name,
+            error: e,
+        })?;
+    }
+}
+
+/// Returns the condition on which the field is present (if the field is conditional).
+///
+/// The condition refers to the `packet_type` and `build` variables.
+fn field_condition(settings: &Settings) -> Option<TS2> {
+    let mut conditions = vec![];
+    if let Some(data) = &settings.only_on {
+        conditions.push(quote! {matches!(packet_type, #data)});
+    } else if let Some(data) = &settings.not_on {
+        conditions.push(quote! {!matches!(packet_type, #data)});
+    }
+    if let Some(since) = &settings.since {
+        conditions.push(quote! {build >= (#since)});
+    }
+    if let Some(until) = &settings.until {
+        conditions.push(quote! {build < (#until)});
+    }
+    (!conditions.is_empty()).then(|| quote! {#(#conditions)&&*})
+}
+
+/// Wraps the field read code to record the field in the trace of the read context.
+fn trace_field(read: &TS2, field_name: &Ident) -> TS2 {
+    quote! {
+        let trace_start = ctx.trace_start(reader);
+        #read
+        ctx.trace_field(stringify!(#field_name), trace_start, reader, || {
+            (&&TraceValue(&#field_name)).trace_value()
+        });
+    }
+}
+
+fn struct_schema(data: &DataStruct) -> syn::Result<TS2> {
+    let mut fields = quote! {};
+    for (id, field) in data.fields.iter().enumerate() {
+        let field_name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => id.to_string(),
+        };
+        let mut seek = 0i64;
+        let mut seek_after = 0i64;
+        let mut const_u16 = quote! {None};
+        let mut condition = quote! {FieldCondition::Always};
+        let mut since = quote! {None};
+        let mut until = quote! {None};
+        let mut manual_rw = quote! {None};
+        let type_name = type_name(&field.ty);
+        let field_type = &field.ty;
+        let mut ty = quote! {<#field_type as HelperReadWrite>::type_desc};
+
+        for attr in &field.attrs {
+            let syn::Meta::List(list) = &attr.meta else {
+                continue;
+            };
+            let Some(attribute_name) = list.path.get_ident() else {
+                continue;
+            };
+            match attribute_name.to_string().as_str() {
+                "Seek" => seek += list.parse_args::<LitInt>()?.base10_parse::<i64>()?,
+                "SeekAfter" => seek_after = list.parse_args::<LitInt>()?.base10_parse()?,
+                "Const_u16" => {
+                    let num: u16 = list.parse_args::<LitInt>()?.base10_parse()?;
+                    const_u16 = quote! {Some(#num)};
+                }
+                "OnlyOn" => {
+                    let data = &list.tokens;
+                    condition = quote! {FieldCondition::only_on(|packet_type| {
+                        matches!(packet_type, #data)
+                    })};
+                }
+                "NotOn" => {
+                    let data = &list.tokens;
+                    condition = quote! {FieldCondition::not_on(|packet_type| {
+                        matches!(packet_type, #data)
+                    })};
+                }
+                "Since" => {
+                    let data = &list.tokens;
+                    since = quote! {Some(#data)};
+                }
+                "Until" => {
+                    let data = &list.tokens;
+                    until = quote! {Some(#data)};
+                }
+                "ManualRW" => {
+                    let ManualRWArgs {
+                        read_fn,
+                        write_fn,
+                        size,
+                    } = list.parse_args()?;
+                    manual_rw = quote! {Some((stringify!(#read_fn), stringify!(#write_fn)))};
+                    let size = match size {
+                        Some(size) => quote! {Some(#size)},
+                        None => quote! {None},
+                    };
+                    // the field isn't encoded by its type
+                    ty = quote! {|| TypeDesc::Opaque {
+                        name: #type_name,
+                        size: #size,
+                    }};
+                }
+                _ => {}
+            }
+        }
+
+        fields.extend(quote! {FieldSchema {
+            name: #field_name,
+            type_name: #type_name,
+            seek: #seek,
+            seek_after: #seek_after,
+            const_u16: #const_u16,
+            condition: #condition,
+            since: #since,
+            until: #until,
+            manual_rw: #manual_rw,
+            ty: #ty,
+        },});
+    }
+    Ok(fields)
+}
+
+/// Returns the code estimating the size of the struct fields.
+fn struct_len(data: &DataStruct) -> syn::Result<TS2> {
+    let mut len = quote! {};
+    if let Fields::Unnamed(fields) = &data.fields {
+        for id in 0..fields.unnamed.len() {
+            let id = syn::Index::from(id);
+            len.extend(quote! {len += HelperReadWrite::serialized_len(&self.#id, packet_type);});
+        }
+        return Ok(len);
+    }
+    for field in &data.fields {
+        let field_name = field.ident.as_ref().unwrap();
+        let mut padding = 0i64;
+        let mut field_len =
+            quote! {HelperReadWrite::serialized_len(&self.#field_name, packet_type)};
+        let mut settings = Settings::default();
+
+        for attr in &field.attrs {
+            let syn::Meta::List(list) = &attr.meta else {
+                continue;
+            };
+            let Some(attribute_name) = list.path.get_ident() else {
+                continue;
+            };
+            match attribute_name.to_string().as_str() {
+                "Seek" | "SeekAfter" => {
+                    padding += list.parse_args::<LitInt>()?.base10_parse::<i64>()?
+                }
+                "Const_u16" => padding += 2,
+                "OnlyOn" => settings.only_on = Some(list.tokens.clone()),
+                "NotOn" => settings.not_on = Some(list.tokens.clone()),
+                "Since" => settings.since = Some(list.tokens.clone()),
+                "Until" => settings.until = Some(list.tokens.clone()),
+                // manually written fields have an unknown layout unless the size is specified
+                "ManualRW" => {
+                    field_len = match list.parse_args::<ManualRWArgs>()?.size {
+                        Some(size) => quote! {#size},
+                        None => quote! {std::mem::size_of_val(&self.#field_name)},
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let padding = padding.max(0) as usize;
+        let condition = field_condition(&settings).unwrap_or(quote! {true});
+        len.extend(quote! {
+            len += #padding;
+            if #condition {
+                len += #field_len;
+            }
+        });
+    }
+    Ok(len)
+}
+
+/// Returns the type as it would be written in the source.
+fn type_name(in_type: &Type) -> String {
+    in_type
+        .to_token_stream()
+        .to_string()
+        .split_whitespace()
+        .collect::<String>()
+        .replace(',', ", ")
+        .replace(';', "; ")
+}
+
 #[derive(Default)]
 struct Settings {
     seek_after: i64,
@@ -457,6 +1076,8 @@ struct Settings {
     to_skip: bool,
     only_on: Option<TS2>,
     not_on: Option<TS2>,
+    since: Option<TS2>,
+    until: Option<TS2>,
     manual_rw: Option<(TS2, TS2)>,
 }
 
@@ -488,22 +1109,34 @@ fn get_attrs(
             };
             set.not_on = Some(attrs);
         }
+        "Since" => {
+            let Some(attrs) = list.map(|l| l.tokens.clone()) else {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "Invalid syntax \nPerhaps you ment Since(..)?",
+                ));
+            };
+            set.since = Some(attrs);
+        }
+        "Until" => {
+            let Some(attrs) = list.map(|l| l.tokens.clone()) else {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "Invalid syntax \nPerhaps you ment Until(..)?",
+                ));
+            };
+            set.until = Some(attrs);
+        }
         "ManualRW" => {
-            let attrs: FnList = list.unwrap().parse_args()?;
+            let attrs: ManualRWArgs = list.unwrap().parse_args()?;
             set.manual_rw = Some((
-                attrs.fields[0].clone().into_token_stream(),
-                attrs.fields[1].clone().into_token_stream(),
+                attrs.read_fn.into_token_stream(),
+                attrs.write_fn.into_token_stream(),
             ));
         }
         "Seek" => {
             let amount: i64 = list.unwrap().parse_args::<LitInt>()?.base10_parse()?;
-            read.extend(quote! {reader.seek(std::io::SeekFrom::Current(#amount))
-                .map_err(|e| Error::PaddingError{
-                    packet_name,
-                    field_name: "unknown",
-                    error: e,
-                })?;
-            });
+            read.extend(skip_padding(amount, quote! {"unknown"}));
             write.extend(quote! {writer.write_all(&[0u8; #amount as usize])
                 .map_err(|e| Error::PaddingError{
                     packet_name,
@@ -541,11 +1174,12 @@ fn parse_field_type(
     in_type: &Type,
     read: &mut TS2,
     write: &mut TS2,
+    skip: &mut TS2,
     field_name: &Ident,
     set: &Settings,
     is_first: bool,
 ) -> syn::Result<()> {
-    let (type_read, type_write) = type_read_write(
+    let (type_read, type_write, type_skip) = type_read_write(
         in_type.to_token_stream().to_string(),
         field_name,
         set,
@@ -553,6 +1187,7 @@ fn parse_field_type(
     )?;
     read.extend(type_read);
     write.extend(type_write);
+    skip.extend(type_skip);
     Ok(())
 }
 
@@ -561,7 +1196,7 @@ fn type_read_write(
     field_name: &Ident,
     set: &Settings,
     is_self: bool,
-) -> syn::Result<(TS2, TS2)> {
+) -> syn::Result<(TS2, TS2, TS2)> {
     let mut read = quote! {};
     let mut write = quote! {};
 
@@ -589,11 +1224,20 @@ fn type_read_write(
                     error: Box::new(e)
                 })?;
         });
-        return Ok((read, write));
+        // manually read fields can only be skipped by reading them
+        let skip = quote! {
+            #read_fn(reader, packet_type, xor, sub)
+                .map_err(|e| Error::CompositeFieldError{
+                    packet_name,
+                    field_name: stringify!(#field_name),
+                    error: Box::new(e)
+                })?;
+        };
+        return Ok((read, write, skip));
     }
 
     let out_type = TS2::from_str(&full_type_path)?;
-    read.extend(quote! {let #field_name = <#out_type as HelperReadWrite>::read(reader, packet_type, xor, sub)
+    read.extend(quote! {let #field_name = <#out_type as HelperReadWrite>::read_limited(reader, packet_type, xor, sub, ctx)
                 .map_err(|e| {
                     Error::CompositeFieldError{
                         packet_name,
@@ -602,7 +1246,18 @@ fn type_read_write(
                     }
                 })?;
             });
-    write.extend(quote! {#write_name.write(writer, packet_type, xor, sub)
+    write.extend(
+        quote! {HelperReadWrite::write_versioned(&#write_name, writer, version, xor, sub)
+            .map_err(|e| {
+                Error::CompositeFieldError{
+                    packet_name,
+                    field_name: stringify!(#field_name),
+                    error: Box::new(e),
+                }
+            })?;
+        },
+    );
+    let skip = quote! {<#out_type as HelperReadWrite>::skip(reader, packet_type, xor, sub, ctx)
         .map_err(|e| {
             Error::CompositeFieldError{
                 packet_name,
@@ -610,8 +1265,8 @@ fn type_read_write(
                 error: Box::new(e),
             }
         })?;
-    });
-    Ok((read, write))
+    };
+    Ok((read, write, skip))
 }
 
 fn get_packet_id(attrs: &[Attribute]) -> syn::Result<(u8, u16)> {
@@ -736,6 +1391,28 @@ enum Size {
     U128,
 }
 
+impl Size {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Size::U8 => "u8",
+            Size::U16 => "u16",
+            Size::U32 => "u32",
+            Size::U64 => "u64",
+            Size::U128 => "u128",
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Size::U8 => 1,
+            Size::U16 => 2,
+            Size::U32 => 4,
+            Size::U64 => 8,
+            Size::U128 => 16,
+        }
+    }
+}
+
 struct AttributeList {
     fields: Punctuated<LitInt, Token![,]>,
 }
@@ -748,14 +1425,26 @@ impl Parse for AttributeList {
     }
 }
 
-struct FnList {
-    fields: Punctuated<Ident, Token![,]>,
+/// Arguments of the `ManualRW` attribute: `(read_fn, write_fn[, encoded_size])`.
+struct ManualRWArgs {
+    read_fn: Ident,
+    write_fn: Ident,
+    size: Option<LitInt>,
 }
 
-impl Parse for FnList {
+impl Parse for ManualRWArgs {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let read_fn = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let write_fn = input.parse()?;
+        let mut size = None;
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            size = Some(input.parse()?);
+        }
         Ok(Self {
-            fields: Punctuated::parse_separated_nonempty(input)?,
+            read_fn,
+            write_fn,
+            size,
         })
     }
 }
@@ -10,8 +10,16 @@ use syn::{
 struct OutputCode {
     read: TS2,
     write: TS2,
+    empty: TS2,
+    len: TS2,
     category: TS2,
+    category_for: TS2,
+    header: TS2,
+    name: TS2,
+    supported: TS2,
+    name_for: TS2,
     read_raw: TS2,
+    registry: TS2,
 }
 
 pub fn protocol_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Result<TokenStream> {
@@ -36,75 +44,111 @@ pub fn protocol_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Resul
     let OutputCode {
         read,
         write,
+        empty,
+        len,
         category,
+        category_for,
+        header,
+        name: names,
+        supported,
+        name_for,
         read_raw,
+        registry,
     } = out_code;
 
     let gen = quote! {
         #[automatically_derived]
         impl #crate_location::protocol::ProtocolRW for #name {
             fn write(&self, packet_type: #crate_location::protocol::PacketType) -> Vec<u8> {
+                let mut buf = Vec::with_capacity(self.serialized_len(packet_type));
+                self.write_into(&mut buf, packet_type)
+                    .expect("Writing to a Vec shouldn't fail");
+                buf
+            }
+            fn write_into(
+                &self,
+                writer: &mut impl std::io::Write,
+                packet_type: #crate_location::protocol::PacketType,
+            ) -> Result<usize, #crate_location::protocol::PacketError> {
+                self.write_versioned(
+                    writer,
+                    &#crate_location::protocol::ProtocolVersion::latest(packet_type),
+                )
+            }
+            fn write_versioned(
+                &self,
+                writer: &mut impl std::io::Write,
+                version: &#crate_location::protocol::ProtocolVersion,
+            ) -> Result<usize, #crate_location::protocol::PacketError> {
                 use #crate_location::derive_reexports::*;
                 use #crate_location::protocol::PacketError;
+                let packet_type = version.packet_type;
 
-                let mut buf: Vec<u8> = vec![0; 4];
-                let packet_out: Result<Vec<u8>, PacketError> = match self {
+                // empty packets don't have a length field
+                #[allow(clippy::match_single_binding)]
+                match self {
+                    #empty
+                    _ => {}
+                }
+                write_length_prefixed(writer, self.name(), |writer| match self {
                     #write
+                })
+            }
+            #[allow(unused_variables)]
+            fn serialized_len(&self, packet_type: #crate_location::protocol::PacketType) -> usize {
+                use #crate_location::derive_reexports::*;
+
+                let body_len = match self {
+                    #len
                 };
-                buf.extend(packet_out.expect("Writing to a Vec shouldn't fail"));
-                let len = buf.len().next_multiple_of(4);
-                buf.resize(len, 0);
-                let len = (len as u32).to_le_bytes();
-                buf[..4].copy_from_slice(&len);
-                buf
+                (body_len + 4).next_multiple_of(4)
             }
             fn read(
                 input: &[u8],
                 packet_type: #crate_location::protocol::PacketType,
             ) -> Result<Vec<Self>, #crate_location::protocol::PacketError> {
+                Self::read_limited(input, packet_type, &Default::default())
+            }
+            fn read_view(
+                view: &#crate_location::protocol::PacketView<'_>,
+            ) -> Result<Self, #crate_location::protocol::PacketError> {
+                Self::read_view_limited(view, &Default::default())
+            }
+            fn read_view_limited(
+                view: &#crate_location::protocol::PacketView<'_>,
+                limits: &#crate_location::protocol::ParseLimits,
+            ) -> Result<Self, #crate_location::protocol::PacketError> {
+                Self::read_view_with(
+                    view,
+                    &mut #crate_location::protocol::ReadContext::new(
+                        #crate_location::protocol::ParseBudget::new(*limits),
+                    ),
+                )
+            }
+            #[allow(unused_variables)]
+            fn read_view_with(
+                view: &#crate_location::protocol::PacketView<'_>,
+                ctx: &mut #crate_location::protocol::ReadContext,
+            ) -> Result<Self, #crate_location::protocol::PacketError> {
                 use #crate_location::derive_reexports::*;
                 use #crate_location::protocol::PacketError;
-                let packet_name = stringify!(#name);
 
-                let mut packets: Vec<Self> = vec![];
-                let buffer_length = input.len();
-                let mut pointer = 0;
-                loop {
-                    if pointer >= buffer_length {
-                        break;
-                    }
-                    if input[pointer..].len() <= 4 {
-                        break;
-                    }
-                    let len = (&input[pointer..pointer + 4]).read_u32::<LittleEndian>().map_err(|e| {
-                        PacketError::PacketLengthError{
-                            error: e,
-                        }
-                    })? as usize - 4;
-                    pointer += 4;
-                    if input[pointer..].len() < len {
-                        return Err(PacketError::PacketLengthError{
-                            error: std::io::ErrorKind::UnexpectedEof.into()
-                        });
-                    }
-                    #read_raw
-                    let mut buf_tmp = std::io::Cursor::new(&input[pointer..pointer + len]);
-                    let header = PacketHeader::read(&mut buf_tmp, packet_type).map_err(|e| {
-                        PacketError::CompositeFieldError {
-                            packet_name: stringify!(#name),
-                            field_name: "header",
-                            error: Box::new(e),
-                        }
-                    })?;
-                    let flags = &header.flag;
+                ctx.budget().limits().check_packet_size(view.len())?;
+                ctx.set_build(view.version().build);
+                let build = ctx.build();
+                let packet_type = view.packet_type();
+                #read_raw
+                let header = view.header();
+                let flags = &header.flag;
 
-                    pointer += len;
-                    match (header.id, header.subid, packet_type) {
+                view.read_with_context(|buf_tmp| {
+                    let packet = match (header.id, header.subid, packet_type) {
                         #read
-                    }
-                }
-
-                Ok(packets)
+                    };
+                    ctx.record_end(buf_tmp)
+                        .map_err(|error| PacketError::PacketLengthError { error })?;
+                    Ok(packet)
+                })
             }
             fn get_category(&self) -> #crate_location::protocol::PacketCategory {
                 let cat = match self {
@@ -113,6 +157,65 @@ pub fn protocol_deriver(ast: &syn::DeriveInput, is_internal: bool) -> syn::Resul
                 };
                 cat
             }
+            fn category_for(
+                id: u8,
+                subid: u16,
+                packet_type: #crate_location::protocol::PacketType,
+            ) -> #crate_location::protocol::PacketCategory {
+                use #crate_location::protocol::PacketType;
+
+                match (id, subid, packet_type) {
+                    #category_for
+                    _ => Default::default(),
+                }
+            }
+            fn header(
+                &self,
+                packet_type: #crate_location::protocol::PacketType,
+            ) -> Option<#crate_location::protocol::PacketHeader> {
+                use #crate_location::derive_reexports::*;
+
+                if !self.is_supported_on(packet_type) {
+                    return None;
+                }
+                match self {
+                    #header
+                    _ => None,
+                }
+            }
+            fn name(&self) -> &'static str {
+                match self {
+                    #names
+                }
+            }
+            #[allow(unused_variables)]
+            fn is_supported_on(&self, packet_type: #crate_location::protocol::PacketType) -> bool {
+                use #crate_location::protocol::PacketType;
+
+                match self {
+                    #supported
+                    _ => true,
+                }
+            }
+            fn name_for(
+                id: u8,
+                subid: u16,
+                packet_type: #crate_location::protocol::PacketType,
+            ) -> Option<&'static str> {
+                use #crate_location::protocol::PacketType;
+
+                match (id, subid, packet_type) {
+                    (_, _, PacketType::Raw) => None,
+                    #name_for
+                    _ => None,
+                }
+            }
+            fn registry() -> &'static #crate_location::protocol::PacketRegistry {
+                use #crate_location::derive_reexports::*;
+
+                static REGISTRY: OnceLock<PacketRegistry> = OnceLock::new();
+                REGISTRY.get_or_init(|| PacketRegistry::new(vec![#registry]))
+            }
         }
     };
     Ok(gen.into())
@@ -123,12 +226,23 @@ fn parse_enum_field(out_code: &mut OutputCode, data: &DataEnum) -> syn::Result<(
     let OutputCode {
         read,
         write,
+        empty,
+        len,
         category,
+        category_for,
+        header,
+        name: names,
+        supported,
+        name_for,
         read_raw,
+        registry,
     } = out_code;
     for variant in &data.variants {
         let name = &variant.ident;
         let mut settings = Settings::default();
+        names.extend(quote! {
+            Self::#name { .. } => stringify!(#name),
+        });
 
         for attr in &variant.attrs {
             match &attr.meta {
@@ -164,11 +278,15 @@ fn parse_enum_field(out_code: &mut OutputCode, data: &DataEnum) -> syn::Result<(
         };
 
         if let PacketType::Empty = settings.packet_type {
-            write.extend(quote! {
-                Self::#name => return vec![],
-            })
+            empty.extend(quote! {
+                Self::#name => return Ok(0),
+            });
+            len.extend(quote! {
+                Self::#name => return 0,
+            });
         }
         let mut push_string = quote! {};
+        let mut schema = quote! {None};
         if !settings.category.is_empty() {
             category_stream = settings.category
         }
@@ -178,14 +296,25 @@ fn parse_enum_field(out_code: &mut OutputCode, data: &DataEnum) -> syn::Result<(
                     if settings.raw {
                         read_raw.extend(quote! {
                             if matches!(packet_type, PacketType::Raw) {
-                                let data = &input[pointer - 4..pointer + len];
-                                packets.push(Self::#name(data.to_vec()));
-                                pointer += len;
-                                continue;
+                                return Ok(Self::#name(view.data().to_vec()));
                             }
                         });
                         write.extend(quote! {
-                            Self::#name(data) => Ok(data[4..].to_vec()),
+                            Self::#name(data) => writer
+                                .write_all(data.get(4..).unwrap_or_default())
+                                .map_err(|error| PacketError::FieldError {
+                                    packet_name: stringify!(#name),
+                                    field_name: "data",
+                                    error,
+                                }),
+                        });
+                        len.extend(quote! {
+                            Self::#name(data) => data.len().saturating_sub(4),
+                        });
+                        header.extend(quote! {
+                            Self::#name(data) => PacketView::new(data, packet_type)
+                                .ok()
+                                .map(|view| view.header().clone()),
                         });
                         continue;
                     }
@@ -196,9 +325,16 @@ fn parse_enum_field(out_code: &mut OutputCode, data: &DataEnum) -> syn::Result<(
                         ));
                     }
                     let struct_field = path.get_ident().unwrap();
-                    push_string = quote! {packets.push(Self::#name(#struct_field::read(&mut buf_tmp, flags, packet_type)?))};
+                    push_string = quote! {Self::#name(#struct_field::read_limited(buf_tmp, flags, packet_type, ctx)?)};
+                    schema = quote! {<#struct_field as PacketReadWrite>::schema()};
                     write.extend(quote! {
-                        Self::#name(packet) => packet.write(packet_type),
+                        Self::#name(packet) => packet.write_versioned(writer, version).map(|_| ()),
+                    });
+                    len.extend(quote! {
+                        Self::#name(packet) => packet.serialized_len(packet_type),
+                    });
+                    header.extend(quote! {
+                        Self::#name(packet) => Some(PacketHeader::new(#id, #subid, packet.flags())),
                     });
                     category.extend(quote! {
                         Self::#name(_) => {#category_stream},
@@ -207,47 +343,59 @@ fn parse_enum_field(out_code: &mut OutputCode, data: &DataEnum) -> syn::Result<(
 
                 if settings.unknown {
                     push_string = quote! {
-                        packets.push(Self::#name({
-                            let mut data = vec![];
-                            buf_tmp.read_to_end(&mut data).map_err(|e| PacketError::FieldError{
-                                packet_name: packet_name,
-                                field_name: stringify!(#name),
-                                error: e
-                            })?;
-                            (header, data)
-                        }));
+                        Self::#name((header.clone(), view.body().to_vec()))
                     };
                     write.extend(quote! {
-                        Self::#name((header, data)) => {
-                            let mut out_data = header.write(packet_type);
-                            out_data.extend_from_slice(&data);
-                            Ok(out_data)
-                        }
+                        Self::#name((header, data)) => header.write_into(writer, packet_type).and_then(|_| {
+                            writer.write_all(data).map_err(|error| PacketError::FieldError {
+                                packet_name: stringify!(#name),
+                                field_name: "data",
+                                error,
+                            })
+                        }),
+                    });
+                    len.extend(quote! {
+                        Self::#name((_, data)) => 4 + data.len(),
+                    });
+                    header.extend(quote! {
+                        Self::#name((header, _)) => Some(header.clone()),
                     });
                 }
             }
             Fields::Unit => {
-                push_string = quote! {packets.push(Self::#name)};
+                push_string = quote! {Self::#name};
                 if settings.raw {
                     read_raw.extend(quote! {
                         if matches!(packet_type, PacketType::Raw) {
-                            packets.push(Self::#name);
-                            pointer += len;
-                            continue;
+                            return Ok(Self::#name);
                         }
                     });
                     write.extend(quote! {
-                        Self::#name => Ok(vec![]),
+                        Self::#name => Ok(()),
+                    });
+                    len.extend(quote! {
+                        Self::#name => 0,
                     });
                     continue;
                 }
                 if settings.unknown {
                     write.extend(quote! {
-                        Self::#name => Ok(vec![]),
+                        Self::#name => Ok(()),
+                    });
+                    len.extend(quote! {
+                        Self::#name => 0,
                     });
                 } else {
                     write.extend(quote! {
-                        Self::#name => Ok(PacketHeader::new(#id, #subid, Flags::default()).write(packet_type)),
+                        Self::#name => PacketHeader::new(#id, #subid, Flags::default()).write_into(writer, packet_type),
+                    });
+                    len.extend(quote! {
+                        Self::#name => 4,
+                    });
+                }
+                if !settings.unknown && !matches!(settings.packet_type, PacketType::Empty) {
+                    header.extend(quote! {
+                        Self::#name => Some(PacketHeader::new(#id, #subid, Flags::default())),
                     });
                 }
                 category.extend(quote! {
@@ -256,26 +404,82 @@ fn parse_enum_field(out_code: &mut OutputCode, data: &DataEnum) -> syn::Result<(
             }
             _ => {}
         }
-        match settings.packet_type {
-            PacketType::Both => read.extend(quote! {
-                (#id, #subid, _) => {#push_string},
-            }),
-            PacketType::Classic => read.extend(quote! {
-                (#id, #subid, PacketType::Classic | PacketType::NA | PacketType::JP | PacketType::Vita) => {#push_string},
-            }),
-            PacketType::Na => read.extend(quote! {
-                (#id, #subid, PacketType::NA) => {#push_string},
-            }),
-            PacketType::Jp => read.extend(quote! {
-                (#id, #subid, PacketType::JP) => {#push_string},
-            }),
-            PacketType::Vita => read.extend(quote! {
-                (#id, #subid, PacketType::Vita) => {#push_string},
-            }),
-            PacketType::Ngs => read.extend(quote! {
-                (#id, #subid, PacketType::NGS) => {#push_string},
-            }),
-            PacketType::Empty => {}
+        let since = settings
+            .since
+            .as_ref()
+            .map(|since| quote! {build >= (#since)});
+        let until = settings
+            .until
+            .as_ref()
+            .map(|until| quote! {build < (#until)});
+        let guard = match (&since, &until) {
+            (None, None) => quote! {},
+            (Some(since), None) => quote! {if #since},
+            (None, Some(until)) => quote! {if #until},
+            (Some(since), Some(until)) => quote! {if #since && #until},
+        };
+        let pattern = match settings.packet_type {
+            PacketType::Both => quote! {(#id, #subid, _)},
+            PacketType::Classic => quote! {
+                (#id, #subid, PacketType::Classic | PacketType::NA | PacketType::JP | PacketType::Vita)
+            },
+            PacketType::Na => quote! {(#id, #subid, PacketType::NA)},
+            PacketType::Jp => quote! {(#id, #subid, PacketType::JP)},
+            PacketType::Vita => quote! {(#id, #subid, PacketType::Vita)},
+            PacketType::Ngs => quote! {(#id, #subid, PacketType::NGS)},
+            PacketType::Empty => continue,
+        };
+        read.extend(quote! {
+            #pattern #guard => {#push_string},
+        });
+        if !settings.unknown {
+            category_for.extend(quote! {
+                #pattern => {#category_stream},
+            });
+            name_for.extend(quote! {
+                #pattern => Some(stringify!(#name)),
+            });
+            let packet_types = match settings.packet_type {
+                PacketType::Both => quote! {
+                    PacketType::NGS,
+                    PacketType::Classic,
+                    PacketType::NA,
+                    PacketType::JP,
+                    PacketType::Vita,
+                },
+                PacketType::Classic => quote! {
+                    PacketType::Classic,
+                    PacketType::NA,
+                    PacketType::JP,
+                    PacketType::Vita,
+                },
+                PacketType::Na => quote! {PacketType::NA},
+                PacketType::Jp => quote! {PacketType::JP},
+                PacketType::Vita => quote! {PacketType::Vita},
+                PacketType::Ngs => quote! {PacketType::NGS},
+                PacketType::Empty => unreachable!(),
+            };
+            supported.extend(quote! {
+                Self::#name { .. } => [#packet_types].contains(&packet_type),
+            });
+            let since_value = match &settings.since {
+                Some(since) => quote! {Some(#since)},
+                None => quote! {None},
+            };
+            let until_value = match &settings.until {
+                Some(until) => quote! {Some(#until)},
+                None => quote! {None},
+            };
+            registry.extend(quote! {PacketEntry {
+                name: stringify!(#name),
+                id: #id,
+                subid: #subid,
+                packet_types: vec![#packet_types],
+                since: #since_value,
+                until: #until_value,
+                category: #category_stream,
+                schema: #schema,
+            },});
         }
     }
     Ok(())
@@ -315,6 +519,24 @@ fn get_attrs(
             set.id = attrs.fields[0].base10_parse()?;
             set.subid = attrs.fields[1].base10_parse()?;
         }
+        "Since" => {
+            let Some(attrs) = list.map(|l| &l.tokens) else {
+                return Err(syn::Error::new(
+                    span,
+                    "Invalid syntax \nPerhaps you ment Since(..)?",
+                ));
+            };
+            set.since = Some(attrs.clone());
+        }
+        "Until" => {
+            let Some(attrs) = list.map(|l| &l.tokens) else {
+                return Err(syn::Error::new(
+                    span,
+                    "Invalid syntax \nPerhaps you ment Until(..)?",
+                ));
+            };
+            set.until = Some(attrs.clone());
+        }
         "Category" => {
             let Some(attrs) = list.map(|l| &l.tokens) else {
                 return Err(syn::Error::new(
@@ -338,6 +560,8 @@ struct Settings {
     unknown: bool,
     skip: bool,
     category: TS2,
+    since: Option<TS2>,
+    until: Option<TS2>,
 }
 
 #[derive(Default)]
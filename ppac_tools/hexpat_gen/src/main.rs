@@ -0,0 +1,444 @@
+use pso2packetlib::protocol::{
+    FieldCondition, FieldSchema, FlagSchema, Flags, HelperReadWrite, Packet, PacketSchema,
+    PacketType, ProtocolRW, TypeDesc, TypeKind, TypeSchema,
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    env,
+    fmt::Write,
+    fs,
+};
+
+const TEMPLATE: &str = include_str!("template.hexpat");
+
+/// Names used by the template.
+const RESERVED: &[&str] = &[
+    "VarString",
+    "VarAsciiString",
+    "MagicBytes",
+    "MagicBytesNoPad",
+    "MagicVec",
+    "FixedArray",
+    "SizedVec",
+    "Ipv4",
+    "HeaderNGS",
+    "HeaderClassic",
+    "Packet",
+    "Client",
+    "Header",
+    "flags",
+    "Packet_header_v1",
+    "Packet_header_v2",
+    "Packet_v1",
+    "Packet_v2",
+    "file",
+];
+
+/// Pattern language keywords that can appear as field names.
+const KEYWORDS: &[&str] = &[
+    "padding", "parent", "this", "str", "in", "out", "ref", "auto",
+];
+
+/// PPAC client byte of the packet types.
+const CLIENTS: &[(PacketType, u8)] = &[
+    (PacketType::Classic, 0),
+    (PacketType::NGS, 1),
+    (PacketType::NA, 2),
+    (PacketType::JP, 3),
+    (PacketType::Vita, 4),
+];
+
+fn main() {
+    let mut args = env::args();
+    args.next();
+    let out_path = args.next().unwrap_or_else(|| "packets.hexpat".to_string());
+    let mut generator = Generator::default();
+    let pattern = generator.generate();
+    fs::write(&out_path, pattern).unwrap();
+    println!("Wrote {out_path}");
+    let mut unknown: Vec<_> = generator.unknown.iter().collect();
+    unknown.sort_unstable();
+    for unknown in unknown {
+        println!("Undecodable type: {unknown}");
+    }
+}
+
+/// Pattern type of a field.
+struct Desc {
+    ty: String,
+    /// Array length (if the field is an array).
+    array: Option<usize>,
+    /// Padding after the field.
+    padding: u64,
+    attrs: &'static str,
+}
+
+/// Pattern type of a helper type.
+struct GeneratedType {
+    name: String,
+    /// Type contains a field that can't be described, so it consumes the rest of the packet.
+    incomplete: bool,
+}
+
+#[derive(Default)]
+struct Generator {
+    types: String,
+    type_ids: HashMap<*const TypeSchema, GeneratedType>,
+    packets: String,
+    packet_ids: HashMap<*const PacketSchema, String>,
+    names: HashSet<String>,
+    unknown: HashSet<String>,
+}
+
+impl Generator {
+    fn generate(&mut self) -> String {
+        self.names.extend(RESERVED.iter().map(|s| s.to_string()));
+        // header flags are always needed
+        if let Some(schema) = Flags::schema() {
+            self.type_id(schema);
+        }
+
+        // arms by client byte
+        let mut arms: BTreeMap<u8, Vec<String>> = BTreeMap::new();
+        for entry in Packet::registry().entries() {
+            let Some(schema) = entry.schema else {
+                continue;
+            };
+            let name = self.packet_id(schema);
+            for &(_, client) in CLIENTS
+                .iter()
+                .filter(|(t, _)| entry.packet_types.contains(t))
+            {
+                arms.entry(client).or_default().push(format!(
+                    "            (0x{:02X}, 0x{:02X}): {name} body;",
+                    entry.id, entry.subid
+                ));
+            }
+        }
+        // group packet types with the same packets
+        let mut groups: Vec<(Vec<u8>, Vec<String>)> = vec![];
+        for (client, arms) in arms {
+            match groups.iter_mut().find(|(_, a)| *a == arms) {
+                Some((clients, _)) => clients.push(client),
+                None => groups.push((vec![client], arms)),
+            }
+        }
+        let mut dispatch = String::new();
+        for (i, (clients, arms)) in groups.iter().enumerate() {
+            let condition = clients
+                .iter()
+                .map(|c| format!("packet_type == {c}"))
+                .collect::<Vec<_>>()
+                .join(" || ");
+            if i != 0 {
+                dispatch.push_str(" else ");
+            } else {
+                dispatch.push_str("    ");
+            }
+            writeln!(dispatch, "if ({condition}) {{").unwrap();
+            writeln!(dispatch, "        match (header.id, header.subid) {{").unwrap();
+            for arm in arms {
+                writeln!(dispatch, "{arm}").unwrap();
+            }
+            dispatch.push_str("        }\n    }");
+        }
+
+        TEMPLATE
+            .replace("@VERSION@", env!("CARGO_PKG_VERSION"))
+            .replace("// @TYPES@", self.types.trim_end())
+            .replace("// @PACKETS@", self.packets.trim_end())
+            .replace("    // @DISPATCH@", &dispatch)
+    }
+
+    fn unique_name(&mut self, name: &str) -> String {
+        let mut unique = name.to_string();
+        let mut i = 2;
+        while self.names.contains(&unique) {
+            unique = format!("{name}_{i}");
+            i += 1;
+        }
+        self.names.insert(unique.clone());
+        unique
+    }
+
+    fn packet_id(&mut self, schema: &'static PacketSchema) -> String {
+        if let Some(name) = self.packet_ids.get(&(schema as *const _)) {
+            return name.clone();
+        }
+        let name = self.unique_name(schema.name);
+        let mut body = String::new();
+        if let Some((xor, sub)) = schema.magic {
+            writeln!(body, "    magic_xor = 0x{xor:X};").unwrap();
+            writeln!(body, "    magic_sub = 0x{sub:X};").unwrap();
+        }
+        self.fields(&schema.fields, &mut body);
+        writeln!(self.packets, "struct {name} {{\n{body}}};\n").unwrap();
+        self.packet_ids.insert(schema, name.clone());
+        name
+    }
+
+    /// Generates the helper type (and its dependencies).
+    fn type_id(&mut self, schema: &'static TypeSchema) -> &GeneratedType {
+        let key = schema as *const _;
+        if !self.type_ids.contains_key(&key) {
+            let generated = self.generate_type(schema);
+            self.type_ids.insert(key, generated);
+        }
+        &self.type_ids[&key]
+    }
+
+    fn generate_type(&mut self, schema: &'static TypeSchema) -> GeneratedType {
+        let mut incomplete = false;
+        let name = self.unique_name(schema.name);
+        let out = match &schema.kind {
+            TypeKind::Struct(fields) => {
+                let mut body = String::new();
+                incomplete = !self.fields(fields, &mut body);
+                format!("struct {name} {{\n{body}}};\n")
+            }
+            TypeKind::Enum { repr, variants } => {
+                let mut body = String::new();
+                let mut seen = vec![];
+                for variant in variants {
+                    if seen.contains(&variant.value) {
+                        continue;
+                    }
+                    seen.push(variant.value);
+                    writeln!(body, "    {} = {},", variant.name, variant.value).unwrap();
+                }
+                format!("enum {name} : {} {{\n{body}}};\n", primitive(repr).unwrap())
+            }
+            TypeKind::Flags { repr, flags } | TypeKind::BitFlags { repr, flags } => {
+                match bitfield(repr, flags) {
+                    Some(body) => format!("bitfield {name} {{\n{body}}};\n"),
+                    None => format!("using {name} = {};\n", primitive(repr).unwrap()),
+                }
+            }
+        };
+        writeln!(self.types, "{out}").unwrap();
+        GeneratedType { name, incomplete }
+    }
+
+    /// Writes the fields, returns `false` if the fields can't be fully described.
+    fn fields(&mut self, fields: &[FieldSchema], out: &mut String) -> bool {
+        for field in fields {
+            let name = field_name(field.name);
+            let mut lines = vec![];
+            if field.seek > 0 {
+                lines.push(format!("padding[{}];", field.seek));
+            } else if field.seek < 0 {
+                lines.push(format!("$ = $ - {};", -field.seek));
+            }
+            if field.const_u16.is_some() {
+                lines.push("padding[2];".to_string());
+            }
+            let desc = match field.manual_rw {
+                Some((read, _)) => {
+                    self.unknown
+                        .insert(format!("{} (read by {read})", field.type_name));
+                    None
+                }
+                None => {
+                    let desc = self.type_desc(&(field.ty)(), true);
+                    if desc.is_none() {
+                        self.unknown.insert(field.type_name.to_string());
+                    }
+                    desc
+                }
+            };
+            let complete = match desc {
+                Some((desc, complete)) => {
+                    let array = desc.array.map(|n| format!("[{n}]")).unwrap_or_default();
+                    lines.push(format!("{} {name}{array}{};", desc.ty, desc.attrs));
+                    if desc.padding != 0 {
+                        lines.push(format!("padding[{}];", desc.padding));
+                    }
+                    complete
+                }
+                None => {
+                    lines.push(format!(
+                        "u8 {name}[packet_end - $] [[comment(\"{} is not supported\")]];",
+                        field.type_name
+                    ));
+                    false
+                }
+            };
+            if complete && field.seek_after > 0 {
+                lines.push(format!("padding[{}];", field.seek_after));
+            } else if complete && field.seek_after < 0 {
+                lines.push(format!("$ = $ - {};", -field.seek_after));
+            }
+
+            let condition = match &field.condition {
+                FieldCondition::Always => None,
+                FieldCondition::OnlyOn(types) => Some(client_condition(types, "==", " || ")),
+                FieldCondition::NotOn(types) => Some(client_condition(types, "!=", " && ")),
+            };
+            match condition {
+                Some(condition) => {
+                    writeln!(out, "    if ({condition}) {{").unwrap();
+                    for line in lines {
+                        writeln!(out, "        {line}").unwrap();
+                    }
+                    writeln!(out, "    }}").unwrap();
+                }
+                None => {
+                    for line in lines {
+                        writeln!(out, "    {line}").unwrap();
+                    }
+                }
+            }
+            if !complete {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Converts a type encoding into a pattern type. Returns `None` if the type can't be
+    /// described. The second value is `false` if the type consumes the rest of the packet.
+    fn type_desc(&mut self, ty: &TypeDesc, is_field: bool) -> Option<(Desc, bool)> {
+        let desc = |ty: String| Desc {
+            ty,
+            array: None,
+            padding: 0,
+            attrs: "",
+        };
+        // containers can't hold types of unknown size
+        let element = |generator: &mut Self, ty: &TypeDesc| match generator.type_desc(ty, false) {
+            Some((desc, true)) => Some(desc.ty),
+            Some((_, false)) => None,
+            None => None,
+        };
+        let array = |elem: String, len: usize| {
+            if is_field {
+                Desc {
+                    ty: elem,
+                    array: Some(len),
+                    padding: 0,
+                    attrs: "",
+                }
+            } else {
+                desc(format!("FixedArray<{elem}, {len}>"))
+            }
+        };
+
+        let out = match ty {
+            TypeDesc::Primitive(name) => desc(primitive(name)?.to_string()),
+            TypeDesc::String => desc("VarString".to_string()),
+            TypeDesc::AsciiString => desc("VarAsciiString".to_string()),
+            TypeDesc::Duration => desc("u32".to_string()),
+            TypeDesc::Ipv4Addr => desc("Ipv4".to_string()),
+            TypeDesc::WinTime => Desc {
+                attrs: " [[format(\"format_wintime\")]]",
+                ..desc("u64".to_string())
+            },
+            TypeDesc::Bytes { pad: true } => desc("MagicBytes".to_string()),
+            TypeDesc::Bytes { pad: false } => desc("MagicBytesNoPad".to_string()),
+            TypeDesc::Vec(of) => desc(format!("MagicVec<{}>", element(self, of)?)),
+            TypeDesc::VecUSize { size, of } => {
+                let size = primitive(&format!("u{}", size * 8))?;
+                desc(format!("SizedVec<{size}, {}>", element(self, of)?))
+            }
+            TypeDesc::FixedVec { n, of } | TypeDesc::Array { n, of } => {
+                array(element(self, of)?, *n)
+            }
+            TypeDesc::FixedString { n } => array("char16".to_string(), *n),
+            TypeDesc::FixedAsciiString { n } => array("char".to_string(), *n),
+            TypeDesc::FixedBytes { n, pad } => {
+                let padding = match pad {
+                    true => n.next_multiple_of(4) - n,
+                    false => 0,
+                };
+                // padding can't be described inside containers
+                if !is_field && padding != 0 {
+                    return None;
+                }
+                Desc {
+                    padding: padding as u64,
+                    ..array("u8".to_string(), *n)
+                }
+            }
+            TypeDesc::Schema(schema) => {
+                let generated = self.type_id(schema);
+                let complete = !generated.incomplete;
+                return Some((desc(generated.name.clone()), complete));
+            }
+            // only the size of these types is known
+            TypeDesc::Opaque { size: Some(n), .. } => array("u8".to_string(), *n),
+            TypeDesc::Opaque { name, size: None } => {
+                let name = name.rsplit("::").next().unwrap_or(name);
+                self.unknown.insert(name.to_string());
+                return None;
+            }
+            _ => return None,
+        };
+        Some((out, true))
+    }
+}
+
+fn primitive(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "u8" => "u8",
+        "i8" => "s8",
+        "u16" => "u16",
+        "i16" => "s16",
+        "u32" => "u32",
+        "i32" => "s32",
+        "u64" => "u64",
+        "i64" => "s64",
+        "u128" => "u128",
+        "i128" => "s128",
+        "f16" => "type::float16",
+        "f32" => "float",
+        "f64" => "double",
+        _ => return None,
+    })
+}
+
+/// Describes flags as a bitfield if every flag is a single bit.
+fn bitfield(repr: &str, flags: &[FlagSchema]) -> Option<String> {
+    let bits = repr.trim_start_matches(['u', 'i']).parse::<u32>().ok()?;
+    if flags.iter().any(|f| !f.mask.is_power_of_two()) {
+        return None;
+    }
+    let mut flags: Vec<_> = flags
+        .iter()
+        .map(|f| (f.mask.trailing_zeros(), f.name))
+        .collect();
+    flags.sort_unstable();
+    flags.dedup_by_key(|(bit, _)| *bit);
+    let mut body = String::new();
+    let mut next = 0;
+    for (bit, name) in flags {
+        if bit >= bits {
+            return None;
+        }
+        if bit > next {
+            writeln!(body, "    padding : {};", bit - next).unwrap();
+        }
+        writeln!(body, "    {} : 1;", field_name(name)).unwrap();
+        next = bit + 1;
+    }
+    if next < bits {
+        writeln!(body, "    padding : {};", bits - next).unwrap();
+    }
+    Some(body)
+}
+
+fn client_condition(types: &[PacketType], op: &str, join: &str) -> String {
+    CLIENTS
+        .iter()
+        .filter(|(t, _)| types.contains(t))
+        .map(|(_, c)| format!("packet_type {op} {c}"))
+        .collect::<Vec<_>>()
+        .join(join)
+}
+
+fn field_name(name: &str) -> String {
+    if KEYWORDS.contains(&name) || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name.to_string()
+    }
+}
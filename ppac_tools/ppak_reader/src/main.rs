@@ -26,15 +26,21 @@ fn main() {
     let mut ppac = PPACReader::open(File::open(&filename).unwrap()).unwrap();
     ppac.set_out_type(OutputType::Both);
     let mut out_file = File::create(&text).unwrap();
-    while let Ok(Some(PacketData {
-        time,
-        direction,
-        packet,
-        data,
-        parse_error,
-        ..
-    })) = ppac.read()
-    {
+    for packet in ppac {
+        let PacketData {
+            time,
+            direction,
+            packet,
+            data,
+            parse_error,
+            ..
+        } = match packet {
+            Ok(packet) => packet,
+            Err(e) => {
+                eprintln!("Failed to read the file: {e}");
+                break;
+            }
+        };
         let packet = match packet {
             Some(x) => x,
             None => pso2packetlib::protocol::Packet::Raw(data.unwrap()),
@@ -0,0 +1,551 @@
+-- PSO2 packet dissector for Wireshark.
+--
+-- Generated by wireshark_gen --[[VERSION]] from the pso2packetlib packet definitions. Do not edit by
+-- hand, regenerate instead.
+--
+-- Dissects decrypted PSO2 TCP streams and uncompressed PPAC archives (versions 1 to 5). Copy this
+-- file to the Wireshark plugin directory to install it.
+
+local pso2 = Proto("pso2", "Phantasy Star Online 2")
+local pso2_ppac = Proto("pso2_ppac", "PSO2 PPAC record")
+
+local PACKET_TYPES = { "NGS", "Classic", "NA", "JP", "Vita" }
+-- client byte of the PPAC header
+local PPAC_CLIENTS = { [0] = "Classic", [1] = "NGS", [2] = "NA", [3] = "JP", [4] = "Vita" }
+
+pso2.prefs.packet_type = Pref.enum(
+    "Packet type",
+    1,
+    "Packet type of TCP streams",
+    { { 1, "NGS", 1 }, { 2, "Classic", 2 }, { 3, "NA", 3 }, { 4, "JP", 4 }, { 5, "Vita", 5 } },
+    false
+)
+pso2.prefs.ports = Pref.range("Server ports", "12000-12999", "TCP ports of the game servers", 65535)
+
+-- ----------------------------------------------------------------
+-- Bit operations (Lua 5.2 has bit32, LuaJIT has bit, Lua 5.3+ has operators)
+-- ----------------------------------------------------------------
+
+local bxor, band
+if bit32 then
+    bxor, band = bit32.bxor, bit32.band
+elseif bit then
+    bxor = function(a, b) return bit.bxor(a, b) % 4294967296 end
+    band = function(a, b) return bit.band(a, b) % 4294967296 end
+else
+    -- loaded from a string, so that older parsers don't choke on the operators
+    bxor = load("return function(a, b) return (a ~ b) & 0xFFFFFFFF end")()
+    band = load("return function(a, b) return (a & b) & 0xFFFFFFFF end")()
+end
+
+-- ----------------------------------------------------------------
+-- Packet definitions
+-- ----------------------------------------------------------------
+
+-- helper types
+local T = {}
+-- packet structs
+local S = {}
+-- packets by packet type and (id << 16 | subid)
+local packets = {}
+for _, pt in ipairs(PACKET_TYPES) do
+    packets[pt] = {}
+end
+
+local function reg(id, subid, name, category, schema, types)
+    local packet = { name = name, category = category, schema = schema }
+    for _, pt in ipairs(types) do
+        if packets[pt] then
+            packets[pt][id * 65536 + subid] = packet
+        end
+    end
+end
+
+--[[GENERATED]]
+
+-- ----------------------------------------------------------------
+-- Fields
+-- ----------------------------------------------------------------
+
+local f = {
+    length = ProtoField.uint32("pso2.length", "Length", base.DEC),
+    id = ProtoField.uint8("pso2.id", "ID", base.HEX),
+    subid = ProtoField.uint16("pso2.subid", "SubID", base.HEX),
+    flags = ProtoField.uint8("pso2.flags", "Flags", base.HEX),
+    name = ProtoField.string("pso2.name", "Packet"),
+    category = ProtoField.string("pso2.category", "Category"),
+    undecoded = ProtoField.bytes("pso2.undecoded", "Undecoded data"),
+}
+local flag_fields = {}
+for _, flag in ipairs(header_flags) do
+    local field = ProtoField.bool("pso2.flags." .. flag[1]:lower(), flag[1], 8, nil, flag[2])
+    flag_fields[#flag_fields + 1] = field
+end
+local fields = {}
+for _, field in pairs(f) do
+    fields[#fields + 1] = field
+end
+for _, field in ipairs(flag_fields) do
+    fields[#fields + 1] = field
+end
+pso2.fields = fields
+
+local ppac_direction = ProtoField.uint8(
+    "pso2_ppac.direction",
+    "Direction",
+    base.DEC,
+    { [0] = "To server", [1] = "To client" }
+)
+local ppac_client = ProtoField.uint8("pso2_ppac.client", "Client", base.DEC, PPAC_CLIENTS)
+pso2_ppac.fields = { ppac_direction, ppac_client }
+
+-- ----------------------------------------------------------------
+-- Reading
+-- ----------------------------------------------------------------
+
+local function fail(msg)
+    error(msg, 0)
+end
+
+local function take(ctx, n)
+    if n < 0 or ctx.off + n > ctx.len then
+        fail(string.format("not enough data (need %d bytes, %d left)", n, ctx.len - ctx.off))
+    end
+    local range = ctx.tvb(ctx.off, n)
+    ctx.off = ctx.off + n
+    return range
+end
+
+local function skip(ctx, n)
+    if ctx.off + n < 0 or ctx.off + n > ctx.len then
+        fail("padding is out of bounds")
+    end
+    ctx.off = ctx.off + n
+end
+
+local function magic(ctx)
+    local value = take(ctx, 4):le_uint()
+    return (bxor(value, ctx.xor) - ctx.sub) % 4294967296
+end
+
+local function f16(value)
+    local sign = value >= 0x8000 and -1 or 1
+    local exp = math.floor(value / 0x400) % 0x20
+    local mantissa = value % 0x400
+    if exp == 0 then
+        return sign * mantissa * 2 ^ -24
+    elseif exp == 31 then
+        return mantissa == 0 and sign * math.huge or 0 / 0
+    end
+    return sign * (1 + mantissa / 1024) * 2 ^ (exp - 15)
+end
+
+local function hex_le(range)
+    local out = {}
+    for i = range:len() - 1, 0, -1 do
+        out[#out + 1] = string.format("%02X", range(i, 1):uint())
+    end
+    return "0x" .. table.concat(out)
+end
+
+local function strip_nul(str)
+    local pos = str:find("\0", 1, true)
+    return pos and str:sub(1, pos - 1) or str
+end
+
+local PRIMITIVES = {
+    u8 = { 1, function(r) return r:le_uint() end },
+    i8 = { 1, function(r) return r:le_int() end },
+    u16 = { 2, function(r) return r:le_uint() end },
+    i16 = { 2, function(r) return r:le_int() end },
+    u32 = { 4, function(r) return r:le_uint() end },
+    i32 = { 4, function(r) return r:le_int() end },
+    u64 = { 8, function(r) return r:le_uint64() end },
+    i64 = { 8, function(r) return r:le_int64() end },
+    u128 = { 16, hex_le },
+    i128 = { 16, hex_le },
+    f16 = { 2, function(r) return f16(r:le_uint()) end },
+    f32 = { 4, function(r) return r:le_float() end },
+    f64 = { 8, function(r) return r:le_float() end },
+}
+
+local function is_present(field, pt)
+    if field.only then
+        return field.only[pt] == true
+    elseif field.never then
+        return field.never[pt] ~= true
+    end
+    return true
+end
+
+local read_type
+
+local function read_fields(ctx, tree, fields)
+    for _, field in ipairs(fields) do
+        if is_present(field, ctx.pt) then
+            if field.seek then
+                skip(ctx, field.seek)
+            end
+            if field.const then
+                take(ctx, 2)
+            end
+            read_type(ctx, tree, field.n, field.t)
+            if field.after then
+                skip(ctx, field.after)
+            end
+        end
+    end
+end
+
+-- Adds a subtree covering everything read by `read`.
+local function subtree(ctx, tree, label, read)
+    local start = ctx.off
+    local item = tree:add(ctx.tvb(start, 0), label)
+    local ok, err = pcall(read, item)
+    item:set_len(ctx.off - start)
+    if not ok then
+        error(err, 0)
+    end
+    return item
+end
+
+local function read_elements(ctx, tree, count, elem)
+    for i = 0, count - 1 do
+        read_type(ctx, tree, "[" .. i .. "]", elem)
+    end
+end
+
+local function read_variable_string(ctx, tree, name, utf16)
+    local start = ctx.off
+    local len = magic(ctx)
+    local value = ""
+    if len ~= 0 then
+        if utf16 then
+            value = strip_nul(take(ctx, len * 2):le_ustring())
+            skip(ctx, 2 * (len % 2))
+        else
+            value = strip_nul(take(ctx, len):string())
+            skip(ctx, 3 - (len - 1) % 4)
+        end
+    end
+    tree:add(ctx.tvb(start, ctx.off - start), string.format("%s: \"%s\"", name, value))
+end
+
+local function read_ref(ctx, tree, name, ty)
+    if ty.kind == "struct" then
+        subtree(ctx, tree, name .. ": " .. ty.name, function(item)
+            read_fields(ctx, item, ty.fields)
+        end)
+    elseif ty.kind == "enum" then
+        local prim = PRIMITIVES[ty.repr]
+        local range = take(ctx, prim[1])
+        local value = prim[2](range)
+        local variant = ty.values[tonumber(tostring(value))] or ty.default or "Unknown"
+        tree:add(range, string.format("%s: %s (%s)", name, variant, tostring(value)))
+    elseif ty.kind == "flags" then
+        local prim = PRIMITIVES[ty.repr]
+        local range = take(ctx, prim[1])
+        local item = tree:add(range, string.format("%s: %s", name, hex_le(range)))
+        for _, flag in ipairs(ty.flags) do
+            local set = true
+            for i, mask in ipairs(flag[2]) do
+                if mask ~= 0 and band(range(i - 1, 1):uint(), mask) ~= mask then
+                    set = false
+                end
+            end
+            item:add(range, string.format("%s: %s", flag[1], tostring(set)))
+        end
+    end
+end
+
+function read_type(ctx, tree, name, t)
+    if type(t) == "string" then
+        local prim = PRIMITIVES[t]
+        if prim then
+            local range = take(ctx, prim[1])
+            tree:add(range, name .. ": " .. tostring(prim[2](range)))
+        elseif t == "string" then
+            read_variable_string(ctx, tree, name, true)
+        elseif t == "ascii" then
+            read_variable_string(ctx, tree, name, false)
+        elseif t == "wintime" then
+            local range = take(ctx, 8)
+            local ms = range:le_uint64():tonumber() - 11644473600000
+            tree:add(range, name .. ": " .. os.date("!%Y-%m-%d %H:%M:%S UTC", math.floor(ms / 1000)))
+        elseif t == "duration" then
+            local range = take(ctx, 4)
+            tree:add(range, string.format("%s: %d s", name, range:le_uint()))
+        elseif t == "ipv4" then
+            local range = take(ctx, 4)
+            tree:add(range, name .. ": " .. tostring(range:ipv4()))
+        end
+        return
+    end
+
+    if t.k == "ref" then
+        read_ref(ctx, tree, name, T[t.id])
+    elseif t.k == "vec" or t.k == "vecu" then
+        local start = ctx.off
+        local count
+        if t.k == "vec" then
+            count = magic(ctx)
+        else
+            local prim = PRIMITIVES[t.size]
+            count = tonumber(tostring(prim[2](take(ctx, prim[1]))))
+        end
+        if count > ctx.len - ctx.off then
+            ctx.off = start
+            fail(string.format("element count %d is too large", count))
+        end
+        ctx.off = start
+        subtree(ctx, tree, string.format("%s (%d elements)", name, count), function(item)
+            skip(ctx, t.k == "vec" and 4 or PRIMITIVES[t.size][1])
+            local data_start = ctx.off
+            read_elements(ctx, item, count, t.of)
+            if t.k == "vec" then
+                skip(ctx, (4 - (ctx.off - data_start) % 4) % 4)
+            end
+        end)
+    elseif t.k == "array" then
+        subtree(ctx, tree, string.format("%s (%d elements)", name, t.n), function(item)
+            read_elements(ctx, item, t.n, t.of)
+        end)
+    elseif t.k == "fstring" then
+        local range = take(ctx, t.n * 2)
+        tree:add(range, string.format("%s: \"%s\"", name, strip_nul(range:le_ustring())))
+    elseif t.k == "fascii" then
+        local range = take(ctx, t.n)
+        tree:add(range, string.format("%s: \"%s\"", name, strip_nul(range:string())))
+    elseif t.k == "bytes" or t.k == "fbytes" then
+        local start = ctx.off
+        local len = t.n or magic(ctx)
+        local range = take(ctx, len)
+        if t.pad then
+            skip(ctx, (4 - len % 4) % 4)
+        end
+        local value = len > 0 and range:bytes():tohex() or ""
+        tree:add(ctx.tvb(start, ctx.off - start), string.format("%s: %s", name, value))
+    else
+        fail("unsupported type " .. t.name)
+    end
+end
+
+-- ----------------------------------------------------------------
+-- Dissection
+-- ----------------------------------------------------------------
+
+-- Dissects a single packet, returns the packet name.
+local function dissect_packet(tvb, tree, pt)
+    local item = tree:add(pso2, tvb())
+    item:add_le(f.length, tvb(0, 4))
+    local id, subid, flags
+    if pt == "NGS" then
+        flags, id, subid = tvb(4, 1), tvb(5, 1), tvb(6, 2)
+        item:add(f.id, id)
+        item:add_le(f.subid, subid)
+    else
+        id, subid, flags = tvb(4, 1), tvb(5, 1), tvb(6, 1)
+        item:add(f.id, id)
+        item:add(f.subid, subid)
+    end
+    local flags_item = item:add(f.flags, flags)
+    for _, field in ipairs(flag_fields) do
+        flags_item:add(field, flags)
+    end
+
+    local packet = packets[pt][id:uint() * 65536 + subid:le_uint()]
+    local name = packet and packet.name
+        or string.format("Unknown 0x%02X 0x%04X", id:uint(), subid:le_uint())
+    item:append_text(", " .. name)
+    item:add(f.name, name):set_generated()
+    if packet then
+        item:add(f.category, packet.category):set_generated()
+    end
+
+    local ctx = { tvb = tvb, len = tvb:len(), off = 8, pt = pt, xor = 0, sub = 0 }
+    if packet and packet.schema then
+        local schema = packet.schema
+        ctx.xor, ctx.sub = schema.xor, schema.sub
+        local ok, err = pcall(read_fields, ctx, item, schema.fields)
+        if not ok then
+            item:add_expert_info(PI_MALFORMED, PI_WARN, "Decoding stopped: " .. tostring(err))
+        end
+    end
+    if ctx.off < ctx.len then
+        item:add(f.undecoded, tvb(ctx.off))
+    end
+    return name
+end
+
+local function server_port(port)
+    for part in tostring(pso2.prefs.ports):gmatch("[^,%s]+") do
+        local first, last = part:match("^(%d+)-(%d+)$")
+        first = tonumber(first or part)
+        last = tonumber(last) or first
+        if first and port >= first and port <= last then
+            return true
+        end
+    end
+    return false
+end
+
+local function dissect_pdu(tvb, pinfo, tree)
+    pinfo.cols.protocol = "PSO2"
+    local pt = PACKET_TYPES[pso2.prefs.packet_type] or "NGS"
+    local name = dissect_packet(tvb, tree, pt)
+    -- several packets can be in a single segment
+    if pinfo.private.pso2_names then
+        pinfo.private.pso2_names = pinfo.private.pso2_names .. ", " .. name
+    else
+        pinfo.private.pso2_names = name
+    end
+    local direction = server_port(pinfo.dst_port) and "C->S" or "S->C"
+    pinfo.cols.info = direction .. " " .. pinfo.private.pso2_names
+    return tvb:len()
+end
+
+local function pdu_length(tvb, pinfo, offset)
+    local len = tvb(offset, 4):le_uint()
+    if len < 8 then
+        -- broken stream, consume the rest
+        return tvb:len() - offset
+    end
+    return len
+end
+
+function pso2.dissector(tvb, pinfo, tree)
+    dissect_tcp_pdus(tvb, tree, 4, pdu_length, dissect_pdu)
+    return tvb:len()
+end
+
+local registered_ports
+
+local function register_ports()
+    local tcp = DissectorTable.get("tcp.port")
+    if registered_ports then
+        tcp:remove(registered_ports, pso2)
+    end
+    registered_ports = pso2.prefs.ports
+    tcp:add(registered_ports, pso2)
+end
+
+pso2.prefs_changed = register_ports
+register_ports()
+
+-- ----------------------------------------------------------------
+-- PPAC archives
+-- ----------------------------------------------------------------
+
+-- Records are passed to the dissector with a 2 byte pseudo header: direction and client.
+function pso2_ppac.dissector(tvb, pinfo, tree)
+    pinfo.cols.protocol = "PSO2"
+    local item = tree:add(pso2_ppac, tvb(0, 2))
+    item:add(ppac_direction, tvb(0, 1))
+    item:add(ppac_client, tvb(1, 1))
+    local direction = tvb(0, 1):uint() == 0 and "C->S" or "S->C"
+    local pt = PPAC_CLIENTS[tvb(1, 1):uint()] or "NGS"
+    local names = {}
+    local offset = 2
+    while offset + 8 <= tvb:len() do
+        local len = tvb(offset, 4):le_uint()
+        if len < 8 or offset + len > tvb:len() then
+            len = tvb:len() - offset
+        end
+        names[#names + 1] = dissect_packet(tvb(offset, len):tvb(), tree, pt)
+        offset = offset + len
+    end
+    pinfo.cols.info = direction .. " " .. table.concat(names, ", ")
+    return tvb:len()
+end
+
+DissectorTable.get("wtap_encap"):add(wtap_encaps.USER0, pso2_ppac)
+
+local ppac = FileHandler.new("PSO2 PPAC", "pso2_ppac", "PSO2 packet archive", "rs")
+
+local TSPREC_NSEC = wtap_tsprecs and wtap_tsprecs.NSEC or wtap_filetypes.TSPREC_NSEC
+
+function ppac.read_open(file, capture)
+    if file:read(4) ~= "PPAC" then
+        return false
+    end
+    local version = file:read(1):byte()
+    if version > 5 then
+        return false
+    end
+    local client = 1
+    if version >= 3 then
+        client = file:read(1):byte()
+    end
+    if version >= 5 then
+        -- game build of the packets
+        file:read(4)
+    end
+    if version >= 4 and file:read(1):byte() ~= 0 then
+        -- zstd packed archives should be unpacked with `reppac {archive} false`
+        return false
+    end
+    capture.encap = wtap_encaps.USER0
+    capture.time_precision = TSPREC_NSEC
+    capture.private_table = { version = version, client = client }
+    return true
+end
+
+local function read_record(file, capture, frame)
+    local state = capture.private_table
+    local secs, nsecs
+    if state.version >= 2 then
+        local time = file:read(16)
+        if not time or #time < 16 then
+            return false
+        end
+        local ns = UInt64.decode(time:sub(1, 8), true)
+        secs = (ns / 1000000000):tonumber()
+        nsecs = (ns % 1000000000):tonumber()
+    else
+        local time = file:read(8)
+        if not time or #time < 8 then
+            return false
+        end
+        secs, nsecs = UInt64.decode(time, true):tonumber(), 0
+    end
+    local direction = file:read(1)
+    local len = file:read(8)
+    if not direction or not len or #len < 8 then
+        return false
+    end
+    len = UInt64.decode(len, true):tonumber()
+    local data = file:read(len) or ""
+    if #data < len then
+        return false
+    end
+    frame.rec_type = wtap_rec_types.PACKET
+    frame.flags = wtap_presence_flags.TS + wtap_presence_flags.CAP_LEN
+    frame.time = NSTime(secs, nsecs)
+    frame.captured_length = len + 2
+    frame.original_length = len + 2
+    frame.data = direction .. string.char(state.client) .. data
+    return true
+end
+
+function ppac.read(file, capture, frame)
+    local position = file:seek()
+    if not read_record(file, capture, frame) then
+        return false
+    end
+    return position
+end
+
+function ppac.seek_read(file, capture, frame, offset)
+    file:seek("set", offset)
+    return read_record(file, capture, frame)
+end
+
+function ppac.read_close(file, capture)
+    return true
+end
+
+function ppac.seq_read_close(file, capture)
+    return true
+end
+
+register_filehandler(ppac)
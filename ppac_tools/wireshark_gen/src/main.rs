@@ -0,0 +1,266 @@
+use pso2packetlib::protocol::{
+    FieldCondition, FieldSchema, Flags, HelperReadWrite, Packet, PacketSchema, ProtocolRW,
+    TypeDesc, TypeKind, TypeSchema,
+};
+use std::{collections::HashMap, env, fmt::Write, fs};
+
+const TEMPLATE: &str = include_str!("dissector.lua");
+
+fn main() {
+    let mut args = env::args();
+    args.next();
+    let out_path = args.next().unwrap_or_else(|| "pso2.lua".to_string());
+    let mut generator = Generator::default();
+    let dissector = generator.generate();
+    let unknown = generator.unknown_types();
+    if !unknown.is_empty() {
+        // the dissector can't continue past a field of unknown size
+        for ty in unknown {
+            eprintln!("Undecodable type: {ty}");
+        }
+        eprintln!("Types must provide a schema or a sized type description");
+        std::process::exit(1);
+    }
+    fs::write(&out_path, dissector).unwrap();
+    println!("Wrote {out_path}");
+}
+
+#[derive(Default)]
+struct Generator {
+    /// Generated helper types (filled after the type is first referenced).
+    types: Vec<Option<String>>,
+    type_ids: HashMap<*const TypeSchema, usize>,
+    /// Generated packet structs.
+    packets: Vec<String>,
+    packet_ids: HashMap<*const PacketSchema, usize>,
+    unknown: Vec<String>,
+}
+
+impl Generator {
+    fn generate(&mut self) -> String {
+        let mut registry = String::new();
+        for entry in Packet::registry().entries() {
+            let schema = match entry.schema {
+                Some(schema) => format!("S[{}]", self.packet_id(schema)),
+                None => "nil".to_string(),
+            };
+            let types = entry
+                .packet_types
+                .iter()
+                .map(|t| format!("\"{t:?}\""))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(
+                registry,
+                "reg(0x{:02X}, 0x{:04X}, \"{}\", \"{:?}\", {schema}, {{ {types} }})",
+                entry.id, entry.subid, entry.name, entry.category
+            )
+            .unwrap();
+        }
+
+        let mut header_flags = String::new();
+        if let Some(TypeSchema {
+            kind: TypeKind::BitFlags { flags, .. },
+            ..
+        }) = Flags::schema()
+        {
+            for flag in flags {
+                writeln!(
+                    header_flags,
+                    "    {{ \"{}\", 0x{:02X} }},",
+                    flag.name, flag.mask
+                )
+                .unwrap();
+            }
+        }
+
+        let mut generated = String::new();
+        writeln!(generated, "local header_flags = {{\n{header_flags}}}\n").unwrap();
+        for (id, ty) in self.types.iter().enumerate() {
+            writeln!(generated, "T[{}] = {}", id + 1, ty.as_deref().unwrap()).unwrap();
+        }
+        generated.push('\n');
+        for (id, packet) in self.packets.iter().enumerate() {
+            writeln!(generated, "S[{}] = {packet}", id + 1).unwrap();
+        }
+        generated.push('\n');
+        generated.push_str(&registry);
+
+        TEMPLATE
+            .replace("--[[VERSION]]", env!("CARGO_PKG_VERSION"))
+            .replace("--[[GENERATED]]", generated.trim_end())
+    }
+
+    fn unknown_types(&self) -> Vec<&str> {
+        let mut unknown: Vec<_> = self.unknown.iter().map(|s| s.as_str()).collect();
+        unknown.sort_unstable();
+        unknown.dedup();
+        unknown
+    }
+
+    fn packet_id(&mut self, schema: &'static PacketSchema) -> usize {
+        if let Some(&id) = self.packet_ids.get(&(schema as *const _)) {
+            return id;
+        }
+        let fields = self.fields(&schema.fields, 1);
+        let (xor, sub) = schema.magic.unwrap_or_default();
+        self.packets.push(format!(
+            "{{ name = \"{}\", xor = 0x{xor:X}, sub = 0x{sub:X}, fields = {fields} }}",
+            schema.name
+        ));
+        let id = self.packets.len();
+        self.packet_ids.insert(schema, id);
+        id
+    }
+
+    fn type_id(&mut self, schema: &'static TypeSchema) -> usize {
+        if let Some(&id) = self.type_ids.get(&(schema as *const _)) {
+            return id;
+        }
+        // reserve the slot first in case the type references itself
+        self.types.push(None);
+        let id = self.types.len();
+        self.type_ids.insert(schema, id);
+        let name = schema.name;
+        let ty = match &schema.kind {
+            TypeKind::Struct(fields) => {
+                let fields = self.fields(fields, 1);
+                format!("{{ kind = \"struct\", name = \"{name}\", fields = {fields} }}")
+            }
+            TypeKind::Enum { repr, variants } => {
+                let mut values = String::new();
+                let mut default = "nil".to_string();
+                let mut seen = vec![];
+                for variant in variants {
+                    if variant.is_default {
+                        default = format!("\"{}\"", variant.name);
+                    }
+                    if seen.contains(&variant.value) {
+                        continue;
+                    }
+                    seen.push(variant.value);
+                    write!(values, " [{}] = \"{}\",", variant.value, variant.name).unwrap();
+                }
+                format!(
+                    "{{ kind = \"enum\", name = \"{name}\", repr = \"{repr}\", default = {default}, \
+                    values = {{{values} }} }}"
+                )
+            }
+            TypeKind::Flags { repr, flags } | TypeKind::BitFlags { repr, flags } => {
+                let size = repr_size(repr);
+                let mut list = String::new();
+                for flag in flags {
+                    let mask = (0..size)
+                        .map(|i| format!("0x{:02X}", (flag.mask >> (8 * i)) as u8))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    write!(list, " {{ \"{}\", {{ {mask} }} }},", flag.name).unwrap();
+                }
+                format!(
+                    "{{ kind = \"flags\", name = \"{name}\", repr = \"{repr}\", flags = {{{list} }} }}"
+                )
+            }
+        };
+        self.types[id - 1] = Some(ty);
+        id
+    }
+
+    fn fields(&mut self, fields: &[FieldSchema], depth: usize) -> String {
+        if fields.is_empty() {
+            return "{}".to_string();
+        }
+        let indent = "    ".repeat(depth);
+        let mut out = "{\n".to_string();
+        for field in fields {
+            let ty = match field.manual_rw {
+                Some((read, _)) => self.unknown(&format!("{} (read by {read})", field.type_name)),
+                None => self.type_desc(&(field.ty)()),
+            };
+            write!(out, "{indent}{{ n = \"{}\", t = {ty}", field.name).unwrap();
+            if field.seek != 0 {
+                write!(out, ", seek = {}", field.seek).unwrap();
+            }
+            if field.seek_after != 0 {
+                write!(out, ", after = {}", field.seek_after).unwrap();
+            }
+            if field.const_u16.is_some() {
+                out.push_str(", const = true");
+            }
+            match &field.condition {
+                FieldCondition::Always => {}
+                FieldCondition::OnlyOn(types) => {
+                    write!(out, ", only = {}", type_set(types)).unwrap()
+                }
+                FieldCondition::NotOn(types) => {
+                    write!(out, ", never = {}", type_set(types)).unwrap()
+                }
+            }
+            out.push_str(" },\n");
+        }
+        out.push_str(&"    ".repeat(depth - 1));
+        out.push('}');
+        out
+    }
+
+    /// Converts a type encoding into a Lua type description.
+    fn type_desc(&mut self, desc: &TypeDesc) -> String {
+        match desc {
+            TypeDesc::Primitive(name) => format!("\"{name}\""),
+            TypeDesc::String => "\"string\"".to_string(),
+            TypeDesc::AsciiString => "\"ascii\"".to_string(),
+            TypeDesc::WinTime => "\"wintime\"".to_string(),
+            TypeDesc::Duration => "\"duration\"".to_string(),
+            TypeDesc::Ipv4Addr => "\"ipv4\"".to_string(),
+            TypeDesc::Bytes { pad } => format!("{{ k = \"bytes\", pad = {pad} }}"),
+            TypeDesc::Vec(of) => {
+                let elem = self.type_desc(of);
+                format!("{{ k = \"vec\", of = {elem} }}")
+            }
+            TypeDesc::VecUSize { size, of } => {
+                let elem = self.type_desc(of);
+                format!("{{ k = \"vecu\", size = \"u{}\", of = {elem} }}", size * 8)
+            }
+            TypeDesc::FixedVec { n, of } | TypeDesc::Array { n, of } => {
+                let elem = self.type_desc(of);
+                format!("{{ k = \"array\", n = {n}, of = {elem} }}")
+            }
+            TypeDesc::FixedString { n } => format!("{{ k = \"fstring\", n = {n} }}"),
+            TypeDesc::FixedAsciiString { n } => format!("{{ k = \"fascii\", n = {n} }}"),
+            TypeDesc::FixedBytes { n, pad } => {
+                format!("{{ k = \"fbytes\", n = {n}, pad = {pad} }}")
+            }
+            TypeDesc::Schema(schema) => format!("{{ k = \"ref\", id = {} }}", self.type_id(schema)),
+            TypeDesc::Opaque {
+                size: Some(n),
+                ..
+            } => format!("{{ k = \"fbytes\", n = {n}, pad = false }}"),
+            TypeDesc::Opaque { name, size: None } => self.unknown(short_name(name)),
+            _ => self.unknown(&format!("{desc:?}")),
+        }
+    }
+
+    fn unknown(&mut self, ty: &str) -> String {
+        self.unknown.push(ty.to_string());
+        format!("{{ k = \"unknown\", name = \"{ty}\" }}")
+    }
+}
+
+/// Strips the module path from the type name.
+fn short_name(name: &str) -> &str {
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn repr_size(repr: &str) -> usize {
+    repr.trim_start_matches(['u', 'i'])
+        .parse::<usize>()
+        .map_or(4, |bits| bits / 8)
+}
+
+fn type_set(types: &[pso2packetlib::protocol::PacketType]) -> String {
+    let list = types
+        .iter()
+        .map(|t| format!("{t:?} = true"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{{ {list} }}")
+}
@@ -14,10 +14,13 @@ pub trait StringRW: Sized + Default + std::ops::Deref<Target = str> {
     /// Reads a variable length string from a reader.
     fn read_variable(reader: &mut (impl Read + Seek), sub: u32, xor: u32) -> std::io::Result<Self> {
         let magic_len = read_magic(reader, sub, xor)? as u64;
-        if magic_len == 0 {
+        Self::read_with_len(reader, magic_len)
+    }
+    /// Reads a variable length string from a reader after the length is read.
+    fn read_with_len(reader: &mut (impl Read + Seek), len: u64) -> std::io::Result<Self> {
+        if len == 0 {
             return Ok(Default::default());
         }
-        let len = magic_len;
         let padding = Self::get_padding(len);
         let string = Self::read_fixed(reader, len)?;
         reader.seek(SeekFrom::Current(padding as i64))?;
@@ -31,10 +34,11 @@ pub trait StringRW: Sized + Default + std::ops::Deref<Target = str> {
                 .unwrap();
             return buf;
         }
+        // strings are stored as UTF-16, ascii strings have the same length
         #[cfg(not(test))]
-        let len = self.chars().count() + 1;
+        let len = self.encode_utf16().count() + 1;
         #[cfg(test)]
-        let len = self.chars().count();
+        let len = self.encode_utf16().count();
         let padding = Self::get_padding(len as u64) as usize;
         buf.write_u32::<LittleEndian>(write_magic(len as u32, sub, xor))
             .unwrap();
@@ -51,7 +55,7 @@ impl StringRW for String {
         reader.take(len).read_to_end(&mut buf)?;
         let buf = &buf;
         let mut words = vec![];
-        for word in buf.chunks(2) {
+        for word in buf.chunks_exact(2) {
             words.push(u16::from_le_bytes(word.try_into().unwrap()))
         }
         #[allow(unused_mut)]
@@ -65,13 +69,12 @@ impl StringRW for String {
 
     fn write_fixed(&self, len: usize) -> Vec<u8> {
         let mut buf = vec![];
-        let string = self
-            .chars()
-            .take(len - 1)
-            .chain("\0".chars().cycle())
-            .take(len)
-            .collect::<String>();
-        for word in string.encode_utf16() {
+        let words = self
+            .encode_utf16()
+            .take(len.saturating_sub(1))
+            .chain(std::iter::repeat(0))
+            .take(len);
+        for word in words {
             buf.extend(word.to_le_bytes())
         }
         buf
@@ -185,6 +188,18 @@ impl<'de> serde::Deserialize<'de> for AsciiString {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for AsciiString {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let string = String::arbitrary(u)?;
+        Ok(string
+            .chars()
+            .filter(char::is_ascii)
+            .collect::<String>()
+            .into())
+    }
+}
+
 impl StringRW for AsciiString {
     fn read_fixed(reader: &mut impl Read, len: u64) -> std::io::Result<Self> {
         let mut buf = vec![];
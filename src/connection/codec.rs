@@ -0,0 +1,309 @@
+//! Sans-IO protocol codec.
+
+use super::{
+    conn_impl::ConnectionReader, detect::Detector, ConnectionError, PrivateKey, PublicKey,
+};
+use crate::{
+    crypto::{encrypt, Encryption, Encryptor},
+    protocol::{
+        login::EncryptionRequestPacket, Packet, PacketType, ParseLimits, ProtocolRW,
+        ProtocolVersion,
+    },
+};
+
+/// Protocol state machine that doesn't perform any IO.
+///
+/// Received data is passed to [`Codec::feed`] and decoded packets are returned from
+/// [`Codec::decode`]. Outgoing packets are converted to (possibly encrypted) bytes by
+/// [`Codec::encode`]. Encryption is set up automatically when
+/// [`Packet::EncryptionRequest`] passes through the codec, in the same way as in
+/// [`Connection`](super::Connection).
+///
+/// # Usage
+/// ```
+/// # use pso2packetlib::connection::codec::Codec;
+/// # use pso2packetlib::protocol::{Packet, PacketType};
+/// # use pso2packetlib::{PrivateKey, PublicKey};
+/// # fn main() -> Result<(), pso2packetlib::connection::ConnectionError> {
+/// let mut client = Codec::<Packet>::new(PacketType::NGS, PrivateKey::None, PublicKey::None);
+/// let mut server = Codec::<Packet>::new(PacketType::NGS, PrivateKey::None, PublicKey::None);
+///
+/// let data = client.encode(&Packet::ServerPing)?;
+/// // feed the data in parts
+/// server.feed(&data[..3])?;
+/// assert_eq!(server.decode()?, None);
+/// server.feed(&data[3..])?;
+/// assert_eq!(server.decode()?, Some(Packet::ServerPing));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Codec<P: ProtocolRW> {
+    pub(crate) encryption: Encryption,
+    pub(crate) read: ConnectionReader,
+    pub(crate) read_packets: Vec<P>,
+    pub(crate) write_buffer: Vec<u8>,
+    pub(crate) in_keyfile: PrivateKey,
+    pub(crate) out_keyfile: PublicKey,
+    pub(crate) version: ProtocolVersion,
+    pub(crate) detect: Detector,
+}
+
+impl<P: ProtocolRW> Codec<P> {
+    /// Creates a new codec.
+    /// `in_keyfile` is the RSA key to decrypt encryption request.
+    /// `out_keyfile` is the RSA key to encrypt encryption request.
+    pub fn new(
+        version: impl Into<ProtocolVersion>,
+        in_keyfile: PrivateKey,
+        out_keyfile: PublicKey,
+    ) -> Self {
+        Self {
+            encryption: Encryption::None,
+            read: ConnectionReader::default(),
+            read_packets: Vec::new(),
+            write_buffer: Vec::new(),
+            in_keyfile,
+            out_keyfile,
+            version: version.into(),
+            detect: Detector::default(),
+        }
+    }
+
+    /// Returns the current packet type.
+    pub fn packet_type(&self) -> PacketType {
+        self.version.packet_type
+    }
+
+    /// Changes the packet type.
+    pub fn change_packet_type(&mut self, packet_type: PacketType) {
+        self.version.packet_type = packet_type;
+    }
+
+    /// Returns the current protocol version.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// Changes the protocol version (i.e. the packet type and the game build).
+    pub fn change_version(&mut self, version: ProtocolVersion) {
+        self.version = version;
+    }
+
+    /// Enables or disables packet type detection.
+    ///
+    /// If enabled, the packet type is inferred from the client handshake (i.e. the first
+    /// received packets must be sent by the client). Until the packet type is detected the one
+    /// passed to [`Codec::new`] is used for outgoing packets. See
+    /// [`Connection::set_auto_detect`](super::Connection::set_auto_detect) for details.
+    pub fn set_auto_detect(&mut self, enabled: bool) {
+        self.detect = Detector::new(enabled, self.version.packet_type);
+    }
+
+    /// Returns `true` if the packet type is not yet detected.
+    pub fn is_detecting(&self) -> bool {
+        self.detect.is_detecting()
+    }
+
+    /// Returns the limits for received packets.
+    pub fn limits(&self) -> &ParseLimits {
+        &self.read.limits
+    }
+
+    /// Sets the limits for received packets. Packets exceeding the limits are rejected with
+    /// [`PacketError::LimitError`](crate::protocol::PacketError::LimitError).
+    pub fn set_limits(&mut self, limits: ParseLimits) {
+        self.read.limits = limits;
+    }
+
+    /// Returns the encryption key (for [`Packet::EncryptionResponse`]).
+    pub fn get_key(&self) -> Vec<u8> {
+        self.encryption.get_key()
+    }
+
+    /// Appends received data to the internal buffer.
+    pub fn feed(&mut self, data: &[u8]) -> Result<(), ConnectionError> {
+        self.read.push_data(&mut self.encryption, data)
+    }
+
+    /// Returns `true` if there is no buffered data or undelivered packets.
+    pub fn is_empty(&self) -> bool {
+        self.read.is_empty() && self.read_packets.is_empty()
+    }
+
+    /// Decodes the next packet. Returns [`None`] if more data is required.
+    pub fn decode(&mut self) -> Result<Option<P>, ConnectionError> {
+        if let Some(packet) = self.take_queued() {
+            return Ok(Some(packet));
+        }
+        match self.next_frame()? {
+            Some(data) => self.decode_frame(&data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Extracts the next decrypted packet data without parsing it. Returns [`None`] if more data
+    /// is required.
+    ///
+    /// The returned data must be passed to [`Codec::decode_frame`] for encryption to be set up.
+    pub fn next_frame(&mut self) -> Result<Option<Vec<u8>>, ConnectionError> {
+        self.read.get_packet_data(&mut self.encryption)
+    }
+
+    /// Parses decrypted packet data returned by [`Codec::next_frame`].
+    ///
+    /// If the data contains multiple packets, then the first one is returned and the rest are
+    /// returned by subsequent calls to [`Codec::decode`].
+    pub fn decode_frame(&mut self, data: &[u8]) -> Result<P, ConnectionError> {
+        if let Some(packet_type) = self.detect.detect_frame(data) {
+            self.version.packet_type = packet_type;
+        }
+        let mut packets = P::read_versioned_limited(data, self.version, &self.read.limits)?;
+        if packets.is_empty() {
+            return Err(ConnectionError::PacketError(
+                crate::protocol::PacketError::PacketLengthError {
+                    error: std::io::ErrorKind::UnexpectedEof.into(),
+                },
+            ));
+        }
+        let mut packet = packets.remove(0);
+        self.read_packets.append(&mut packets);
+        if let Some(data) = packet.mut_enc_data() {
+            if !matches!(&self.in_keyfile, PrivateKey::None) {
+                let dec_data = Encryption::decrypt_rsa_data(data, &self.in_keyfile)?;
+                if let Some(packet_type) = self.detect.detect_key(&dec_data) {
+                    self.version.packet_type = packet_type;
+                }
+                self.encryption = Encryption::from_dec_data(
+                    &dec_data,
+                    matches!(self.version.packet_type, PacketType::NGS),
+                )?;
+                *data = dec_data;
+            }
+        }
+        Ok(packet)
+    }
+
+    /// Encodes a packet into bytes ready to be sent.
+    pub fn encode(&mut self, packet: &impl ProtocolRW) -> Result<Vec<u8>, ConnectionError> {
+        self.encode_packet(packet).map(|(_, data)| data)
+    }
+
+    pub(crate) fn take_queued(&mut self) -> Option<P> {
+        if self.read_packets.is_empty() {
+            return None;
+        }
+        Some(self.read_packets.remove(0))
+    }
+
+    /// Returns both the plain and the encrypted packet data.
+    pub(crate) fn encode_packet(
+        &mut self,
+        packet: &impl ProtocolRW,
+    ) -> Result<(&[u8], Vec<u8>), ConnectionError> {
+        self.write_buffer.clear();
+        if packet.is_enc_data() && !matches!(&self.out_keyfile, PublicKey::None) {
+            let rsa_data = packet
+                .as_enc_data()
+                .expect("is_enc_data returned true while as_enc_data returned None");
+            let mut new_packet = EncryptionRequestPacket::default();
+            let enc = Encryption::from_dec_data(
+                rsa_data,
+                matches!(self.version.packet_type, PacketType::NGS),
+            )?;
+            self.encryption = enc;
+            new_packet.rsa_data = encrypt(rsa_data, &self.out_keyfile)?.into();
+            Packet::EncryptionRequest(new_packet)
+                .write_versioned(&mut self.write_buffer, &self.version)?;
+            Ok((&self.write_buffer, self.write_buffer.clone()))
+        } else {
+            packet.write_versioned(&mut self.write_buffer, &self.version)?;
+            let data = self.encryption.encrypt(&self.write_buffer)?;
+            Ok((&self.write_buffer, data))
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<P: ProtocolRW> tokio_util::codec::Decoder for Codec<P> {
+    type Item = P;
+    type Error = ConnectionError;
+
+    fn decode(&mut self, src: &mut bytes::BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if !src.is_empty() {
+            self.feed(&src.split())?;
+        }
+        Codec::decode(self)
+    }
+
+    fn decode_eof(&mut self, src: &mut bytes::BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        match tokio_util::codec::Decoder::decode(self, src)? {
+            Some(packet) => Ok(Some(packet)),
+            None if self.read.is_empty() => Ok(None),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "bytes remaining on stream",
+            )
+            .into()),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<P: ProtocolRW, T: ProtocolRW> tokio_util::codec::Encoder<T> for Codec<P> {
+    type Error = ConnectionError;
+
+    fn encode(&mut self, item: T, dst: &mut bytes::BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&Codec::encode(self, &item)?);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Codec;
+    use crate::{
+        connection::ConnectionError,
+        protocol::{LimitKind, Packet, PacketError, PacketType, ParseLimits, ProtocolRW},
+        PrivateKey, PublicKey,
+    };
+
+    #[test]
+    fn frame_limits() {
+        let new = || Codec::<Packet>::new(PacketType::NGS, PrivateKey::None, PublicKey::None);
+
+        // length smaller than a header
+        let mut codec = new();
+        codec.feed(&[0, 0, 0, 0]).unwrap();
+        assert!(matches!(
+            codec.decode(),
+            Err(ConnectionError::PacketError(
+                PacketError::PacketLengthError { .. }
+            ))
+        ));
+
+        // length is checked before the packet is buffered
+        let mut codec = new();
+        codec.set_limits(ParseLimits {
+            max_packet_size: 0x100,
+            ..Default::default()
+        });
+        codec.feed(&0x101u32.to_le_bytes()).unwrap();
+        assert!(matches!(
+            codec.decode(),
+            Err(ConnectionError::PacketError(PacketError::LimitError {
+                kind: LimitKind::PacketSize,
+                value: 0x101,
+                max: 0x100,
+            }))
+        ));
+
+        let mut codec = new();
+        codec
+            .feed(&Packet::ServerPing.write(PacketType::NGS))
+            .unwrap();
+        assert_eq!(codec.decode().unwrap(), Some(Packet::ServerPing));
+    }
+}
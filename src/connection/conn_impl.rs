@@ -1,86 +1,34 @@
-use crate::encryption::{Decryptor, Encryptor, LengthType};
-use std::io::{Read, Write};
-use super::ConnectionError;
-
-pub trait ConnReadAsync {
-    fn readable_conn(&self) -> impl std::future::Future<Output = std::io::Result<()>> + Send;
-    fn try_read_conn(&mut self, buf: &mut [u8]) -> std::io::Result<usize>;
-}
-pub trait ConnWriteAsync {
-    fn writable_conn(&self) -> impl std::future::Future<Output = std::io::Result<()>> + Send;
-    fn try_write_conn(&mut self, buf: &[u8]) -> std::io::Result<usize>;
-}
-
-impl ConnReadAsync for std::net::TcpStream {
-    async fn readable_conn(&self) -> std::io::Result<()> {
-        unimplemented!()
-    }
-
-    fn try_read_conn(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.read(buf)
-    }
-}
-impl ConnWriteAsync for std::net::TcpStream {
-    async fn writable_conn(&self) -> std::io::Result<()> {
-        unimplemented!()
-    }
-    fn try_write_conn(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.write(buf)
-    }
-}
-#[cfg(feature = "tokio")]
-impl ConnReadAsync for tokio::net::TcpStream {
-    async fn readable_conn(&self) -> std::io::Result<()> {
-        self.readable().await
-    }
-    fn try_read_conn(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.try_read(buf)
-    }
-}
-#[cfg(feature = "tokio")]
-impl ConnWriteAsync for tokio::net::TcpStream {
-    async fn writable_conn(&self) -> std::io::Result<()> {
-        self.writable().await
-    }
-    fn try_write_conn(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.try_write(buf)
-    }
-}
-#[cfg(feature = "tokio")]
-impl ConnReadAsync for tokio::net::tcp::OwnedReadHalf {
-    async fn readable_conn(&self) -> std::io::Result<()> {
-        self.readable().await
-    }
-    fn try_read_conn(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.try_read(buf)
-    }
-}
 #[cfg(feature = "tokio")]
-impl ConnWriteAsync for tokio::net::tcp::OwnedWriteHalf {
-    async fn writable_conn(&self) -> std::io::Result<()> {
-        self.writable().await
-    }
-    fn try_write_conn(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.try_write(buf)
-    }
-}
+use super::transport::{AsyncTransportRead, AsyncTransportWrite};
+use super::transport::{TransportRead, TransportWrite};
+use super::ConnectionError;
+#[cfg(feature = "split_connection")]
+use crate::crypto::Encryptor;
+use crate::crypto::Decryptor;
+use crate::framing::frame_length;
+use crate::protocol::ParseLimits;
+#[cfg(feature = "split_connection")]
+use crate::protocol::{ProtocolRW, ProtocolVersion};
 
 #[derive(Default, Debug)]
 pub struct ConnectionReader {
     read_buffer: Vec<u8>,
     packet_length: usize,
+    pub(crate) limits: ParseLimits,
 }
 
 #[derive(Default, Debug)]
 pub struct ConnectionWriter {
     write_buffer: Vec<u8>,
+    #[cfg(feature = "split_connection")]
+    plain_buffer: Vec<u8>,
 }
 
 impl ConnectionReader {
     /// Reads a packet data from stream.
     pub fn try_read_data(
         &mut self,
-        stream: &mut (impl ConnReadAsync + Send),
+        stream: &mut impl TransportRead,
         dec: &mut impl Decryptor,
     ) -> Result<Vec<u8>, ConnectionError> {
         if !self.read_buffer.is_empty() {
@@ -90,7 +38,10 @@ impl ConnectionReader {
         }
         let mut buf = [0; 4096];
         loop {
-            let read_bytes = stream.try_read_conn(&mut buf)?;
+            let read_bytes = match stream.read_transport(&mut buf)? {
+                0 => return Err(ConnectionError::Io(std::io::ErrorKind::ConnectionAborted.into())),
+                n => n,
+            };
             if let Some(packet) = self.handle_data(dec, &buf[..read_bytes])? {
                 return Ok(packet);
             }
@@ -99,7 +50,7 @@ impl ConnectionReader {
     #[cfg(feature = "tokio")]
     pub async fn read_data_async(
         &mut self,
-        stream: &mut (impl ConnReadAsync + Send),
+        stream: &mut impl AsyncTransportRead,
         dec: &mut (impl Decryptor + Send),
     ) -> Result<Vec<u8>, ConnectionError> {
         if !self.read_buffer.is_empty() {
@@ -107,10 +58,9 @@ impl ConnectionReader {
                 return Ok(packet);
             }
         }
+        let mut buf = [0; 4096];
         loop {
-            stream.readable_conn().await?;
-            let mut buf = [0; 4096];
-            let read_bytes = match stream.try_read_conn(&mut buf) {
+            let read_bytes = match stream.read_transport_async(&mut buf).await {
                 Ok(0) => return Err(ConnectionError::Io(std::io::ErrorKind::ConnectionAborted.into())),
                 Ok(n) => n,
                 Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
@@ -126,18 +76,28 @@ impl ConnectionReader {
         dec: &mut impl Decryptor,
         buf: &[u8],
     ) -> Result<Option<Vec<u8>>, ConnectionError> {
+        self.push_data(dec, buf)?;
+        self.get_packet_data(dec)
+    }
+    /// Appends received data to the buffer.
+    pub fn push_data(&mut self, dec: &mut impl Decryptor, buf: &[u8]) -> Result<(), ConnectionError> {
         if dec.is_rc4() {
             let mut decrypted_stream = dec.decrypt(buf)?;
             self.read_buffer.append(&mut decrypted_stream);
         } else {
             self.read_buffer.extend_from_slice(buf);
         }
-        self.get_packet_data(dec)
+        Ok(())
+    }
+    /// Returns `true` if there is no buffered data.
+    pub fn is_empty(&self) -> bool {
+        self.read_buffer.is_empty()
     }
-    fn get_packet_data(&mut self, dec: &mut impl Decryptor) -> Result<Option<Vec<u8>>, ConnectionError> {
+    /// Extracts a single packet from the buffer.
+    pub fn get_packet_data(&mut self, dec: &mut impl Decryptor) -> Result<Option<Vec<u8>>, ConnectionError> {
         let mut output_data = vec![0u8; 0];
         if self.packet_length == 0 {
-            self.get_length(dec);
+            self.get_length(dec)?;
         }
         if self.read_buffer.len() >= self.packet_length && self.packet_length != 0 {
             output_data.extend(self.read_buffer.drain(..self.packet_length));
@@ -151,48 +111,66 @@ impl ConnectionReader {
         }
         Ok(None)
     }
-    fn get_length(&mut self, dec: &impl Decryptor) {
-        let data = &self.read_buffer;
-        let len_type = dec.get_len_type();
-        let len = match (data.len(), len_type) {
-            (0x48.., LengthType::Aes) => {
-                u32::from_le_bytes(data[0x44..0x48].try_into().unwrap()) as usize
-            }
-            (0x4.., LengthType::Default) => {
-                u32::from_le_bytes(data[0x0..0x4].try_into().unwrap()) as usize
-            }
-            _ => 0,
-        };
-        self.packet_length = len
+    fn get_length(&mut self, dec: &impl Decryptor) -> Result<(), ConnectionError> {
+        let offset = dec.get_len_type().offset();
+        if let Some(len) = frame_length(&self.read_buffer, offset, &self.limits)? {
+            self.packet_length = len;
+        }
+        Ok(())
     }
 }
 
 impl ConnectionWriter {
-    pub fn prepare_data(&mut self, data: &[u8], enc: &mut impl Encryptor) -> Result<(), ConnectionError> {
-        self.write_buffer.extend_from_slice(&enc.encrypt(data)?);
-        Ok(())
+    /// Appends already encrypted data to the buffer.
+    pub fn push_data(&mut self, data: &[u8]) {
+        self.write_buffer.extend_from_slice(data);
     }
-    pub fn flush(&mut self, stream: &mut (impl ConnWriteAsync + Send)) -> std::io::Result<()> {
+    /// Writes and encrypts a packet, returning the plain packet data.
+    #[cfg(feature = "split_connection")]
+    pub fn prepare_packet(
+        &mut self,
+        packet: &impl ProtocolRW,
+        version: &ProtocolVersion,
+        enc: &mut impl Encryptor,
+    ) -> Result<&[u8], ConnectionError> {
+        self.plain_buffer.clear();
+        packet.write_versioned(&mut self.plain_buffer, version)?;
+        self.write_buffer.extend_from_slice(&enc.encrypt(&self.plain_buffer)?);
+        Ok(&self.plain_buffer)
+    }
+    /// Moves all data from `other` to the end of the buffer.
+    #[cfg(feature = "split_connection")]
+    pub fn append(&mut self, other: &mut Self) {
+        self.write_buffer.append(&mut other.write_buffer);
+    }
+    #[cfg(feature = "split_connection")]
+    pub fn is_empty(&self) -> bool {
+        self.write_buffer.is_empty()
+    }
+    pub fn flush(&mut self, stream: &mut impl TransportWrite) -> std::io::Result<()> {
         while !self.write_buffer.is_empty() {
-            let wrote_bytes = stream.try_write_conn(&self.write_buffer)?;
+            let wrote_bytes = stream.write_transport(&self.write_buffer)?;
+            if wrote_bytes == 0 {
+                return Err(std::io::ErrorKind::WriteZero.into());
+            }
             self.write_buffer.drain(..wrote_bytes).count();
         }
-        Ok(())
+        stream.flush_transport()
     }
     #[cfg(feature = "tokio")]
     pub async fn flush_async(
         &mut self,
-        stream: &mut (impl ConnWriteAsync + Send),
+        stream: &mut impl AsyncTransportWrite,
     ) -> std::io::Result<()> {
         while !self.write_buffer.is_empty() {
-            stream.writable_conn().await?;
-            let wrote_bytes = match stream.try_write_conn(&self.write_buffer) {
+            let wrote_bytes = match stream.write_transport_async(&self.write_buffer).await {
+                Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
                 Ok(n) => n,
                 Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => 0,
                 Err(e) => return Err(e),
             };
             self.write_buffer.drain(..wrote_bytes).count();
         }
-        Ok(())
+        stream.flush_transport_async().await
     }
 }
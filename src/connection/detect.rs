@@ -0,0 +1,128 @@
+//! Packet type detection from the client handshake.
+
+use crate::protocol::{PacketType, PacketView};
+
+/// Stage of the packet type detection.
+///
+/// NGS clients are detected by the header layout and Vita clients by the login packet ID or the
+/// encryption key size. NA and JP clients send identical handshakes, so other classic clients
+/// are detected as the region the detector was created with.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) enum Detector {
+    /// Detection is disabled or the packet type is locked in.
+    #[default]
+    Done,
+    /// Waiting for the first packet. Contains the region of classic clients.
+    Header(PacketType),
+    /// Client is a classic one, waiting for the encryption key or the login packet. Packets are
+    /// parsed as [`PacketType::Classic`] until then.
+    Classic(PacketType),
+}
+
+impl Detector {
+    /// Creates a new detector. Classic clients are detected as `packet_type` if it's NA or JP
+    /// and as JP otherwise.
+    pub fn new(enabled: bool, packet_type: PacketType) -> Self {
+        let region = match packet_type {
+            PacketType::NA | PacketType::JP => packet_type,
+            _ => PacketType::JP,
+        };
+        if enabled {
+            Self::Header(region)
+        } else {
+            Self::Done
+        }
+    }
+
+    pub fn is_detecting(&self) -> bool {
+        !matches!(self, Self::Done)
+    }
+
+    /// Checks the received packet data. Returns the packet type to use from now on, which is
+    /// final only if [`Detector::is_detecting`] returns `false`.
+    pub fn detect_frame(&mut self, data: &[u8]) -> Option<PacketType> {
+        match *self {
+            Self::Done => None,
+            Self::Header(region) => {
+                // [len; 4] [flag, id, subid; 2] for NGS and [len; 4] [id, subid, flag, 0] for
+                // classic packets. The first packet must be the encryption request (0x11, 0x0B).
+                let header = data.get(4..8)?;
+                if header[1..] == [0x11, 0x0B, 0x00] {
+                    *self = Self::Done;
+                    Some(PacketType::NGS)
+                } else if header[..2] == [0x11, 0x0B] && header[3] == 0 {
+                    *self = Self::Classic(region);
+                    Some(PacketType::Classic)
+                } else {
+                    // unknown client, keep the current packet type
+                    *self = Self::Done;
+                    None
+                }
+            }
+            Self::Classic(region) => {
+                let view = PacketView::new(data, PacketType::Classic).ok()?;
+                let header = view.header();
+                let packet_type = match (header.id, header.subid) {
+                    // vita login
+                    (0x11, 0x63) => PacketType::Vita,
+                    // sega id login
+                    (0x11, 0x00) => region,
+                    _ => return None,
+                };
+                *self = Self::Done;
+                Some(packet_type)
+            }
+        }
+    }
+
+    /// Checks the decrypted encryption request. Returns the detected packet type.
+    pub fn detect_key(&mut self, dec_data: &[u8]) -> Option<PacketType> {
+        // only the vita client uses RC4 keys
+        if matches!(self, Self::Classic(_)) && dec_data.len() <= 0x30 {
+            *self = Self::Done;
+            return Some(PacketType::Vita);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Detector;
+    use crate::protocol::{
+        login::{SegaIDLoginPacket, VitaLoginPacket},
+        Packet, PacketType, ProtocolRW,
+    };
+
+    #[test]
+    fn detect() {
+        let request = Packet::EncryptionRequest(Default::default());
+
+        let mut detect = Detector::new(true, PacketType::NGS);
+        let data = request.write(PacketType::NGS);
+        assert_eq!(detect.detect_frame(&data), Some(PacketType::NGS));
+        assert!(!detect.is_detecting());
+
+        let mut detect = Detector::new(true, PacketType::NGS);
+        let data = request.write(PacketType::Vita);
+        assert_eq!(detect.detect_frame(&data), Some(PacketType::Classic));
+        let data = Packet::VitaLogin(VitaLoginPacket::default()).write(PacketType::Vita);
+        assert_eq!(detect.detect_frame(&data), Some(PacketType::Vita));
+
+        for (region, detected) in [
+            (PacketType::NGS, PacketType::JP),
+            (PacketType::NA, PacketType::NA),
+        ] {
+            let mut detect = Detector::new(true, region);
+            let data = request.write(PacketType::NA);
+            assert_eq!(detect.detect_frame(&data), Some(PacketType::Classic));
+            // other packets don't finish the detection
+            let data = Packet::ServerPong.write(PacketType::NA);
+            assert_eq!(detect.detect_frame(&data), None);
+            assert!(detect.is_detecting());
+            let data = Packet::SegaIDLogin(SegaIDLoginPacket::default()).write(PacketType::NA);
+            assert_eq!(detect.detect_frame(&data), Some(detected));
+            assert!(!detect.is_detecting());
+        }
+    }
+}
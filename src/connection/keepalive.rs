@@ -0,0 +1,239 @@
+//! Automatic ping handling.
+
+#[cfg(feature = "tokio")]
+use super::transport::AsyncTransportWrite;
+use super::{conn_impl::ConnectionWriter, transport::TransportWrite, ConnectionError};
+use crate::protocol::{
+    login::{ClientPingPacket, ClientPongPacket},
+    Packet, PacketType, PacketView, ProtocolRW,
+};
+use std::time::{Duration, Instant};
+#[cfg(feature = "tokio")]
+use std::{future::Future, pin::Pin};
+
+/// Keepalive policy of a connection. By default everything is disabled.
+///
+/// # Note
+///
+/// Pings and timeouts are only checked when a read function is called. If the transport is in a
+/// blocking mode, then a read timeout should be set on it.
+///
+/// Keepalive packets are recognized by the IDs of [`Packet::ServerPing`], [`Packet::ServerPong`]
+/// and [`Packet::ClientPing`], regardless of the protocol enum of the connection. Replies are
+/// always sent as [`Packet`]s.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Keepalive {
+    /// Automatically answer [`Packet::ServerPing`] and [`Packet::ClientPing`]. Answered pings are
+    /// not returned by read functions.
+    pub answer_pings: bool,
+    /// Interval between [`Packet::ServerPing`]s sent by this side. Should only be set on the
+    /// server side. If set, [`Packet::ServerPong`]s are not returned by read functions.
+    pub ping_interval: Option<Duration>,
+    /// Maximum time without receiving any data before [`ConnectionError::Timeout`] is returned.
+    pub timeout: Option<Duration>,
+}
+
+/// What to do with the received keepalive packet.
+pub(crate) enum KeepaliveAction {
+    /// Answer with the following packet.
+    Reply(Box<Packet>),
+    /// Don't return the packet to the user.
+    Swallow,
+}
+
+#[cfg(feature = "tokio")]
+type AsyncFlushFn<S> = for<'a> fn(
+    &'a mut ConnectionWriter,
+    &'a mut S,
+)
+    -> Pin<Box<dyn Future<Output = std::io::Result<()>> + Send + 'a>>;
+
+/// Functions used by the read functions to send keepalive packets.
+///
+/// Read functions don't require the transport to be writable, so these are recorded by the
+/// functions that do.
+pub(crate) struct KeepaliveFlush<S> {
+    flush: Option<fn(&mut ConnectionWriter, &mut S) -> std::io::Result<()>>,
+    #[cfg(feature = "tokio")]
+    flush_async: Option<AsyncFlushFn<S>>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct KeepaliveState {
+    policy: Keepalive,
+    last_recv: Instant,
+    last_ping: Instant,
+}
+
+impl Default for KeepaliveState {
+    fn default() -> Self {
+        Self::new(Keepalive::default())
+    }
+}
+
+impl KeepaliveState {
+    pub fn new(policy: Keepalive) -> Self {
+        let now = Instant::now();
+        Self {
+            policy,
+            last_recv: now,
+            last_ping: now,
+        }
+    }
+
+    pub fn policy(&self) -> &Keepalive {
+        &self.policy
+    }
+
+    /// Marks that some data was received.
+    pub fn received(&mut self) {
+        self.last_recv = Instant::now();
+    }
+
+    pub fn check_timeout(&self) -> Result<(), ConnectionError> {
+        match self.policy.timeout {
+            Some(timeout) if self.last_recv.elapsed() >= timeout => Err(ConnectionError::Timeout),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns `true` if a ping should be sent now.
+    pub fn ping_due(&mut self) -> bool {
+        match self.policy.ping_interval {
+            Some(interval) if self.last_ping.elapsed() >= interval => {
+                self.last_ping = Instant::now();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the time when the connection will time out.
+    #[cfg(feature = "tokio")]
+    pub fn timeout_deadline(&self) -> Option<Instant> {
+        self.policy.timeout.map(|t| self.last_recv + t)
+    }
+
+    /// Returns the earliest time when either a ping or a timeout check is needed.
+    #[cfg(feature = "tokio")]
+    pub fn deadline(&self) -> Option<Instant> {
+        let timeout = self.timeout_deadline();
+        let ping = self.policy.ping_interval.map(|i| self.last_ping + i);
+        match (timeout, ping) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Checks if the packet data contains a single keepalive packet.
+    pub fn handle_frame(&self, data: &[u8], packet_type: PacketType) -> Option<KeepaliveAction> {
+        if !self.policy.answer_pings && self.policy.ping_interval.is_none() {
+            return None;
+        }
+        let view = PacketView::new(data, packet_type).ok()?;
+        if view.len() != data.len() {
+            return None;
+        }
+        let header = view.header();
+        let is = |packet: Packet| {
+            packet
+                .header(packet_type)
+                .is_some_and(|h| (h.id, h.subid) == (header.id, header.subid))
+        };
+        if self.policy.answer_pings && is(Packet::ServerPing) {
+            Some(KeepaliveAction::Reply(Box::new(Packet::ServerPong)))
+        } else if self.policy.ping_interval.is_some() && is(Packet::ServerPong) {
+            Some(KeepaliveAction::Swallow)
+        } else if self.policy.answer_pings && is(Packet::ClientPing(Default::default())) {
+            let ping: ClientPingPacket = view.parse_as().ok()?;
+            let pong = ClientPongPacket {
+                client_time: ping.time,
+                server_time: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .into(),
+                unk1: 0,
+            };
+            Some(KeepaliveAction::Reply(Box::new(Packet::ClientPong(pong))))
+        } else {
+            None
+        }
+    }
+}
+
+impl<S> Default for KeepaliveFlush<S> {
+    fn default() -> Self {
+        Self {
+            flush: None,
+            #[cfg(feature = "tokio")]
+            flush_async: None,
+        }
+    }
+}
+
+impl<S> std::fmt::Debug for KeepaliveFlush<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut s = f.debug_struct("KeepaliveFlush");
+        s.field("flush", &self.flush.is_some());
+        #[cfg(feature = "tokio")]
+        s.field("flush_async", &self.flush_async.is_some());
+        s.finish()
+    }
+}
+
+impl<S> KeepaliveFlush<S> {
+    /// Records that the transport can be written synchronously.
+    pub fn record(&mut self)
+    where
+        S: TransportWrite,
+    {
+        self.flush = Some(|write: &mut ConnectionWriter, stream: &mut S| write.flush(stream));
+    }
+
+    /// Records that the transport can be written asynchronously.
+    #[cfg(feature = "tokio")]
+    pub fn record_async(&mut self)
+    where
+        S: AsyncTransportWrite,
+    {
+        self.flush_async = Some(flush_boxed::<S>);
+    }
+
+    /// Writes the pending data. If the transport would block (or it can't be written), the data
+    /// is left in the buffer.
+    pub fn flush(&self, write: &mut ConnectionWriter, stream: &mut S) -> std::io::Result<()> {
+        match self.flush {
+            Some(flush) => ignore_would_block(flush(write, stream)),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the pending data. If the transport can't be written, the data is left in the
+    /// buffer.
+    #[cfg(feature = "tokio")]
+    pub async fn flush_async(
+        &self,
+        write: &mut ConnectionWriter,
+        stream: &mut S,
+    ) -> std::io::Result<()> {
+        match self.flush_async {
+            Some(flush) => flush(write, stream).await,
+            None => self.flush(write, stream),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+fn flush_boxed<'a, S: AsyncTransportWrite>(
+    write: &'a mut ConnectionWriter,
+    stream: &'a mut S,
+) -> Pin<Box<dyn Future<Output = std::io::Result<()>> + Send + 'a>> {
+    Box::pin(write.flush_async(stream))
+}
+
+fn ignore_would_block(result: std::io::Result<()>) -> std::io::Result<()> {
+    match result {
+        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(()),
+        r => r,
+    }
+}
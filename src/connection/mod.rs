@@ -1,59 +1,77 @@
 //! Client <-> Server connection handling.
 
-pub use crate::encryption::EncryptionError;
+pub use crate::crypto::EncryptionError;
 
+pub mod codec;
 pub(crate) mod conn_impl;
-#[cfg(feature = "split_connection")]
-use crate::encryption::{DecryptorType, EncryptorType};
+mod detect;
+pub mod keepalive;
+#[cfg(feature = "ppac")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ppac")))]
+pub mod offline;
+pub mod transport;
 #[cfg(feature = "ppac")]
 use crate::ppac::{Direction, PPACWriter};
+use crate::protocol::{Packet, PacketType, ParseLimits, ProtocolRW, ProtocolVersion};
+#[cfg(feature = "split_connection")]
 use crate::{
-    encryption::{encrypt, Encryption},
-    protocol::{login::EncryptionRequestPacket, Packet, PacketType, ProtocolRW},
+    crypto::{encrypt, DecryptorType, Encryption, EncryptorType},
+    protocol::login::EncryptionRequestPacket,
 };
-use conn_impl::{ConnectionReader, ConnectionWriter};
+use codec::Codec;
+#[cfg(feature = "split_connection")]
+use conn_impl::ConnectionReader;
+use conn_impl::ConnectionWriter;
+#[cfg(feature = "split_connection")]
+use detect::Detector;
+pub use keepalive::Keepalive;
+use keepalive::{KeepaliveAction, KeepaliveFlush, KeepaliveState};
 use rsa::{
     pkcs8::{DecodePrivateKey, DecodePublicKey},
     BigUint, RsaPrivateKey, RsaPublicKey,
 };
 #[cfg(all(feature = "split_connection", not(feature = "tokio")))]
 use std::sync::mpsc::{Receiver, Sender};
+#[cfg(feature = "split_connection")]
+use std::sync::Arc;
 #[cfg(all(feature = "split_connection", feature = "ppac"))]
-use std::sync::{Arc, Mutex};
+use std::sync::Mutex;
 #[cfg(all(feature = "split_connection", feature = "tokio"))]
-use tokio::{
-    net::tcp::{OwnedReadHalf, OwnedWriteHalf},
-    sync::mpsc::{UnboundedReceiver as Receiver, UnboundedSender as Sender},
-};
+use tokio::sync::mpsc::{UnboundedReceiver as Receiver, UnboundedSender as Sender};
+#[cfg(feature = "tokio")]
+use transport::{AsyncTransportRead, AsyncTransportWrite};
+#[cfg(feature = "split_connection")]
+use transport::{DefaultReadHalf, DefaultWriteHalf, SplitTransport};
+use transport::{DefaultStream, PeerAddr, TransportRead, TransportWrite};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ConnectionError {
     #[error("packet error occured: {0}")]
     PacketError(#[from] crate::protocol::PacketError),
     #[error("encryption error occured: {0}")]
-    EncryptionError(#[from] crate::encryption::EncryptionError),
+    EncryptionError(#[from] crate::crypto::EncryptionError),
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[cfg(feature = "ppac")]
     #[error("error occured while storing a packet: {0}")]
     PPACError(#[from] crate::ppac::PPACError),
+    #[error("connection timed out")]
+    Timeout,
 }
 
 /// Represents a connection between a client and a server.
+///
+/// `S` is the underlying transport (see [`transport`]). Synchronous functions require `S` to
+/// implement [`TransportRead`]/[`TransportWrite`], asynchronous ones require
+/// [`AsyncTransportRead`](transport::AsyncTransportRead)/
+/// [`AsyncTransportWrite`](transport::AsyncTransportWrite).
 #[derive(Debug)]
-pub struct Connection<P: ProtocolRW + Send> {
-    // this is probably not the best way to do this
-    #[cfg(not(feature = "tokio"))]
-    stream: std::net::TcpStream,
-    #[cfg(feature = "tokio")]
-    stream: tokio::net::TcpStream,
-    encryption: Encryption,
-    read: ConnectionReader,
+pub struct Connection<P: ProtocolRW + Send, S = DefaultStream> {
+    stream: S,
+    codec: Codec<P>,
     write: ConnectionWriter,
-    read_packets: Vec<P>,
-    in_keyfile: PrivateKey,
-    out_keyfile: PublicKey,
-    packet_type: PacketType,
+    keepalive: KeepaliveState,
+    keepalive_flush: KeepaliveFlush<S>,
     #[cfg(feature = "ppac")]
     ppac: Option<PPACWriter<std::fs::File>>,
     #[cfg(feature = "ppac")]
@@ -101,7 +119,7 @@ pub enum PrivateKey {
     Key(RsaPrivateKey),
 }
 
-impl<P: ProtocolRW + Send> Connection<P> {
+impl<P: ProtocolRW + Send> Connection<P, DefaultStream> {
     /// Creates a new connection.
     /// `in_keyfile` is the RSA key to decrypt encryption request.
     /// `out_keyfile` is the RSA key to encrypt encryption request.
@@ -117,7 +135,7 @@ impl<P: ProtocolRW + Send> Connection<P> {
     /// async stream. This function panics if this conversion fails.
     pub fn new(
         stream: std::net::TcpStream,
-        packet_type: PacketType,
+        version: impl Into<ProtocolVersion>,
         in_keyfile: PrivateKey,
         out_keyfile: PublicKey,
     ) -> Self {
@@ -128,20 +146,7 @@ impl<P: ProtocolRW + Send> Connection<P> {
                 .expect("set_nonblocking failed");
             tokio::net::TcpStream::from_std(stream).expect("Failed to make async stream")
         };
-        Self {
-            stream,
-            encryption: Encryption::None,
-            read: ConnectionReader::default(),
-            write: ConnectionWriter::default(),
-            read_packets: Vec::new(),
-            in_keyfile,
-            out_keyfile,
-            packet_type,
-            #[cfg(feature = "ppac")]
-            ppac: None,
-            #[cfg(feature = "ppac")]
-            direction: Direction::ToServer,
-        }
+        Self::with_transport(stream, version, in_keyfile, out_keyfile)
     }
 
     /// Creates a new connection.
@@ -151,19 +156,30 @@ impl<P: ProtocolRW + Send> Connection<P> {
     #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
     pub fn new_async(
         stream: tokio::net::TcpStream,
-        packet_type: PacketType,
+        version: impl Into<ProtocolVersion>,
+        in_keyfile: PrivateKey,
+        out_keyfile: PublicKey,
+    ) -> Self {
+        Self::with_transport(stream, version, in_keyfile, out_keyfile)
+    }
+}
+
+impl<P: ProtocolRW + Send, S> Connection<P, S> {
+    /// Creates a new connection over an arbitrary transport.
+    /// `in_keyfile` is the RSA key to decrypt encryption request.
+    /// `out_keyfile` is the RSA key to encrypt encryption request.
+    pub fn with_transport(
+        stream: S,
+        version: impl Into<ProtocolVersion>,
         in_keyfile: PrivateKey,
         out_keyfile: PublicKey,
     ) -> Self {
         Self {
             stream,
-            encryption: Encryption::None,
-            read: ConnectionReader::default(),
+            codec: Codec::new(version, in_keyfile, out_keyfile),
             write: ConnectionWriter::default(),
-            read_packets: Vec::new(),
-            in_keyfile,
-            out_keyfile,
-            packet_type,
+            keepalive: KeepaliveState::default(),
+            keepalive_flush: KeepaliveFlush::default(),
             #[cfg(feature = "ppac")]
             ppac: None,
             #[cfg(feature = "ppac")]
@@ -171,45 +187,131 @@ impl<P: ProtocolRW + Send> Connection<P> {
         }
     }
 
+    /// Returns a reference to the underlying transport.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Returns a mutable reference to the underlying transport.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+
     /// Returns the ip address of the client.
-    pub fn get_ip(&self) -> std::io::Result<std::net::Ipv4Addr> {
-        let ip = self.stream.peer_addr()?.ip();
-        let ip = match ip {
-            std::net::IpAddr::V4(x) => x,
-            std::net::IpAddr::V6(_) => std::net::Ipv4Addr::UNSPECIFIED,
-        };
-        Ok(ip)
+    pub fn get_ip(&self) -> std::io::Result<std::net::Ipv4Addr>
+    where
+        S: PeerAddr,
+    {
+        get_ip(&self.stream)
+    }
+
+    /// Returns the current keepalive policy.
+    pub fn keepalive(&self) -> &Keepalive {
+        self.keepalive.policy()
+    }
+
+    /// Sets the keepalive policy. See [`Keepalive`] for details.
+    ///
+    /// Keepalive packets are sent by the read functions. Use [`Connection::set_keepalive_async`]
+    /// if the connection is read asynchronously.
+    pub fn set_keepalive(&mut self, policy: Keepalive)
+    where
+        S: TransportWrite,
+    {
+        self.keepalive = KeepaliveState::new(policy);
+        self.keepalive_flush.record();
+    }
+
+    /// Sets the keepalive policy. See [`Keepalive`] for details.
+    ///
+    /// Keepalive packets are sent by the read functions.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn set_keepalive_async(&mut self, policy: Keepalive)
+    where
+        S: AsyncTransportWrite,
+    {
+        self.keepalive = KeepaliveState::new(policy);
+        self.keepalive_flush.record_async();
+    }
+
+    /// Enables or disables packet type detection.
+    ///
+    /// If enabled, the packet type is inferred from the client handshake, so this should only be
+    /// used on the server side. Until the packet type is detected the one passed to the
+    /// constructor is used.
+    ///
+    /// NA and JP clients send identical handshakes, so classic (non-Vita) clients are detected as
+    /// the current packet type if it's [`PacketType::NA`] or [`PacketType::JP`] and as
+    /// [`PacketType::JP`] otherwise.
+    pub fn set_auto_detect(&mut self, enabled: bool) {
+        self.codec.set_auto_detect(enabled);
+    }
+
+    /// Returns the current packet type.
+    pub fn packet_type(&self) -> PacketType {
+        self.codec.packet_type()
+    }
+
+    /// Returns the limits for received packets.
+    pub fn limits(&self) -> &ParseLimits {
+        self.codec.limits()
+    }
+
+    /// Sets the limits for received packets. See [`ParseLimits`] for details.
+    pub fn set_limits(&mut self, limits: ParseLimits) {
+        self.codec.set_limits(limits);
+    }
+
+    /// Returns the current protocol version.
+    pub fn version(&self) -> ProtocolVersion {
+        self.codec.version()
     }
 
     /// Changes connection type.
     pub fn change_packet_type(&mut self, packet_type: PacketType) {
+        self.change_version(ProtocolVersion {
+            packet_type,
+            ..self.codec.version()
+        });
+    }
+
+    /// Changes the protocol version (i.e. the packet type and the game build).
+    pub fn change_version(&mut self, version: ProtocolVersion) {
         #[cfg(feature = "ppac")]
         if let Some(writer) = &mut self.ppac {
-            let _ = writer.change_packet_type(packet_type);
+            let _ = writer.change_version(version);
         }
-        self.packet_type = packet_type;
+        self.codec.change_version(version);
     }
 
     /// Splits the connection into separate read and write components.
+    ///
+    /// The keepalive policy is moved to the read half, but it's only able to send keepalive
+    /// packets after the policy is set again with [`ConnectionRead::set_keepalive`] (or
+    /// [`ConnectionRead::set_keepalive_async`]). Until then, they are sent with the next packet of
+    /// the write half.
     #[cfg(feature = "split_connection")]
     #[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
-    pub fn into_split(self) -> std::io::Result<(ConnectionRead<P>, ConnectionWrite)> {
-        #[cfg(feature = "tokio")]
-        let (read, write) = self.stream.into_split();
-        #[cfg(not(feature = "tokio"))]
-        let (read, write) = (self.stream.try_clone()?, self.stream);
+    #[allow(clippy::type_complexity)]
+    pub fn into_split(
+        self,
+    ) -> std::io::Result<(
+        ConnectionRead<P, S::Read, S::Write>,
+        ConnectionWrite<S::Write>,
+    )>
+    where
+        S: SplitTransport,
+    {
+        let (read, write) = self.stream.split_transport()?;
         #[cfg(feature = "ppac")]
         let ppac = self
             .ppac
             .map(|p| std::sync::Arc::new(std::sync::Mutex::new(p)));
         #[cfg(feature = "tokio")]
-        let ((reader_send, writer_recv), (writer_send, reader_recv)) = (
-            tokio::sync::mpsc::unbounded_channel(),
-            tokio::sync::mpsc::unbounded_channel(),
-        );
+        let (enc_send, enc_recv) = tokio::sync::mpsc::unbounded_channel();
         #[cfg(not(feature = "tokio"))]
-        let ((reader_send, writer_recv), (writer_send, reader_recv)) =
-            (std::sync::mpsc::channel(), std::sync::mpsc::channel());
+        let (enc_send, enc_recv) = std::sync::mpsc::channel();
         #[cfg(feature = "tokio")]
         let ((readpt_send, writept_recv), (writept_send, readpt_recv)) = (
             tokio::sync::mpsc::unbounded_channel(),
@@ -218,29 +320,33 @@ impl<P: ProtocolRW + Send> Connection<P> {
         #[cfg(not(feature = "tokio"))]
         let ((readpt_send, writept_recv), (writept_send, readpt_recv)) =
             (std::sync::mpsc::channel(), std::sync::mpsc::channel());
-        let (enc, dec) = self.encryption.into_split();
+        let codec = self.codec;
+        let (enc, dec) = codec.encryption.into_split();
+        let shared = Arc::new(SharedWrite::new(write, self.write, enc));
         let reader = ConnectionRead {
             stream: read,
-            enc_channel: (reader_send, reader_recv),
+            shared: shared.clone(),
+            enc_channel: enc_recv,
             packettype_channel: (readpt_send, readpt_recv),
+            keepalive: self.keepalive,
+            keepalive_flush: KeepaliveFlush::default(),
             encryption: dec,
-            read: self.read,
-            read_packets: self.read_packets,
-            in_keyfile: self.in_keyfile.clone(),
-            packet_type: self.packet_type,
+            read: codec.read,
+            read_packets: codec.read_packets,
+            in_keyfile: codec.in_keyfile,
+            version: codec.version,
+            detect: codec.detect,
             #[cfg(feature = "ppac")]
             ppac: ppac.clone(),
             #[cfg(feature = "ppac")]
             direction: self.direction,
         };
         let writer = ConnectionWrite {
-            stream: write,
-            enc_channel: (writer_send, writer_recv),
+            shared,
+            enc_channel: enc_send,
             packettype_channel: (writept_send, writept_recv),
-            write: self.write,
-            encryption: enc,
-            out_keyfile: self.out_keyfile,
-            packet_type: self.packet_type,
+            out_keyfile: codec.out_keyfile,
+            version: codec.version,
             #[cfg(feature = "ppac")]
             ppac,
             #[cfg(feature = "ppac")]
@@ -253,61 +359,124 @@ impl<P: ProtocolRW + Send> Connection<P> {
     ///
     /// # Note
     ///
-    /// If `tokio` feature is enabled this function becomes nonblocking
-    pub fn read_packet(&mut self) -> Result<P, ConnectionError> {
-        if !self.read_packets.is_empty() {
-            return Ok(self.read_packets.remove(0));
-        }
-        let data = self
-            .read
-            .try_read_data(&mut self.stream, &mut self.encryption)?;
-        #[cfg(feature = "ppac")]
-        if let Some(writer) = &mut self.ppac {
-            let direction = match self.direction {
-                Direction::ToServer => Direction::ToClient,
-                Direction::ToClient => Direction::ToServer,
+    /// If the transport is in a nonblocking mode then this function is nonblocking.
+    ///
+    /// If a keepalive timeout is set, then [`ConnectionError::Timeout`] is returned when the read
+    /// would block (or times out) and nothing was received for the specified duration.
+    pub fn read_packet(&mut self) -> Result<P, ConnectionError>
+    where
+        S: TransportRead,
+    {
+        loop {
+            if let Some(packet) = self.codec.take_queued() {
+                return Ok(packet);
+            }
+            if self.keepalive.ping_due() {
+                self.prepare_data(&Packet::ServerPing)?;
+                self.keepalive_flush
+                    .flush(&mut self.write, &mut self.stream)?;
+            }
+            let data = match self
+                .codec
+                .read
+                .try_read_data(&mut self.stream, &mut self.codec.encryption)
+            {
+                Ok(data) => data,
+                Err(ConnectionError::Io(e))
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    self.keepalive.check_timeout()?;
+                    return Err(e.into());
+                }
+                Err(e) => return Err(e),
             };
-            writer.write_data(crate::ppac::get_now(), direction, &data)?;
+            match self.handle_data(&data)? {
+                Some(KeepaliveAction::Reply(reply)) => {
+                    self.prepare_data(&*reply)?;
+                    self.keepalive_flush
+                        .flush(&mut self.write, &mut self.stream)?;
+                }
+                Some(KeepaliveAction::Swallow) => {}
+                None => return self.decode_frame(&data),
+            }
         }
-        self.parse_packet(&data)
     }
 
     /// Reads a packet from the stream.
     #[cfg(feature = "tokio")]
     #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
-    pub async fn read_packet_async(&mut self) -> Result<P, ConnectionError> {
-        if !self.read_packets.is_empty() {
-            return Ok(self.read_packets.remove(0));
+    pub async fn read_packet_async(&mut self) -> Result<P, ConnectionError>
+    where
+        S: AsyncTransportRead,
+    {
+        loop {
+            if let Some(packet) = self.codec.take_queued() {
+                return Ok(packet);
+            }
+            if self.keepalive.ping_due() {
+                self.prepare_data(&Packet::ServerPing)?;
+                self.keepalive_flush
+                    .flush_async(&mut self.write, &mut self.stream)
+                    .await?;
+            }
+            let read = self
+                .codec
+                .read
+                .read_data_async(&mut self.stream, &mut self.codec.encryption);
+            let data = match self.keepalive.deadline() {
+                Some(deadline) => tokio::select! {
+                    data = read => data?,
+                    _ = tokio::time::sleep_until(deadline.into()) => {
+                        self.keepalive.check_timeout()?;
+                        continue;
+                    }
+                },
+                None => read.await?,
+            };
+            match self.handle_data(&data)? {
+                Some(KeepaliveAction::Reply(reply)) => {
+                    self.prepare_data(&*reply)?;
+                    self.keepalive_flush
+                        .flush_async(&mut self.write, &mut self.stream)
+                        .await?;
+                }
+                Some(KeepaliveAction::Swallow) => {}
+                None => return self.decode_frame(&data),
+            }
         }
-        let data = self
-            .read
-            .read_data_async(&mut self.stream, &mut self.encryption)
-            .await?;
+    }
+
+    fn decode_frame(&mut self, data: &[u8]) -> Result<P, ConnectionError> {
+        #[cfg(feature = "ppac")]
+        let detecting = self.codec.is_detecting();
+        let packet = self.codec.decode_frame(data);
+        // only the final detected packet type is recorded
+        #[cfg(feature = "ppac")]
+        if detecting && !self.codec.is_detecting() {
+            if let Some(writer) = &mut self.ppac {
+                let _ = writer.change_packet_type(self.codec.packet_type());
+            }
+        }
+        packet
+    }
+
+    /// Logs received data and checks if it needs to be answered by keepalive.
+    fn handle_data(&mut self, data: &[u8]) -> Result<Option<KeepaliveAction>, ConnectionError> {
+        self.keepalive.received();
         #[cfg(feature = "ppac")]
         if let Some(writer) = &mut self.ppac {
             let direction = match self.direction {
                 Direction::ToServer => Direction::ToClient,
                 Direction::ToClient => Direction::ToServer,
             };
-            writer.write_data(crate::ppac::get_now(), direction, &data)?;
+            writer.write_data(crate::ppac::get_now(), direction, data)?;
         }
-        self.parse_packet(&data)
-    }
-    fn parse_packet(&mut self, data: &[u8]) -> Result<P, ConnectionError> {
-        let mut packets = P::read(data, self.packet_type)?;
-        let mut packet = packets.remove(0);
-        self.read_packets.append(&mut packets);
-        if let Some(data) = packet.mut_enc_data() {
-            if !matches!(&self.in_keyfile, PrivateKey::None) {
-                let dec_data = Encryption::decrypt_rsa_data(data, &self.in_keyfile)?;
-                self.encryption = Encryption::from_dec_data(
-                    &dec_data,
-                    matches!(self.packet_type, PacketType::NGS),
-                )?;
-                *data = dec_data;
-            }
-        }
-        Ok(packet)
+        Ok(self
+            .keepalive
+            .handle_frame(data, self.codec.packet_type()))
     }
 
     /// Creates a packet storage file. `direction` is the direction of the `write` side of the
@@ -321,7 +490,7 @@ impl<P: ProtocolRW + Send> Connection<P> {
     ) -> Result<(), ConnectionError> {
         self.ppac = Some(PPACWriter::new(
             std::fs::File::create(path)?,
-            self.packet_type,
+            self.codec.version(),
             true,
         )?);
         self.direction = direction;
@@ -332,8 +501,11 @@ impl<P: ProtocolRW + Send> Connection<P> {
     ///
     /// # Note
     ///
-    /// If `tokio` feature is enabled this function becomes nonblocking
-    pub fn write_packet(&mut self, packet: &impl ProtocolRW) -> Result<(), ConnectionError> {
+    /// If the transport is in a nonblocking mode then this function is nonblocking.
+    pub fn write_packet(&mut self, packet: &impl ProtocolRW) -> Result<(), ConnectionError>
+    where
+        S: TransportWrite,
+    {
         self.prepare_data(packet)?;
         self.write.flush(&mut self.stream)?;
         Ok(())
@@ -345,69 +517,66 @@ impl<P: ProtocolRW + Send> Connection<P> {
     pub async fn write_packet_async(
         &mut self,
         packet: &(impl ProtocolRW + Sync),
-    ) -> Result<(), ConnectionError> {
+    ) -> Result<(), ConnectionError>
+    where
+        S: AsyncTransportWrite,
+    {
         self.prepare_data(packet)?;
         self.write.flush_async(&mut self.stream).await?;
         Ok(())
     }
 
     fn prepare_data(&mut self, packet: &impl ProtocolRW) -> Result<(), ConnectionError> {
-        let _packet = if packet.is_enc_data() && !matches!(&self.out_keyfile, PublicKey::None) {
-            let rsa_data = packet
-                .as_enc_data()
-                .expect("is_enc_data returned true while as_enc_data returned None");
-            let mut new_packet = EncryptionRequestPacket::default();
-            let enc =
-                Encryption::from_dec_data(rsa_data, matches!(self.packet_type, PacketType::NGS))?;
-            self.encryption = enc;
-            new_packet.rsa_data = encrypt(rsa_data, &self.out_keyfile)?.into();
-            let packet = Packet::EncryptionRequest(new_packet).write(self.packet_type);
-            self.write.prepare_data(&packet, &mut Encryption::None)?;
-            packet
-        } else {
-            let packet = packet.write(self.packet_type);
-            self.write.prepare_data(&packet, &mut self.encryption)?;
-            packet
-        };
+        let (_packet, data) = self.codec.encode_packet(packet)?;
+        self.write.push_data(&data);
         #[cfg(feature = "ppac")]
         if let Some(writer) = &mut self.ppac {
-            writer.write_data(crate::ppac::get_now(), self.direction, &_packet)?;
+            writer.write_data(crate::ppac::get_now(), self.direction, _packet)?;
         }
         Ok(())
     }
 
-    /// Returns the encryption key (for [`Packet::EncryptionResponse`]).
+    /// Returns the encryption key (for [`Packet::EncryptionResponse`](crate::protocol::Packet::EncryptionResponse)).
     pub fn get_key(&mut self) -> Vec<u8> {
-        self.encryption.get_key()
+        self.codec.get_key()
     }
     /// Writes all pending packets.
-    pub fn flush(&mut self) -> std::io::Result<()> {
+    pub fn flush(&mut self) -> std::io::Result<()>
+    where
+        S: TransportWrite,
+    {
         self.write.flush(&mut self.stream)
     }
     /// Writes all pending packets.
     #[cfg(feature = "tokio")]
     #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
-    pub async fn flush_async(&mut self) -> std::io::Result<()> {
+    pub async fn flush_async(&mut self) -> std::io::Result<()>
+    where
+        S: AsyncTransportWrite,
+    {
         self.write.flush_async(&mut self.stream).await
     }
 }
 
 /// Represents a reader portion of the connection between a client and a server.
+///
+/// Keepalive packets are sent by the read half (see [`ConnectionRead::set_keepalive`]).
 #[cfg(feature = "split_connection")]
 #[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
 #[derive(Debug)]
-pub struct ConnectionRead<P: ProtocolRW + Send> {
-    #[cfg(not(feature = "tokio"))]
-    stream: std::net::TcpStream,
-    #[cfg(feature = "tokio")]
-    stream: OwnedReadHalf,
-    enc_channel: (Sender<EncryptorType>, Receiver<DecryptorType>),
-    packettype_channel: (Sender<PacketType>, Receiver<PacketType>),
+pub struct ConnectionRead<P: ProtocolRW + Send, R = DefaultReadHalf, W = DefaultWriteHalf> {
+    stream: R,
+    shared: Arc<SharedWrite<W>>,
+    enc_channel: Receiver<DecryptorType>,
+    packettype_channel: (Sender<ProtocolVersion>, Receiver<ProtocolVersion>),
+    keepalive: KeepaliveState,
+    keepalive_flush: KeepaliveFlush<W>,
     read: ConnectionReader,
     encryption: DecryptorType,
     read_packets: Vec<P>,
     in_keyfile: PrivateKey,
-    packet_type: PacketType,
+    version: ProtocolVersion,
+    detect: Detector,
     #[cfg(feature = "ppac")]
     ppac: Option<Arc<Mutex<PPACWriter<std::fs::File>>>>,
     #[cfg(feature = "ppac")]
@@ -415,56 +584,219 @@ pub struct ConnectionRead<P: ProtocolRW + Send> {
 }
 
 /// Represents a writer portion of the connection between a client and a server.
+///
+/// The underlying transport is shared with the read half. If the read half is sending a keepalive
+/// packet asynchronously, synchronous functions return [`std::io::ErrorKind::WouldBlock`].
 #[cfg(feature = "split_connection")]
 #[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
 #[derive(Debug)]
-pub struct ConnectionWrite {
-    #[cfg(not(feature = "tokio"))]
-    stream: std::net::TcpStream,
-    #[cfg(feature = "tokio")]
-    stream: OwnedWriteHalf,
-    enc_channel: (Sender<DecryptorType>, Receiver<EncryptorType>),
-    packettype_channel: (Sender<PacketType>, Receiver<PacketType>),
-    write: ConnectionWriter,
-    encryption: EncryptorType,
+pub struct ConnectionWrite<W = DefaultWriteHalf> {
+    shared: Arc<SharedWrite<W>>,
+    enc_channel: Sender<DecryptorType>,
+    packettype_channel: (Sender<ProtocolVersion>, Receiver<ProtocolVersion>),
     out_keyfile: PublicKey,
-    packet_type: PacketType,
+    version: ProtocolVersion,
     #[cfg(feature = "ppac")]
     ppac: Option<Arc<Mutex<PPACWriter<std::fs::File>>>>,
     #[cfg(feature = "ppac")]
     direction: Direction,
 }
 
+/// Write side of a split connection. Shared between the halves, so that the read half can send
+/// keepalive packets.
+#[cfg(feature = "split_connection")]
+#[derive(Debug)]
+struct SharedWrite<W> {
+    /// Encrypted data not yet moved to the transport buffer. Never locked across an await.
+    pending: std::sync::Mutex<PendingWrite>,
+    #[cfg(not(feature = "tokio"))]
+    transport: std::sync::Mutex<(W, ConnectionWriter)>,
+    #[cfg(feature = "tokio")]
+    transport: tokio::sync::Mutex<(W, ConnectionWriter)>,
+}
+
+#[cfg(feature = "split_connection")]
+#[derive(Debug)]
+struct PendingWrite {
+    encryption: EncryptorType,
+    write: ConnectionWriter,
+}
+
+#[cfg(feature = "split_connection")]
+impl<W> SharedWrite<W> {
+    fn new(stream: W, write: ConnectionWriter, encryption: EncryptorType) -> Self {
+        Self {
+            pending: std::sync::Mutex::new(PendingWrite {
+                encryption,
+                write: ConnectionWriter::default(),
+            }),
+            transport: (stream, write).into(),
+        }
+    }
+
+    fn pending(&self) -> std::sync::MutexGuard<'_, PendingWrite> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    fn lock_transport(
+        &self,
+    ) -> std::io::Result<impl std::ops::DerefMut<Target = (W, ConnectionWriter)> + '_> {
+        Ok(self.transport.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Locks the transport. Returns [`std::io::ErrorKind::WouldBlock`] if the other half is
+    /// writing asynchronously.
+    #[cfg(feature = "tokio")]
+    fn lock_transport(
+        &self,
+    ) -> std::io::Result<impl std::ops::DerefMut<Target = (W, ConnectionWriter)> + '_> {
+        self.transport
+            .try_lock()
+            .map_err(|_| std::io::ErrorKind::WouldBlock.into())
+    }
+
+    /// Writes all queued data using `flush`.
+    fn flush(
+        &self,
+        flush: impl Fn(&mut ConnectionWriter, &mut W) -> std::io::Result<()>,
+    ) -> std::io::Result<()> {
+        let mut transport = self.lock_transport()?;
+        let (stream, write) = &mut *transport;
+        loop {
+            write.append(&mut self.pending().write);
+            flush(write, stream)?;
+            if self.pending().write.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Writes all queued data.
+    #[cfg(feature = "tokio")]
+    async fn flush_async(&self) -> std::io::Result<()>
+    where
+        W: AsyncTransportWrite,
+    {
+        let mut transport = self.transport.lock().await;
+        let (stream, write) = &mut *transport;
+        loop {
+            write.append(&mut self.pending().write);
+            write.flush_async(stream).await?;
+            if self.pending().write.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Writes all queued data using the recorded keepalive functions.
+    #[cfg(feature = "tokio")]
+    async fn flush_keepalive_async(&self, flush: &KeepaliveFlush<W>) -> std::io::Result<()> {
+        let mut transport = self.transport.lock().await;
+        let (stream, write) = &mut *transport;
+        loop {
+            write.append(&mut self.pending().write);
+            flush.flush_async(write, stream).await?;
+            if self.pending().write.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+}
+
 #[cfg(feature = "split_connection")]
 #[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
-impl<P: ProtocolRW + Send> ConnectionRead<P> {
+impl<P: ProtocolRW + Send, R, W> ConnectionRead<P, R, W> {
+    /// Returns a reference to the underlying transport.
+    pub fn get_ref(&self) -> &R {
+        &self.stream
+    }
+
+    /// Returns a mutable reference to the underlying transport.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.stream
+    }
+
     /// Returns the ip address of the client.
-    pub fn get_ip(&self) -> std::io::Result<std::net::Ipv4Addr> {
-        let ip = self.stream.peer_addr()?.ip();
-        let ip = match ip {
-            std::net::IpAddr::V4(x) => x,
-            std::net::IpAddr::V6(_) => std::net::Ipv4Addr::UNSPECIFIED,
-        };
-        Ok(ip)
+    pub fn get_ip(&self) -> std::io::Result<std::net::Ipv4Addr>
+    where
+        R: PeerAddr,
+    {
+        get_ip(&self.stream)
+    }
+
+    /// Returns the current keepalive policy.
+    pub fn keepalive(&self) -> &Keepalive {
+        self.keepalive.policy()
+    }
+
+    /// Sets the keepalive policy. See [`Keepalive`] for details.
+    ///
+    /// Keepalive packets are sent by the read functions through the write half. Use
+    /// [`ConnectionRead::set_keepalive_async`] if the write half is written asynchronously.
+    pub fn set_keepalive(&mut self, policy: Keepalive)
+    where
+        W: TransportWrite,
+    {
+        self.keepalive = KeepaliveState::new(policy);
+        self.keepalive_flush.record();
+    }
+
+    /// Sets the keepalive policy. See [`Keepalive`] for details.
+    ///
+    /// Keepalive packets are sent by the read functions through the write half.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn set_keepalive_async(&mut self, policy: Keepalive)
+    where
+        W: AsyncTransportWrite,
+    {
+        self.keepalive = KeepaliveState::new(policy);
+        self.keepalive_flush.record_async();
+    }
+
+    /// Enables or disables packet type detection. The detected packet type is automatically
+    /// sent to the other side.
+    ///
+    /// If enabled, the packet type is inferred from the client handshake, so this should only be
+    /// used on the server side. See [`Connection::set_auto_detect`] for details.
+    pub fn set_auto_detect(&mut self, enabled: bool) {
+        self.detect = Detector::new(enabled, self.version.packet_type);
+    }
+
+    /// Returns the limits for received packets.
+    pub fn limits(&self) -> &ParseLimits {
+        &self.read.limits
+    }
+
+    /// Sets the limits for received packets. See [`ParseLimits`] for details.
+    pub fn set_limits(&mut self, limits: ParseLimits) {
+        self.read.limits = limits;
+    }
+
+    /// Returns the current protocol version.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
     }
 
     /// Changes connection type. Automatically changes the other side.
     pub fn change_packet_type(&mut self, packet_type: PacketType) {
+        self.change_version(ProtocolVersion {
+            packet_type,
+            ..self.version
+        });
+    }
+
+    /// Changes the protocol version (i.e. the packet type and the game build). Automatically
+    /// changes the other side.
+    pub fn change_version(&mut self, version: ProtocolVersion) {
         #[cfg(feature = "ppac")]
         if let Some(writer) = &mut self.ppac {
             let mut lock = writer.lock().unwrap();
-            let _ = lock.change_packet_type(packet_type);
+            let _ = lock.change_version(version);
         }
-        self.packet_type = packet_type;
-        let _ = self.packettype_channel.0.send(packet_type);
-    }
-
-    /// Same as [`std::net::TcpStream::set_nonblocking`]. Does nothing if `tokio` feature is
-    /// enabled.
-    pub fn set_nonblocking(&self, _nonblocking: bool) -> std::io::Result<()> {
-        #[cfg(not(feature = "tokio"))]
-        self.stream.set_nonblocking(_nonblocking)?;
-        Ok(())
+        self.version = version;
+        let _ = self.packettype_channel.0.send(version);
     }
 
     /// Inserts a packet storage file. `direction` is the direction of the `write` side of the
@@ -488,56 +820,102 @@ impl<P: ProtocolRW + Send> ConnectionRead<P> {
     /// If the encryption was not yet setup (i.e [`Packet::EncryptionResponse`] was not
     /// sent) and the stream is in a blocking mode then this function might not setup
     /// encryption correctly  
-    pub fn read_packet(&mut self) -> Result<P, ConnectionError> {
-        if !self.read_packets.is_empty() {
-            return Ok(self.get_one_packet());
-        }
-        if let Ok(enc) = self.enc_channel.1.try_recv() {
-            self.encryption = enc
-        }
-        let data = self
-            .read
-            .try_read_data(&mut self.stream, &mut self.encryption)?;
-        if let Ok(packet_type) = self.packettype_channel.1.try_recv() {
-            self.packet_type = packet_type
-        }
-        #[cfg(feature = "ppac")]
-        if let Some(writer) = &self.ppac {
-            let direction = match self.direction {
-                Direction::ToServer => Direction::ToClient,
-                Direction::ToClient => Direction::ToServer,
+    pub fn read_packet(&mut self) -> Result<P, ConnectionError>
+    where
+        R: TransportRead,
+    {
+        loop {
+            if !self.read_packets.is_empty() {
+                return Ok(self.get_one_packet());
+            }
+            if self.keepalive.ping_due() {
+                self.send_keepalive(&Packet::ServerPing)?;
+            }
+            if let Ok(enc) = self.enc_channel.try_recv() {
+                self.encryption = enc
+            }
+            let data = match self
+                .read
+                .try_read_data(&mut self.stream, &mut self.encryption)
+            {
+                Ok(data) => data,
+                Err(ConnectionError::Io(e))
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    self.keepalive.check_timeout()?;
+                    return Err(e.into());
+                }
+                Err(e) => return Err(e),
             };
-            let mut lock = writer.lock().unwrap();
-            lock.write_data(crate::ppac::get_now(), direction, &data)?;
+            if let Ok(version) = self.packettype_channel.1.try_recv() {
+                self.version = version
+            }
+            match self.handle_data(&data)? {
+                Some(KeepaliveAction::Reply(reply)) => self.send_keepalive(&reply)?,
+                Some(KeepaliveAction::Swallow) => {}
+                None => return self.parse_packet(&data),
+            }
         }
-        self.parse_packet(&data)
     }
     /// Reads a packet from stream.
     #[cfg(feature = "tokio")]
     #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
-    pub async fn read_packet_async(&mut self) -> Result<P, ConnectionError> {
-        if !self.read_packets.is_empty() {
-            return Ok(self.get_one_packet());
-        }
-        let data = loop {
-            tokio::select! {
-                result = self
-                    .read
-                    .read_data_async(&mut self.stream, &mut self.encryption) =>
-                {
-                    let data = result?;
-                    break data;
+    pub async fn read_packet_async(&mut self) -> Result<P, ConnectionError>
+    where
+        R: AsyncTransportRead,
+    {
+        'read: loop {
+            if !self.read_packets.is_empty() {
+                return Ok(self.get_one_packet());
+            }
+            if self.keepalive.ping_due() {
+                self.send_keepalive_async(&Packet::ServerPing).await?;
+            }
+            let deadline = self.keepalive.deadline();
+            let timeout = async move {
+                match deadline {
+                    Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+                    None => std::future::pending().await,
                 }
+            };
+            tokio::pin!(timeout);
+            let data = loop {
+                tokio::select! {
+                    result = self
+                        .read
+                        .read_data_async(&mut self.stream, &mut self.encryption) =>
+                    {
+                        let data = result?;
+                        break data;
+                    }
 
-                Some(enc) = self.enc_channel.1.recv() => {
-                    self.encryption = enc
-                }
+                    Some(enc) = self.enc_channel.recv() => {
+                        self.encryption = enc
+                    }
 
-                Some(packet_type) = self.packettype_channel.1.recv() => {
-                    self.packet_type = packet_type
+                    Some(version) = self.packettype_channel.1.recv() => {
+                        self.version = version
+                    }
+
+                    _ = &mut timeout => {
+                        self.keepalive.check_timeout()?;
+                        continue 'read;
+                    }
                 }
+            };
+            match self.handle_data(&data)? {
+                Some(KeepaliveAction::Reply(reply)) => self.send_keepalive_async(&reply).await?,
+                Some(KeepaliveAction::Swallow) => {}
+                None => return self.parse_packet(&data),
             }
-        };
+        }
+    }
+    /// Logs received data and checks if it needs to be answered by keepalive.
+    fn handle_data(&mut self, data: &[u8]) -> Result<Option<KeepaliveAction>, ConnectionError> {
+        self.keepalive.received();
         #[cfg(feature = "ppac")]
         if let Some(writer) = &self.ppac {
             let direction = match self.direction {
@@ -545,24 +923,83 @@ impl<P: ProtocolRW + Send> ConnectionRead<P> {
                 Direction::ToClient => Direction::ToServer,
             };
             let mut lock = writer.lock().unwrap();
-            lock.write_data(crate::ppac::get_now(), direction, &data)?;
+            lock.write_data(crate::ppac::get_now(), direction, data)?;
+        }
+        Ok(self
+            .keepalive
+            .handle_frame(data, self.version.packet_type))
+    }
+    /// Queues a keepalive packet in the write half.
+    fn push_keepalive(&mut self, packet: &Packet) -> Result<(), ConnectionError> {
+        let mut pending = self.shared.pending();
+        let pending = &mut *pending;
+        let _data = pending
+            .write
+            .prepare_packet(packet, &self.version, &mut pending.encryption)?;
+        #[cfg(feature = "ppac")]
+        if let Some(writer) = &self.ppac {
+            let mut lock = writer.lock().unwrap();
+            lock.write_data(crate::ppac::get_now(), self.direction, _data)?;
+        }
+        Ok(())
+    }
+    /// Sends a keepalive packet. If the transport is busy, the packet is sent with the next
+    /// packet.
+    fn send_keepalive(&mut self, packet: &Packet) -> Result<(), ConnectionError> {
+        self.push_keepalive(packet)?;
+        match self
+            .shared
+            .flush(|write, stream| self.keepalive_flush.flush(write, stream))
+        {
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(()),
+            r => Ok(r?),
+        }
+    }
+    /// Sends a keepalive packet.
+    #[cfg(feature = "tokio")]
+    async fn send_keepalive_async(&mut self, packet: &Packet) -> Result<(), ConnectionError> {
+        self.push_keepalive(packet)?;
+        self.shared
+            .flush_keepalive_async(&self.keepalive_flush)
+            .await?;
+        Ok(())
+    }
+    /// Switches to the packet type returned by the detector. Only the final one is recorded.
+    fn set_detected(&mut self, packet_type: PacketType) {
+        if self.detect.is_detecting() {
+            self.version.packet_type = packet_type;
+            let _ = self.packettype_channel.0.send(self.version);
+        } else {
+            self.change_packet_type(packet_type);
         }
-        self.parse_packet(&data)
     }
     fn parse_packet(&mut self, data: &[u8]) -> Result<P, ConnectionError> {
-        let mut packets = P::read(data, self.packet_type)?;
+        if let Some(packet_type) = self.detect.detect_frame(data) {
+            self.set_detected(packet_type);
+        }
+        let mut packets = P::read_versioned_limited(data, self.version, &self.read.limits)?;
+        if packets.is_empty() {
+            return Err(ConnectionError::PacketError(
+                crate::protocol::PacketError::PacketLengthError {
+                    error: std::io::ErrorKind::UnexpectedEof.into(),
+                },
+            ));
+        }
         let mut packet = packets.remove(0);
         self.read_packets.append(&mut packets);
         if let Some(data) = packet.mut_enc_data() {
             if !matches!(&self.in_keyfile, PrivateKey::None) {
                 let dec_data = Encryption::decrypt_rsa_data(data, &self.in_keyfile)?;
+                if let Some(packet_type) = self.detect.detect_key(&dec_data) {
+                    self.set_detected(packet_type);
+                }
                 let (enc, dec) = Encryption::from_dec_data(
                     &dec_data,
-                    matches!(self.packet_type, PacketType::NGS),
+                    matches!(self.version.packet_type, PacketType::NGS),
                 )?
                 .into_split();
                 *data = dec_data;
-                let _ = self.enc_channel.0.send(enc);
+                self.shared.pending().encryption = enc;
                 self.encryption = dec;
             }
         }
@@ -572,7 +1009,7 @@ impl<P: ProtocolRW + Send> ConnectionRead<P> {
     /// Returns the encryption key (for [`Packet::EncryptionResponse`]).
     pub fn get_key(&mut self) -> Vec<u8> {
         if matches!(self.encryption, DecryptorType::None) {
-            if let Ok(enc) = self.enc_channel.1.try_recv() {
+            if let Ok(enc) = self.enc_channel.try_recv() {
                 self.encryption = enc
             }
         }
@@ -585,34 +1022,38 @@ impl<P: ProtocolRW + Send> ConnectionRead<P> {
 
 #[cfg(feature = "split_connection")]
 #[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
-impl ConnectionWrite {
+impl<W> ConnectionWrite<W> {
     /// Returns the ip address of the client.
-    pub fn get_ip(&self) -> std::io::Result<std::net::Ipv4Addr> {
-        let ip = self.stream.peer_addr()?.ip();
-        let ip = match ip {
-            std::net::IpAddr::V4(x) => x,
-            std::net::IpAddr::V6(_) => std::net::Ipv4Addr::UNSPECIFIED,
-        };
-        Ok(ip)
+    pub fn get_ip(&self) -> std::io::Result<std::net::Ipv4Addr>
+    where
+        W: PeerAddr,
+    {
+        get_ip(&self.shared.lock_transport()?.0)
+    }
+
+    /// Returns the current protocol version.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
     }
 
     /// Changes connection type. Automatically changes the other side.
     pub fn change_packet_type(&mut self, packet_type: PacketType) {
+        self.change_version(ProtocolVersion {
+            packet_type,
+            ..self.version
+        });
+    }
+
+    /// Changes the protocol version (i.e. the packet type and the game build). Automatically
+    /// changes the other side.
+    pub fn change_version(&mut self, version: ProtocolVersion) {
         #[cfg(feature = "ppac")]
         if let Some(writer) = &mut self.ppac {
             let mut lock = writer.lock().unwrap();
-            let _ = lock.change_packet_type(packet_type);
+            let _ = lock.change_version(version);
         }
-        self.packet_type = packet_type;
-        let _ = self.packettype_channel.0.send(packet_type);
-    }
-
-    /// Same as [`std::net::TcpStream::set_nonblocking`]. Does nothing if `tokio` feature is
-    /// enabled.
-    pub fn set_nonblocking(&self, _nonblocking: bool) -> std::io::Result<()> {
-        #[cfg(not(feature = "tokio"))]
-        self.stream.set_nonblocking(_nonblocking)?;
-        Ok(())
+        self.version = version;
+        let _ = self.packettype_channel.0.send(version);
     }
 
     /// Inserts a packet storage file. `direction` is the direction of the `write` side of the
@@ -633,10 +1074,13 @@ impl ConnectionWrite {
     ///
     /// # Note
     ///
-    /// If `tokio` feature is enabled this function becomes nonblocking
-    pub fn write_packet(&mut self, packet: &impl ProtocolRW) -> Result<(), ConnectionError> {
-        self.prepare_data(packet)?;
-        self.write.flush(&mut self.stream)?;
+    /// If the transport is in a nonblocking mode then this function is nonblocking.
+    pub fn write_packet(&mut self, packet: &impl ProtocolRW) -> Result<(), ConnectionError>
+    where
+        W: TransportWrite,
+    {
+        self.prepare_packet(packet)?;
+        self.flush()?;
         Ok(())
     }
 
@@ -646,43 +1090,48 @@ impl ConnectionWrite {
     pub async fn write_packet_async(
         &mut self,
         packet: &(impl ProtocolRW + Sync),
-    ) -> Result<(), ConnectionError> {
-        self.prepare_data(packet)?;
-        self.write.flush_async(&mut self.stream).await?;
+    ) -> Result<(), ConnectionError>
+    where
+        W: AsyncTransportWrite,
+    {
+        self.prepare_packet(packet)?;
+        self.flush_async().await?;
         Ok(())
     }
-    fn prepare_data(&mut self, packet: &impl ProtocolRW) -> Result<(), ConnectionError> {
-        if matches!(self.encryption, EncryptorType::None) {
-            if let Ok(enc) = self.enc_channel.1.try_recv() {
-                self.encryption = enc
-            }
-        }
-        if let Ok(packet_type) = self.packettype_channel.1.try_recv() {
-            self.packet_type = packet_type
+
+    fn prepare_packet(&mut self, packet: &impl ProtocolRW) -> Result<(), ConnectionError> {
+        if let Ok(version) = self.packettype_channel.1.try_recv() {
+            self.version = version
         }
+        let mut pending = self.shared.pending();
+        let pending = &mut *pending;
         let _packet = if packet.is_enc_data() && !matches!(&self.out_keyfile, PublicKey::None) {
             let rsa_data = packet
                 .as_enc_data()
                 .expect("is_enc_data returned true while as_enc_data returned None");
             let mut new_packet = EncryptionRequestPacket::default();
-            let (enc, dec) =
-                Encryption::from_dec_data(rsa_data, matches!(self.packet_type, PacketType::NGS))?
-                    .into_split();
-            let _ = self.enc_channel.0.send(dec);
-            self.encryption = enc;
+            let (enc, dec) = Encryption::from_dec_data(
+                rsa_data,
+                matches!(self.version.packet_type, PacketType::NGS),
+            )?
+            .into_split();
+            let _ = self.enc_channel.send(dec);
+            pending.encryption = enc;
             new_packet.rsa_data = encrypt(rsa_data, &self.out_keyfile)?.into();
-            let packet = Packet::EncryptionRequest(new_packet).write(self.packet_type);
-            self.write.prepare_data(&packet, &mut EncryptorType::None)?;
-            packet
+            pending.write.prepare_packet(
+                &Packet::EncryptionRequest(new_packet),
+                &self.version,
+                &mut EncryptorType::None,
+            )?
         } else {
-            let packet = packet.write(self.packet_type);
-            self.write.prepare_data(&packet, &mut self.encryption)?;
-            packet
+            pending
+                .write
+                .prepare_packet(packet, &self.version, &mut pending.encryption)?
         };
         #[cfg(feature = "ppac")]
         if let Some(writer) = &self.ppac {
             let mut lock = writer.lock().unwrap();
-            lock.write_data(crate::ppac::get_now(), self.direction, &_packet)?;
+            lock.write_data(crate::ppac::get_now(), self.direction, _packet)?;
         }
 
         Ok(())
@@ -690,25 +1139,62 @@ impl ConnectionWrite {
 
     /// Returns the encryption key (for [`Packet::EncryptionResponse`]).
     pub fn get_key(&mut self) -> Vec<u8> {
-        if matches!(self.encryption, EncryptorType::None) {
-            if let Ok(enc) = self.enc_channel.1.try_recv() {
-                self.encryption = enc
-            }
-        }
-        self.encryption.get_key()
+        self.shared.pending().encryption.get_key()
     }
-    /// Writes all pending packets.
-    pub fn flush(&mut self) -> std::io::Result<()> {
-        self.write.flush(&mut self.stream)
+    /// Writes all pending packets (including keepalive packets queued by the read half).
+    pub fn flush(&mut self) -> std::io::Result<()>
+    where
+        W: TransportWrite,
+    {
+        self.shared.flush(|write, stream| write.flush(stream))
     }
-    /// Writes all pending packets.
+    /// Writes all pending packets (including keepalive packets queued by the read half).
     #[cfg(feature = "tokio")]
     #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
-    pub async fn flush_async(&mut self) -> std::io::Result<()> {
-        self.write.flush_async(&mut self.stream).await
+    pub async fn flush_async(&mut self) -> std::io::Result<()>
+    where
+        W: AsyncTransportWrite,
+    {
+        self.shared.flush_async().await
     }
 }
 
+#[cfg(feature = "split_connection")]
+#[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
+impl<P: ProtocolRW + Send> ConnectionRead<P, DefaultReadHalf, DefaultWriteHalf> {
+    /// Same as [`std::net::TcpStream::set_nonblocking`]. Does nothing if `tokio` feature is
+    /// enabled.
+    pub fn set_nonblocking(&self, _nonblocking: bool) -> std::io::Result<()> {
+        #[cfg(not(feature = "tokio"))]
+        self.stream.set_nonblocking(_nonblocking)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "split_connection")]
+#[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
+impl ConnectionWrite<DefaultWriteHalf> {
+    /// Same as [`std::net::TcpStream::set_nonblocking`]. Does nothing if `tokio` feature is
+    /// enabled.
+    pub fn set_nonblocking(&self, _nonblocking: bool) -> std::io::Result<()> {
+        #[cfg(not(feature = "tokio"))]
+        self.shared
+            .lock_transport()?
+            .0
+            .set_nonblocking(_nonblocking)?;
+        Ok(())
+    }
+}
+
+fn get_ip(stream: &impl PeerAddr) -> std::io::Result<std::net::Ipv4Addr> {
+    let ip = stream.peer_addr()?.ip();
+    let ip = match ip {
+        std::net::IpAddr::V4(x) => x,
+        std::net::IpAddr::V6(_) => std::net::Ipv4Addr::UNSPECIFIED,
+    };
+    Ok(ip)
+}
+
 impl PublicKey {
     pub fn into_key(&self) -> rsa::errors::Result<Option<RsaPublicKey>> {
         match self {
@@ -741,3 +1227,171 @@ impl PrivateKey {
         }
     }
 }
+
+#[cfg(all(unix, test))]
+mod tests {
+    use super::{Connection, ConnectionError, Keepalive};
+    use crate::protocol::{Packet, PacketType};
+    use crate::{PrivateKey, PublicKey};
+    use std::time::Duration;
+
+    #[test]
+    fn unix_transport() {
+        let (a, b) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut client: Connection<Packet, _> =
+            Connection::with_transport(a, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        let mut server: Connection<Packet, _> =
+            Connection::with_transport(b, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        client.write_packet(&Packet::ServerPing).unwrap();
+        assert_eq!(server.read_packet().unwrap(), Packet::ServerPing);
+        drop(client);
+        assert!(server.read_packet().is_err());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn tokio_sync_read() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let a = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (b, _) = listener.accept().unwrap();
+        let mut client: Connection<Packet> =
+            Connection::new(a, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        let mut server: Connection<Packet> =
+            Connection::new(b, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        // synchronous reads don't block
+        assert!(matches!(
+            server.read_packet(),
+            Err(ConnectionError::Io(e)) if e.kind() == std::io::ErrorKind::WouldBlock
+        ));
+        client.get_ref().writable().await.unwrap();
+        client.write_packet(&Packet::ServerPing).unwrap();
+        server.get_ref().readable().await.unwrap();
+        assert_eq!(server.read_packet().unwrap(), Packet::ServerPing);
+    }
+
+    #[test]
+    fn keepalive() {
+        let (a, b) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut client: Connection<Packet, _> =
+            Connection::with_transport(a, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        let mut server: Connection<Packet, _> =
+            Connection::with_transport(b, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        client.set_keepalive(Keepalive {
+            answer_pings: true,
+            timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        });
+        server.write_packet(&Packet::ServerPing).unwrap();
+        server.write_packet(&Packet::ServerPong).unwrap();
+        assert_eq!(client.read_packet().unwrap(), Packet::ServerPong);
+        assert_eq!(server.read_packet().unwrap(), Packet::ServerPong);
+
+        client
+            .get_ref()
+            .set_read_timeout(Some(Duration::from_millis(10)))
+            .unwrap();
+        loop {
+            match client.read_packet() {
+                Err(ConnectionError::Timeout) => break,
+                Err(ConnectionError::Io(_)) => {}
+                r => panic!("unexpected result: {r:?}"),
+            }
+        }
+    }
+
+    #[cfg(feature = "ppac")]
+    #[test]
+    fn detect_ppac() {
+        use crate::ppac::{Direction, PPACReader};
+
+        let (a, b) = std::os::unix::net::UnixStream::pair().unwrap();
+        let mut client: Connection<Packet, _> =
+            Connection::with_transport(a, PacketType::NA, PrivateKey::None, PublicKey::None);
+        let mut server: Connection<Packet, _> =
+            Connection::with_transport(b, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        server.set_auto_detect(true);
+        let path = std::env::temp_dir().join(format!("detect_ppac_{}.pak", std::process::id()));
+        server.create_ppac(&path, Direction::ToClient).unwrap();
+        client
+            .write_packet(&Packet::EncryptionRequest(Default::default()))
+            .unwrap();
+        server.read_packet().unwrap();
+        // the login packet is not received yet
+        assert_eq!(server.packet_type(), PacketType::Classic);
+        drop(server);
+
+        let reader: PPACReader<_, Packet> =
+            PPACReader::open(std::fs::File::open(&path).unwrap()).unwrap();
+        let packet_type = reader.get_protocol_type();
+        drop(reader);
+        std::fs::remove_file(&path).unwrap();
+        // the transitional packet type is not recorded
+        assert_eq!(packet_type, PacketType::NGS);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn keepalive_async() {
+        let (a, b) = tokio::io::duplex(4096);
+        let mut client: Connection<Packet, _> =
+            Connection::with_transport(a, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        let mut server: Connection<Packet, _> =
+            Connection::with_transport(b, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        client.set_keepalive_async(Keepalive {
+            answer_pings: true,
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        });
+        server.set_keepalive_async(Keepalive {
+            ping_interval: Some(Duration::from_millis(10)),
+            ..Default::default()
+        });
+        // pings are sent and answered while both sides are only reading
+        let (server_result, _) = tokio::join!(server.read_packet_async(), async {
+            let read = tokio::time::timeout(Duration::from_millis(50), client.read_packet_async());
+            assert!(read.await.is_err());
+            client
+                .write_packet_async(&Packet::InitialLoad)
+                .await
+                .unwrap();
+        });
+        assert_eq!(server_result.unwrap(), Packet::InitialLoad);
+
+        assert!(matches!(
+            client.read_packet_async().await,
+            Err(ConnectionError::Timeout)
+        ));
+    }
+
+    #[cfg(all(feature = "tokio", feature = "split_connection"))]
+    #[tokio::test]
+    async fn keepalive_split() {
+        let (a, b) = tokio::io::duplex(4096);
+        let client: Connection<Packet, _> =
+            Connection::with_transport(a, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        let mut server: Connection<Packet, _> =
+            Connection::with_transport(b, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        let (mut client_read, _client_write) = client.into_split().unwrap();
+        client_read.set_keepalive_async(Keepalive {
+            answer_pings: true,
+            ..Default::default()
+        });
+        server
+            .write_packet_async(&Packet::ServerPing)
+            .await
+            .unwrap();
+        server
+            .write_packet_async(&Packet::InitialLoad)
+            .await
+            .unwrap();
+        // the ping is answered without using the write half
+        assert_eq!(
+            client_read.read_packet_async().await.unwrap(),
+            Packet::InitialLoad
+        );
+        assert_eq!(
+            server.read_packet_async().await.unwrap(),
+            Packet::ServerPong
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Offline decryption of captured sessions.
+
+use super::{detect::Detector, ConnectionError, PrivateKey};
+use crate::{
+    crypto::{Decryptor, Encryption},
+    framing::frame_length,
+    ppac::{Direction, PPACWriter},
+    protocol::{
+        Packet, PacketEncryption, PacketError, PacketType, PacketView, ParseLimits, ProtocolRW,
+        ProtocolVersion,
+    },
+};
+use std::{collections::VecDeque, io::Write, time::Duration};
+
+/// Decryptor of captured client <-> server sessions.
+///
+/// Session data of both directions is passed to [`SessionDecryptor::feed`] in the capture order
+/// (e.g. as [`StreamChunk`](crate::pcap::StreamChunk)s of a reassembled TCP session). When the
+/// client sends [`Packet::EncryptionRequest`] the session key is recovered using the server's
+/// private key and all following data in both directions is decrypted.
+///
+/// # Usage
+/// ```no_run
+/// # use pso2packetlib::connection::offline::SessionDecryptor;
+/// # use pso2packetlib::ppac::Direction;
+/// # use pso2packetlib::protocol::{Packet, PacketType};
+/// # use pso2packetlib::PrivateKey;
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), pso2packetlib::connection::ConnectionError> {
+/// # let (client_data, server_data) = (vec![], vec![]);
+/// let key = PrivateKey::Path("server_privkey.pem".into());
+/// let mut decryptor = SessionDecryptor::new(PacketType::NGS, key);
+/// decryptor.feed(Duration::ZERO, Direction::ToClient, &server_data)?;
+/// decryptor.feed(Duration::ZERO, Direction::ToServer, &client_data)?;
+/// while let Some((direction, packet)) = decryptor.next_packet::<Packet>()? {
+///     println!("{direction:?}: {packet:?}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SessionDecryptor {
+    version: ProtocolVersion,
+    key: PrivateKey,
+    detect: Detector,
+    limits: ParseLimits,
+    /// Client and server stream states.
+    streams: [StreamState; 2],
+    frames: VecDeque<DecryptedFrame>,
+}
+
+/// Decrypted packet data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecryptedFrame {
+    /// Capture time of the data that completed the packet.
+    pub time: Duration,
+    /// Direction of the packet.
+    pub direction: Direction,
+    /// Decrypted packet data.
+    pub data: Vec<u8>,
+}
+
+/// Single direction of a session.
+#[derive(Debug, Default)]
+struct StreamState {
+    encryption: Encryption,
+    /// Cipher that is used once the buffered data is consumed.
+    pending: Option<Encryption>,
+    buffer: Vec<u8>,
+    /// Number of already decrypted bytes in the buffer (for stream ciphers).
+    decrypted: usize,
+}
+
+impl SessionDecryptor {
+    /// Creates a new decryptor.
+    /// `key` is the server's RSA key to decrypt encryption request.
+    pub fn new(version: impl Into<ProtocolVersion>, key: PrivateKey) -> Self {
+        Self {
+            version: version.into(),
+            key,
+            detect: Detector::default(),
+            limits: ParseLimits::default(),
+            streams: Default::default(),
+            frames: VecDeque::new(),
+        }
+    }
+
+    /// Returns the current packet type.
+    pub fn packet_type(&self) -> PacketType {
+        self.version.packet_type
+    }
+
+    /// Returns the current protocol version.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// Enables or disables packet type detection.
+    ///
+    /// If enabled, the packet type is inferred from the client handshake. See
+    /// [`Connection::set_auto_detect`](super::Connection::set_auto_detect) for details.
+    pub fn set_auto_detect(&mut self, enabled: bool) {
+        self.detect = Detector::new(enabled, self.version.packet_type);
+    }
+
+    /// Sets the limits for received packets.
+    pub fn set_limits(&mut self, limits: ParseLimits) {
+        self.limits = limits;
+    }
+
+    /// Appends session data sent in the specified direction.
+    ///
+    /// Data must be passed in the capture order, otherwise the start of the server encryption
+    /// can't be determined.
+    pub fn feed(
+        &mut self,
+        time: Duration,
+        direction: Direction,
+        data: &[u8],
+    ) -> Result<(), ConnectionError> {
+        self.streams[direction as usize].push_data(data);
+        while let Some(data) = self.streams[direction as usize].next_frame(&self.limits)? {
+            if direction == Direction::ToServer {
+                self.handle_client_frame(&data)?;
+            }
+            self.frames.push_back(DecryptedFrame {
+                time,
+                direction,
+                data,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if there is no buffered data or undelivered packets.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty() && self.streams.iter().all(|s| s.buffer.is_empty())
+    }
+
+    /// Returns the next decrypted packet data.
+    pub fn next_frame(&mut self) -> Option<DecryptedFrame> {
+        self.frames.pop_front()
+    }
+
+    /// Returns the next decrypted packet.
+    pub fn next_packet<P: ProtocolRW>(
+        &mut self,
+    ) -> Result<Option<(Direction, P)>, ConnectionError> {
+        let Some(frame) = self.frames.pop_front() else {
+            return Ok(None);
+        };
+        let mut packets = P::read_versioned_limited(&frame.data, self.version, &self.limits)?;
+        if packets.is_empty() {
+            return Err(ConnectionError::PacketError(
+                PacketError::PacketLengthError {
+                    error: std::io::ErrorKind::UnexpectedEof.into(),
+                },
+            ));
+        }
+        Ok(Some((frame.direction, packets.swap_remove(0))))
+    }
+
+    /// Decrypts all fed data and writes it to the PPAC file. Returns the number of written
+    /// packets.
+    pub fn write_ppac<W: Write>(
+        &mut self,
+        writer: &mut PPACWriter<W>,
+    ) -> Result<u64, ConnectionError> {
+        let mut count = 0;
+        while let Some(frame) = self.frames.pop_front() {
+            writer.write_data_unchecked(frame.time, frame.direction, &frame.data)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn handle_client_frame(&mut self, data: &[u8]) -> Result<(), ConnectionError> {
+        if let Some(packet_type) = self.detect.detect_frame(data) {
+            self.version.packet_type = packet_type;
+        }
+        let Ok(view) = PacketView::new_versioned(data, self.version) else {
+            return Ok(());
+        };
+        let header = view.header();
+        if (header.id, header.subid) != (0x11, 0x0B) || matches!(self.key, PrivateKey::None) {
+            return Ok(());
+        }
+        let mut packets = Packet::read_versioned_limited(data, self.version, &self.limits)?;
+        let Some(rsa_data) = packets.first_mut().and_then(|p| p.mut_enc_data()) else {
+            return Ok(());
+        };
+        let dec_data = Encryption::decrypt_rsa_data(rsa_data, &self.key)?;
+        if let Some(packet_type) = self.detect.detect_key(&dec_data) {
+            self.version.packet_type = packet_type;
+        }
+        let is_ngs = matches!(self.version.packet_type, PacketType::NGS);
+        // both sides start with the same cipher state. The rest of the client data follows the
+        // request, but the buffered server data was sent before the server received it.
+        let [client, server] = &mut self.streams;
+        client.set_encryption(Encryption::from_dec_data(&dec_data, is_ngs)?);
+        server.pending = Some(Encryption::from_dec_data(&dec_data, is_ngs)?);
+        server.apply_pending();
+        Ok(())
+    }
+}
+
+impl StreamState {
+    fn push_data(&mut self, data: &[u8]) {
+        self.apply_pending();
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Switches the cipher at the start of the buffered data.
+    fn set_encryption(&mut self, encryption: Encryption) {
+        self.encryption = encryption;
+        self.pending = None;
+        self.decrypted = 0;
+    }
+
+    /// Switches to the pending cipher if there is no buffered data.
+    fn apply_pending(&mut self) {
+        if self.buffer.is_empty() {
+            if let Some(encryption) = self.pending.take() {
+                self.set_encryption(encryption);
+            }
+        }
+    }
+
+    /// Decrypts the buffered data up to `end` (for stream ciphers).
+    fn decrypt_to(&mut self, end: usize) -> Result<(), ConnectionError> {
+        let end = end.min(self.buffer.len());
+        if !self.encryption.is_rc4() || self.decrypted >= end {
+            return Ok(());
+        }
+        let data = self.encryption.decrypt(&self.buffer[self.decrypted..end])?;
+        self.buffer[self.decrypted..end].copy_from_slice(&data);
+        self.decrypted = end;
+        Ok(())
+    }
+
+    /// Extracts a single decrypted packet from the buffer.
+    fn next_frame(&mut self, limits: &ParseLimits) -> Result<Option<Vec<u8>>, ConnectionError> {
+        let offset = self.encryption.get_len_type().offset();
+        // stream ciphers are only advanced up to the end of the packet
+        self.decrypt_to(offset + 4)?;
+        let Some(len) = frame_length(&self.buffer, offset, limits)? else {
+            return Ok(None);
+        };
+        if self.buffer.len() < len {
+            return Ok(None);
+        }
+        self.decrypt_to(len)?;
+        let mut data: Vec<_> = self.buffer.drain(..len).collect();
+        self.decrypted = 0;
+        if !self.encryption.is_rc4() {
+            data = self.encryption.decrypt(&data)?;
+        }
+        self.apply_pending();
+        Ok(Some(data))
+    }
+}
+
+#[cfg(all(test, feature = "vita_enc", feature = "ngs_enc"))]
+mod tests {
+    use super::SessionDecryptor;
+    use crate::{
+        connection::codec::Codec,
+        ppac::Direction,
+        protocol::{
+            login::{EncryptionRequestPacket, SegaIDLoginPacket},
+            Packet, PacketType, ProtocolRW,
+        },
+        PrivateKey, PublicKey,
+    };
+    use aes::cipher::{block_padding::Pkcs7, BlockEncryptMut, KeyIvInit};
+    use std::time::Duration;
+
+    /// Runs a session with the given key material and decrypts it offline. If `interleave` is
+    /// set, then the first server packet is completed after the encryption request.
+    fn session(packet_type: PacketType, key_data: Vec<u8>, interleave: bool) {
+        let key = rsa::RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let mut client = Codec::<Packet>::new(
+            packet_type,
+            PrivateKey::None,
+            PublicKey::Key(key.to_public_key()),
+        );
+        let mut server =
+            Codec::<Packet>::new(packet_type, PrivateKey::Key(key.clone()), PublicKey::None);
+
+        let request = Packet::EncryptionRequest(EncryptionRequestPacket {
+            rsa_data: key_data.into(),
+        });
+        let mut packets = [
+            (Direction::ToClient, Packet::ServerHello(Default::default())),
+            (Direction::ToServer, request),
+            (
+                Direction::ToClient,
+                Packet::EncryptionResponse(Default::default()),
+            ),
+            (
+                Direction::ToServer,
+                Packet::SegaIDLogin(SegaIDLoginPacket::default()),
+            ),
+            (Direction::ToClient, Packet::ServerPing),
+            (Direction::ToServer, Packet::ServerPong),
+        ];
+        let mut stream = vec![];
+        for (direction, packet) in &packets {
+            let (sender, receiver) = match direction {
+                Direction::ToServer => (&mut client, &mut server),
+                Direction::ToClient => (&mut server, &mut client),
+            };
+            let data = sender.encode(packet).unwrap();
+            receiver.feed(&data).unwrap();
+            receiver.decode().unwrap().unwrap();
+            stream.push((*direction, data));
+        }
+
+        // split the data to test buffering
+        let mut chunks: Vec<_> = stream
+            .iter()
+            .flat_map(|(direction, data)| {
+                let (first, second) = data.split_at(data.len() / 2);
+                [(*direction, first), (*direction, second)]
+            })
+            .collect();
+        if interleave {
+            // unencrypted server data is still buffered when the key is recovered
+            chunks[1..4].rotate_left(1);
+            packets.swap(0, 1);
+        }
+        let mut decryptor = SessionDecryptor::new(packet_type, PrivateKey::Key(key));
+        for (direction, data) in chunks {
+            decryptor.feed(Duration::ZERO, direction, data).unwrap();
+        }
+        for (direction, packet) in packets {
+            let frame = decryptor.next_frame().unwrap();
+            assert_eq!(frame.direction, direction);
+            // encryption request contains the encrypted key
+            if !matches!(packet, Packet::EncryptionRequest(_)) {
+                assert_eq!(frame.data, packet.write(packet_type));
+            }
+        }
+        assert!(decryptor.is_empty());
+    }
+
+    #[test]
+    fn rc4_session() {
+        session(PacketType::Vita, (0..0x20).collect(), false);
+        session(PacketType::Vita, (0..0x20).collect(), true);
+    }
+
+    #[test]
+    fn ngs_session() {
+        let key = [0x42; 0x20];
+        let iv: [u8; 0x10] = core::array::from_fn(|i| i as u8);
+        let mut secret = [0u8; 0x30];
+        secret[..0x20].fill(0x13);
+        let secret = cbc::Encryptor::<aes::Aes256>::new(&key.into(), &iv.into())
+            .encrypt_padded_mut::<Pkcs7>(&mut secret, 0x20)
+            .unwrap()
+            .to_vec();
+        let key_data = [secret, key.to_vec()].concat();
+        session(PacketType::NGS, key_data.clone(), false);
+        session(PacketType::NGS, key_data, true);
+    }
+}
@@ -0,0 +1,289 @@
+//! Transports used by [`Connection`](super::Connection).
+//!
+//! Synchronous transports are implemented for TCP and Unix streams. With the `tokio` feature
+//! enabled, tokio streams can be used both synchronously (all operations are nonblocking) and
+//! asynchronously. Any other type that implements [`AsyncRead`]/[`AsyncWrite`] can be used as an
+//! asynchronous transport and any type that implements [`Read`]/[`Write`] can be wrapped in
+//! [`IoTransport`].
+//!
+//! [`AsyncRead`]: tokio::io::AsyncRead
+//! [`AsyncWrite`]: tokio::io::AsyncWrite
+
+use std::io::{Read, Write};
+
+/// Default stream type used by [`Connection`](super::Connection).
+#[cfg(not(feature = "tokio"))]
+pub type DefaultStream = std::net::TcpStream;
+/// Default stream type used by [`Connection`](super::Connection).
+#[cfg(feature = "tokio")]
+pub type DefaultStream = tokio::net::TcpStream;
+
+/// Default read half type used by [`ConnectionRead`](super::ConnectionRead).
+#[cfg(all(feature = "split_connection", not(feature = "tokio")))]
+pub type DefaultReadHalf = std::net::TcpStream;
+/// Default read half type used by [`ConnectionRead`](super::ConnectionRead).
+#[cfg(all(feature = "split_connection", feature = "tokio"))]
+pub type DefaultReadHalf = tokio::net::tcp::OwnedReadHalf;
+
+/// Default write half type used by [`ConnectionWrite`](super::ConnectionWrite).
+#[cfg(all(feature = "split_connection", not(feature = "tokio")))]
+pub type DefaultWriteHalf = std::net::TcpStream;
+/// Default write half type used by [`ConnectionWrite`](super::ConnectionWrite).
+#[cfg(all(feature = "split_connection", feature = "tokio"))]
+pub type DefaultWriteHalf = tokio::net::tcp::OwnedWriteHalf;
+
+/// Readable side of a synchronous transport.
+///
+/// If the underlying stream is in a nonblocking mode then all read operations are nonblocking.
+pub trait TransportRead: Send {
+    /// Reads some data from the transport. Returns `0` if the transport was closed.
+    fn read_transport(&mut self, buf: &mut [u8]) -> std::io::Result<usize>;
+}
+
+/// Writable side of a synchronous transport.
+///
+/// If the underlying stream is in a nonblocking mode then all write operations are nonblocking.
+pub trait TransportWrite: Send {
+    /// Writes some data to the transport.
+    fn write_transport(&mut self, buf: &[u8]) -> std::io::Result<usize>;
+    /// Flushes the transport.
+    fn flush_transport(&mut self) -> std::io::Result<()>;
+}
+
+/// Readable side of an asynchronous transport.
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub trait AsyncTransportRead: Send {
+    /// Reads some data from the transport. Returns `0` if the transport was closed.
+    ///
+    /// This function must be cancel safe.
+    fn read_transport_async(
+        &mut self,
+        buf: &mut [u8],
+    ) -> impl std::future::Future<Output = std::io::Result<usize>> + Send;
+}
+
+/// Writable side of an asynchronous transport.
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub trait AsyncTransportWrite: Send {
+    /// Writes some data to the transport.
+    fn write_transport_async(
+        &mut self,
+        buf: &[u8],
+    ) -> impl std::future::Future<Output = std::io::Result<usize>> + Send;
+    /// Flushes the transport.
+    fn flush_transport_async(
+        &mut self,
+    ) -> impl std::future::Future<Output = std::io::Result<()>> + Send;
+}
+
+/// Transport that can be split into separate read and write halves.
+#[cfg(feature = "split_connection")]
+#[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
+pub trait SplitTransport {
+    /// Read half of the transport.
+    type Read;
+    /// Write half of the transport.
+    type Write;
+    /// Splits the transport.
+    fn split_transport(self) -> std::io::Result<(Self::Read, Self::Write)>;
+}
+
+/// Transport that has a remote address.
+pub trait PeerAddr {
+    /// Returns the address of the remote peer.
+    fn peer_addr(&self) -> std::io::Result<std::net::SocketAddr>;
+}
+
+/// Synchronous transport over any [`Read`]/[`Write`] type.
+///
+/// # Usage
+/// ```
+/// # use pso2packetlib::{Connection, PrivateKey, PublicKey};
+/// # use pso2packetlib::connection::transport::IoTransport;
+/// # use pso2packetlib::protocol::{Packet, PacketType};
+/// let stream = IoTransport(std::io::Cursor::new(vec![]));
+/// let mut conn: Connection<Packet, _> =
+///     Connection::with_transport(stream, PacketType::NGS, PrivateKey::None, PublicKey::None);
+/// conn.write_packet(&Packet::ServerPing).unwrap();
+/// assert!(!conn.get_ref().0.get_ref().is_empty());
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IoTransport<T>(pub T);
+
+impl<T: Read + Send> TransportRead for IoTransport<T> {
+    fn read_transport(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl<T: Write + Send> TransportWrite for IoTransport<T> {
+    fn write_transport(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+    fn flush_transport(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+macro_rules! std_transport {
+    ($($(#[$attr:meta])* $name:ty),+ $(,)?) => {$(
+        $(#[$attr])*
+        impl TransportRead for $name {
+            fn read_transport(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.read(buf)
+            }
+        }
+
+        $(#[$attr])*
+        impl TransportWrite for $name {
+            fn write_transport(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.write(buf)
+            }
+            fn flush_transport(&mut self) -> std::io::Result<()> {
+                self.flush()
+            }
+        }
+    )+};
+}
+
+std_transport!(
+    std::net::TcpStream,
+    #[cfg(unix)]
+    std::os::unix::net::UnixStream,
+);
+
+// tokio streams are used in a nonblocking mode
+#[cfg(feature = "tokio")]
+macro_rules! tokio_transport {
+    (read: $($(#[$attr:meta])* $name:ty),+ $(,)?) => {$(
+        $(#[$attr])*
+        impl TransportRead for $name {
+            fn read_transport(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                self.try_read(buf)
+            }
+        }
+    )+};
+    (write: $($(#[$attr:meta])* $name:ty),+ $(,)?) => {$(
+        $(#[$attr])*
+        impl TransportWrite for $name {
+            fn write_transport(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.try_write(buf)
+            }
+            fn flush_transport(&mut self) -> std::io::Result<()> {
+                // writes aren't buffered
+                Ok(())
+            }
+        }
+    )+};
+}
+
+#[cfg(feature = "tokio")]
+tokio_transport!(read:
+    tokio::net::TcpStream,
+    tokio::net::tcp::OwnedReadHalf,
+    #[cfg(unix)]
+    tokio::net::UnixStream,
+    #[cfg(unix)]
+    tokio::net::unix::OwnedReadHalf,
+);
+
+#[cfg(feature = "tokio")]
+tokio_transport!(write:
+    tokio::net::TcpStream,
+    tokio::net::tcp::OwnedWriteHalf,
+    #[cfg(unix)]
+    tokio::net::UnixStream,
+    #[cfg(unix)]
+    tokio::net::unix::OwnedWriteHalf,
+);
+
+#[cfg(feature = "tokio")]
+impl<T: tokio::io::AsyncRead + Unpin + Send> AsyncTransportRead for T {
+    async fn read_transport_async(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        tokio::io::AsyncReadExt::read(self, buf).await
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T: tokio::io::AsyncWrite + Unpin + Send> AsyncTransportWrite for T {
+    async fn write_transport_async(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        tokio::io::AsyncWriteExt::write(self, buf).await
+    }
+    async fn flush_transport_async(&mut self) -> std::io::Result<()> {
+        tokio::io::AsyncWriteExt::flush(self).await
+    }
+}
+
+#[cfg(feature = "split_connection")]
+impl SplitTransport for std::net::TcpStream {
+    type Read = Self;
+    type Write = Self;
+    fn split_transport(self) -> std::io::Result<(Self::Read, Self::Write)> {
+        Ok((self.try_clone()?, self))
+    }
+}
+
+#[cfg(all(feature = "split_connection", unix))]
+impl SplitTransport for std::os::unix::net::UnixStream {
+    type Read = Self;
+    type Write = Self;
+    fn split_transport(self) -> std::io::Result<(Self::Read, Self::Write)> {
+        Ok((self.try_clone()?, self))
+    }
+}
+
+#[cfg(all(feature = "split_connection", feature = "tokio"))]
+impl SplitTransport for tokio::net::TcpStream {
+    type Read = tokio::net::tcp::OwnedReadHalf;
+    type Write = tokio::net::tcp::OwnedWriteHalf;
+    fn split_transport(self) -> std::io::Result<(Self::Read, Self::Write)> {
+        Ok(self.into_split())
+    }
+}
+
+#[cfg(all(feature = "split_connection", feature = "tokio", unix))]
+impl SplitTransport for tokio::net::UnixStream {
+    type Read = tokio::net::unix::OwnedReadHalf;
+    type Write = tokio::net::unix::OwnedWriteHalf;
+    fn split_transport(self) -> std::io::Result<(Self::Read, Self::Write)> {
+        Ok(self.into_split())
+    }
+}
+
+#[cfg(all(feature = "split_connection", feature = "tokio"))]
+impl SplitTransport for tokio::io::DuplexStream {
+    type Read = tokio::io::ReadHalf<Self>;
+    type Write = tokio::io::WriteHalf<Self>;
+    fn split_transport(self) -> std::io::Result<(Self::Read, Self::Write)> {
+        Ok(tokio::io::split(self))
+    }
+}
+
+impl PeerAddr for std::net::TcpStream {
+    fn peer_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.peer_addr()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl PeerAddr for tokio::net::TcpStream {
+    fn peer_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.peer_addr()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl PeerAddr for tokio::net::tcp::OwnedReadHalf {
+    fn peer_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.peer_addr()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl PeerAddr for tokio::net::tcp::OwnedWriteHalf {
+    fn peer_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.peer_addr()
+    }
+}
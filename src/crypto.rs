@@ -0,0 +1,1108 @@
+//! Packet encryption.
+//!
+//! The session key is sent by the client in [`Packet::EncryptionRequest`] encrypted with the
+//! server's RSA key. The decrypted key data is turned into a cipher by
+//! [`Encryption::from_dec_data`]. Depending on the client the following ciphers are used:
+//! - [`Aes`] for the classic clients (requires the `base_enc` feature),
+//! - [`AesNgs`] for the NGS client (requires the `ngs_enc` feature),
+//! - [`Rc4Enc`]/[`Rc4Dec`] for the Vita client (requires the `vita_enc` feature).
+//!
+//! Block ciphers encrypt whole packets and have their own framing (see [`LengthType`]), while
+//! RC4 encrypts the byte stream.
+//!
+//! [`Packet::EncryptionRequest`]: crate::protocol::Packet::EncryptionRequest
+#![allow(unused_variables)]
+#![allow(unused_imports)]
+use crate::{
+    connection::{PrivateKey, PublicKey},
+    protocol::{
+        login::{EncryptionRequestPacket, EncryptionResponsePacket},
+        PacketType,
+    },
+};
+#[cfg(any(feature = "base_enc", feature = "ngs_enc"))]
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
+#[cfg(any(feature = "base_enc", feature = "ngs_enc"))]
+use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
+#[cfg(feature = "vita_enc")]
+use rc4::{consts::U16, Rc4};
+#[cfg(any(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc"))]
+use rsa::Pkcs1v15Encrypt;
+#[cfg(any(feature = "base_enc", feature = "ngs_enc"))]
+use sha2::Sha256;
+use std::{
+    fmt::Debug,
+    io::{Error, ErrorKind},
+};
+
+/// Error type returned by encryption methods.
+#[derive(Debug, thiserror::Error)]
+pub enum EncryptionError {
+    /// Error has occured in [`rsa`] functions.
+    #[error("RSA error occured: {0}")]
+    RSAError(#[from] rsa::errors::Error),
+    /// No private key was provided for decryption.
+    #[error("no private key provided")]
+    NoPrivateKey,
+    /// No public key was provided for encryption.
+    #[error("no public key provided")]
+    NoPublicKey,
+    /// AES encryption padding failed.
+    #[error("AES encryption padding failed")]
+    PadError,
+    /// AES decryption unpadding failed (or the encrypted data is too short).
+    #[error("AES decryption unpadding failed")]
+    UnpadError,
+    /// Error occured during ZSTD operations.
+    #[error("error occured while performing ZSTD operations: {error}")]
+    ZSTDError {
+        #[source]
+        error: std::io::Error,
+    },
+    /// Encryption of the packet type is not enabled.
+    #[error("encryption for {0:?} packets is not enabled")]
+    UnsupportedPacketType(PacketType),
+    /// Server responded with a different session secret.
+    #[error("server responded with a different session secret")]
+    SecretMismatch,
+    /// Decrypted key data has an invalid length.
+    #[error("invalid key data length: {0}")]
+    InvalidKeyLength(usize),
+}
+
+/// Packet encryption.
+pub trait Encryptor {
+    /// Encrypts a single packet. Returned data is ready to be sent.
+    fn encrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError>;
+}
+/// Packet decryption.
+pub trait Decryptor {
+    /// Decrypts data.
+    ///
+    /// If [`Decryptor::is_rc4`] returns `true`, then `data` is a part of the received byte stream,
+    /// otherwise `data` is a single encrypted packet (see [`Decryptor::get_len_type`]).
+    fn decrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError>;
+    /// Returns `true` if the cipher is a stream cipher (i.e. the stream must be decrypted before
+    /// it's split into packets).
+    fn is_rc4(&self) -> bool {
+        false
+    }
+    /// Returns the location of the packet length in the received data.
+    fn get_len_type(&self) -> LengthType;
+}
+
+/// Session cipher used for both directions.
+#[derive(Debug, Default)]
+pub enum Encryption {
+    /// No encryption.
+    #[default]
+    None,
+    /// Classic AES encryption.
+    #[cfg(feature = "base_enc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "base_enc")))]
+    Aes(Aes),
+    /// NGS AES encryption.
+    #[cfg(feature = "ngs_enc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ngs_enc")))]
+    AesNgs(AesNgs),
+    /// Vita RC4 encryption.
+    #[cfg(feature = "vita_enc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "vita_enc")))]
+    Rc4((Rc4Dec, Rc4Enc)),
+}
+
+/// Location of the packet length in the received data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthType {
+    /// Length is stored in the first 4 bytes (plain or RC4 data).
+    Default,
+    /// Length is stored at `0x44..0x48` (AES encrypted data).
+    Aes,
+}
+
+impl LengthType {
+    /// Returns the offset of the length field.
+    pub(crate) fn offset(self) -> usize {
+        match self {
+            Self::Default => 0,
+            Self::Aes => 0x44,
+        }
+    }
+}
+
+/// Encrypting half of the split [`Encryption`].
+#[cfg(feature = "split_connection")]
+#[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
+#[derive(Debug, Default)]
+pub enum EncryptorType {
+    /// No encryption.
+    #[default]
+    None,
+    /// Classic AES encryption.
+    #[cfg(feature = "base_enc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "base_enc")))]
+    Aes(Aes),
+    /// NGS AES encryption.
+    #[cfg(feature = "ngs_enc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ngs_enc")))]
+    AesNgs(AesNgs),
+    /// Vita RC4 encryption.
+    #[cfg(feature = "vita_enc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "vita_enc")))]
+    Rc4(Rc4Enc),
+}
+
+/// Decrypting half of the split [`Encryption`].
+#[cfg(feature = "split_connection")]
+#[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
+#[derive(Debug, Default)]
+pub enum DecryptorType {
+    /// No encryption.
+    #[default]
+    None,
+    /// Classic AES encryption.
+    #[cfg(feature = "base_enc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "base_enc")))]
+    Aes(Aes),
+    /// NGS AES encryption.
+    #[cfg(feature = "ngs_enc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ngs_enc")))]
+    AesNgs(AesNgs),
+    /// Vita RC4 encryption.
+    #[cfg(feature = "vita_enc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "vita_enc")))]
+    Rc4(Rc4Dec),
+}
+
+impl Encryption {
+    /// Decrypts the key data of [`Packet::EncryptionRequest`] with the server's RSA key.
+    ///
+    /// If no encryption features are enabled, then the data is returned as is.
+    ///
+    /// [`Packet::EncryptionRequest`]: crate::protocol::Packet::EncryptionRequest
+    pub fn decrypt_rsa_data(packet: &[u8], key: &PrivateKey) -> Result<Vec<u8>, EncryptionError> {
+        #[cfg(any(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc"))]
+        let private_key = match key.into_key()? {
+            Some(x) => x,
+            None => {
+                return Err(EncryptionError::NoPrivateKey);
+            }
+        };
+        #[cfg(any(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc"))]
+        let dec_data = private_key.decrypt(Pkcs1v15Encrypt, packet)?;
+        #[cfg(not(any(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc")))]
+        let dec_data = packet.to_vec();
+        Ok(dec_data)
+    }
+    /// Creates a cipher from the decrypted key data.
+    ///
+    /// Key data of `0x50` bytes creates an AES cipher ([`AesNgs`] if `is_ngs` is `true`), key data
+    /// of `0x20` bytes creates an RC4 cipher. If the required feature is not enabled, then
+    /// [`Encryption::None`] is returned.
+    ///
+    /// # Errors
+    /// Returns [`EncryptionError::InvalidKeyLength`] if the key data has any other length.
+    pub fn from_dec_data(data: &[u8], is_ngs: bool) -> Result<Self, EncryptionError> {
+        if !matches!(data.len(), 0x20 | 0x50) {
+            return Err(EncryptionError::InvalidKeyLength(data.len()));
+        }
+        #[cfg(any(feature = "base_enc", feature = "ngs_enc"))]
+        if data.len() == 0x50 {
+            let mut iv = AES_KEY_IV;
+            let mut key = [0u8; 32];
+            let mut key_d = [0u8; 0x30];
+            key_d.copy_from_slice(&data[0x0..0x30]);
+            key.copy_from_slice(&data[0x30..0x50]);
+            let aes = cbc::Decryptor::<aes::Aes256>::new(&key.into(), &iv.into());
+            aes.decrypt_padded_mut::<Pkcs7>(&mut key_d)
+                .map_err(|_| EncryptionError::UnpadError)?;
+            iv.copy_from_slice(&key_d[0x00..0x10]);
+            if is_ngs {
+                #[cfg(feature = "ngs_enc")]
+                return Ok(Self::AesNgs(AesNgs::new(key, key_d)));
+            } else {
+                #[cfg(feature = "base_enc")]
+                return Ok(Self::Aes(Aes::new(key, key_d)));
+            }
+        }
+        #[cfg(feature = "vita_enc")]
+        if data.len() == 0x20 {
+            use rc4::{KeyInit, StreamCipher};
+            let mut rc4_key = [0u8; 0x10];
+            let mut secret = [0u8; 0x10];
+            rc4_key.clone_from_slice(&data[0x10..0x20]);
+            secret.clone_from_slice(&data[0x00..0x10]);
+            let mut tmp_dec = Rc4::new(&rc4_key.into());
+            tmp_dec.apply_keystream(&mut secret);
+            return Ok(Self::Rc4((
+                Rc4Dec::new(rc4_key, secret),
+                Rc4Enc::new(rc4_key, secret),
+            )));
+        }
+        Ok(Self::None)
+    }
+    /// Splits the cipher into encrypting and decrypting halves.
+    #[cfg(feature = "split_connection")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "split_connection")))]
+    pub fn into_split(self) -> (EncryptorType, DecryptorType) {
+        match self {
+            Encryption::None => (EncryptorType::None, DecryptorType::None),
+            #[cfg(feature = "base_enc")]
+            Encryption::Aes(x) => (EncryptorType::Aes(x.clone()), DecryptorType::Aes(x)),
+            #[cfg(feature = "ngs_enc")]
+            Encryption::AesNgs(x) => (EncryptorType::AesNgs(x.clone()), DecryptorType::AesNgs(x)),
+            #[cfg(feature = "vita_enc")]
+            Encryption::Rc4((dec, enc)) => (EncryptorType::Rc4(enc), DecryptorType::Rc4(dec)),
+        }
+    }
+    /// Returns the session secret (for [`Packet::EncryptionResponse`]).
+    ///
+    /// [`Packet::EncryptionResponse`]: crate::protocol::Packet::EncryptionResponse
+    pub fn get_key(&self) -> Vec<u8> {
+        match self {
+            Self::None => Vec::new(),
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => x.secret.clone(),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => x.secret.clone(),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4((x, _)) => x.secret.to_vec(),
+        }
+    }
+    /// Exports the key data (i.e. the inverse of [`Encryption::from_dec_data`]).
+    ///
+    /// Returned data can be sent in [`Packet::EncryptionRequest`] or used to create the same
+    /// cipher later.
+    ///
+    /// [`Packet::EncryptionRequest`]: crate::protocol::Packet::EncryptionRequest
+    pub fn to_dec_data(&self) -> Vec<u8> {
+        match self {
+            Self::None => Vec::new(),
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => aes_key_data(&x.key, &x.secret),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => aes_key_data(&x.key, &x.secret),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4((x, _)) => {
+                use rc4::{KeyInit, StreamCipher};
+                let mut secret = x.secret;
+                Rc4::new(&x.key.into()).apply_keystream(&mut secret);
+                [secret, x.key].concat()
+            }
+        }
+    }
+}
+
+/// Encrypts the AES secret with the session key.
+#[cfg(any(feature = "base_enc", feature = "ngs_enc"))]
+fn aes_key_data(key: &[u8; 0x20], secret: &[u8]) -> Vec<u8> {
+    use aes::cipher::block_padding::NoPadding;
+    let mut data = secret.to_vec();
+    let len = data.len();
+    cbc::Encryptor::<aes::Aes256>::new(key.into(), &AES_KEY_IV.into())
+        .encrypt_padded_mut::<NoPadding>(&mut data, len)
+        .expect("secret should be block aligned");
+    data.extend_from_slice(key);
+    data
+}
+
+/// IV of the AES secret encryption.
+#[cfg(any(feature = "base_enc", feature = "ngs_enc"))]
+const AES_KEY_IV: [u8; 0x10] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+];
+
+impl Encryptor for Encryption {
+    fn encrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+        match self {
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => x.encrypt(data),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => x.encrypt(data),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4((_, x)) => x.encrypt(data),
+            Self::None => Ok(data.to_vec()),
+        }
+    }
+}
+impl Decryptor for Encryption {
+    fn decrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+        match self {
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => x.decrypt(data),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => x.decrypt(data),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4((x, _)) => x.decrypt(data),
+            Self::None => Ok(data.to_vec()),
+        }
+    }
+    fn is_rc4(&self) -> bool {
+        match self {
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => x.is_rc4(),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => x.is_rc4(),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4((x, _)) => x.is_rc4(),
+            Self::None => false,
+        }
+    }
+    fn get_len_type(&self) -> LengthType {
+        match self {
+            #[cfg(feature = "base_enc")]
+            Self::Aes(_) => LengthType::Aes,
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(_) => LengthType::Aes,
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4(_) => LengthType::Default,
+            Self::None => LengthType::Default,
+        }
+    }
+}
+
+#[cfg(feature = "split_connection")]
+impl EncryptorType {
+    pub fn get_key(&self) -> Vec<u8> {
+        match self {
+            Self::None => Vec::new(),
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => x.secret.clone(),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => x.secret.clone(),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4(x) => x.secret.to_vec(),
+        }
+    }
+}
+#[cfg(feature = "split_connection")]
+impl Encryptor for EncryptorType {
+    fn encrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+        match self {
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => x.encrypt(data),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => x.encrypt(data),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4(x) => x.encrypt(data),
+            Self::None => Ok(data.to_vec()),
+        }
+    }
+}
+
+#[cfg(feature = "split_connection")]
+impl DecryptorType {
+    pub fn get_key(&self) -> Vec<u8> {
+        match self {
+            Self::None => Vec::new(),
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => x.secret.clone(),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => x.secret.clone(),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4(x) => x.secret.to_vec(),
+        }
+    }
+}
+
+#[cfg(feature = "split_connection")]
+impl Decryptor for DecryptorType {
+    fn decrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+        match self {
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => x.decrypt(data),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => x.decrypt(data),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4(x) => x.decrypt(data),
+            Self::None => Ok(data.to_vec()),
+        }
+    }
+    fn is_rc4(&self) -> bool {
+        match self {
+            #[cfg(feature = "base_enc")]
+            Self::Aes(x) => x.is_rc4(),
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(x) => x.is_rc4(),
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4(x) => x.is_rc4(),
+            Self::None => false,
+        }
+    }
+    fn get_len_type(&self) -> LengthType {
+        match self {
+            #[cfg(feature = "base_enc")]
+            Self::Aes(_) => LengthType::Aes,
+            #[cfg(feature = "ngs_enc")]
+            Self::AesNgs(_) => LengthType::Aes,
+            #[cfg(feature = "vita_enc")]
+            Self::Rc4(_) => LengthType::Default,
+            Self::None => LengthType::Default,
+        }
+    }
+}
+
+/// Classic AES-256-CBC cipher.
+///
+/// Each packet has a random IV and is authenticated with HMAC-SHA256.
+#[cfg(feature = "base_enc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "base_enc")))]
+#[derive(Debug, Clone)]
+pub struct Aes {
+    key: [u8; 0x20],
+    secret: Vec<u8>,
+}
+#[cfg(feature = "base_enc")]
+impl Aes {
+    /// Creates a new cipher from the session key and the decrypted session secret.
+    pub fn new(key: [u8; 0x20], secret: [u8; 0x30]) -> Self {
+        Self {
+            key,
+            secret: secret.to_vec(),
+        }
+    }
+    /// Returns the session key.
+    pub fn key(&self) -> &[u8; 0x20] {
+        &self.key
+    }
+    /// Returns the session secret.
+    pub fn secret(&self) -> &[u8] {
+        &self.secret
+    }
+}
+#[cfg(feature = "base_enc")]
+impl Decryptor for Aes {
+    fn decrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        // header, IV and at least one block
+        if data.len() < 0x68 {
+            return Err(EncryptionError::UnpadError);
+        }
+        let mut iv = [0u8; 0x10];
+        iv.copy_from_slice(&data[0x48..0x58]);
+        let aes = cbc::Decryptor::<aes::Aes256>::new(&self.key.into(), &iv.into());
+        let mut data_copy = data[0x58..].to_vec();
+        let plain_data = aes
+            .decrypt_padded_mut::<Pkcs7>(&mut data_copy[..])
+            .map_err(|x| EncryptionError::UnpadError)?;
+        Ok(plain_data.to_vec())
+    }
+    fn get_len_type(&self) -> LengthType {
+        LengthType::Aes
+    }
+}
+#[cfg(feature = "base_enc")]
+impl Encryptor for Aes {
+    fn encrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        use hmac::Hmac;
+        use hmac::Mac;
+        use rand::RngCore;
+
+        let mut iv = [0u8; 0x10];
+        rand::thread_rng().fill_bytes(&mut iv);
+
+        let mut out_data = vec![0u8; 0x40];
+        out_data
+            .write_u32::<BigEndian>(0x01_00_FF_FF)
+            .expect("writing to Vec should not fail");
+
+        let mut in_data = data.to_vec();
+        let len = in_data.len();
+        in_data.resize(len + 16, 0);
+
+        let crypt_data = cbc::Encryptor::<aes::Aes256>::new(&self.key.into(), &iv.into())
+            .encrypt_padded_mut::<Pkcs7>(&mut in_data, len)
+            .map_err(|_| EncryptionError::PadError)?;
+
+        out_data
+            .write_u32::<LittleEndian>((crypt_data.len() + 0x58) as u32)
+            .expect("writing to Vec should not fail");
+        out_data.extend_from_slice(&iv);
+        out_data.extend_from_slice(crypt_data);
+
+        // hash calculation
+        let mut sha = Hmac::<Sha256>::new_from_slice(b"passwordxxxxxxxx").unwrap();
+        sha.update(&out_data[0x44..]);
+        out_data[0x20..0x40].copy_from_slice(&sha.finalize().into_bytes().to_vec()[..]);
+        let mut sha = Hmac::<Sha256>::new_from_slice(b"passwordxxxxxxxx").unwrap();
+        sha.update(&out_data[..0x58]);
+        out_data[..0x20].copy_from_slice(&sha.finalize().into_bytes().to_vec()[..]);
+
+        Ok(out_data)
+    }
+}
+
+/// NGS AES-256-CBC cipher.
+///
+/// The IV is chained between packets (separately for each direction) and large packets may be
+/// compressed with zstd.
+#[cfg(feature = "ngs_enc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ngs_enc")))]
+#[derive(Debug, Clone)]
+pub struct AesNgs {
+    iv_in: [u8; 0x10],
+    iv_out: [u8; 0x10],
+    key: [u8; 0x20],
+    secret: Vec<u8>,
+}
+#[cfg(feature = "ngs_enc")]
+impl AesNgs {
+    /// Creates a new cipher from the session key and the decrypted session secret.
+    ///
+    /// The initial IV is the first 16 bytes of the secret.
+    pub fn new(key: [u8; 0x20], secret: [u8; 0x30]) -> Self {
+        let mut iv = [0u8; 0x10];
+        iv.copy_from_slice(&secret[..0x10]);
+        Self {
+            iv_in: iv,
+            iv_out: iv,
+            key,
+            secret: secret.to_vec(),
+        }
+    }
+    /// Returns the session key.
+    pub fn key(&self) -> &[u8; 0x20] {
+        &self.key
+    }
+    /// Returns the session secret.
+    pub fn secret(&self) -> &[u8] {
+        &self.secret
+    }
+}
+#[cfg(feature = "ngs_enc")]
+impl Decryptor for AesNgs {
+    fn decrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        // header and at least one block
+        if data.len() < 0x58 {
+            return Err(EncryptionError::UnpadError);
+        }
+        let mut next_iv = [0u8; 0x10];
+        next_iv.copy_from_slice(&data[data.len() - 0x10..]);
+        let aes = cbc::Decryptor::<aes::Aes256>::new(&self.key.into(), &self.iv_in.into());
+        let mut data_copy = data[0x48..].to_vec();
+        let plain_data = aes
+            .decrypt_padded_mut::<Pkcs7>(&mut data_copy[..])
+            .map_err(|x| EncryptionError::UnpadError)?;
+        let mut ready_data = vec![];
+        if plain_data.get(1..=3) == Some(&[0xb5, 0x2f, 0xfd]) {
+            let mut unpacked_data = zstd::stream::decode_all(plain_data)
+                .map_err(|e| EncryptionError::ZSTDError { error: e })?;
+            ready_data.append(&mut unpacked_data);
+        } else {
+            ready_data.append(&mut plain_data.to_vec());
+        }
+        self.iv_in = next_iv;
+        Ok(ready_data)
+    }
+    fn get_len_type(&self) -> LengthType {
+        LengthType::Aes
+    }
+}
+#[cfg(feature = "ngs_enc")]
+impl Encryptor for AesNgs {
+    fn encrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        use sha2::Digest;
+        let mut tmp_data = vec![];
+        // if enc {
+        //     match zstd_enc(data) {
+        //         Ok(ref mut packed_data) => tmp_data.append(packed_data),
+        //         Err(e) => return Err(Error::new(ErrorKind::Other, format!("{e}"))),
+        //     }
+        // } else {
+        tmp_data.extend_from_slice(data);
+        // }
+
+        let mut out_data = vec![0u8; 0x40];
+        out_data
+            .write_u32::<BigEndian>(0x01_00_FF_FF)
+            .expect("writing to Vec should not fail");
+
+        let len = tmp_data.len();
+        tmp_data.resize(len + 16, 0);
+
+        let crypt_data = cbc::Encryptor::<aes::Aes256>::new(&self.key.into(), &self.iv_out.into())
+            .encrypt_padded_mut::<Pkcs7>(&mut tmp_data, len)
+            .map_err(|_| EncryptionError::PadError)?;
+
+        out_data
+            .write_u32::<LittleEndian>((crypt_data.len() + 0x48) as u32)
+            .expect("writing to Vec should not fail");
+        out_data.extend_from_slice(crypt_data);
+
+        // hash calculation
+        let mut sha_hasher = Sha256::new();
+        sha_hasher.update(&out_data[0x44..]);
+        out_data[0x20..0x40].copy_from_slice(&sha_hasher.finalize());
+        let mut sha_hasher = Sha256::new();
+        sha_hasher.update(&out_data[..0x48]);
+        out_data[..0x20].copy_from_slice(&sha_hasher.finalize());
+
+        self.iv_out
+            .copy_from_slice(&out_data[out_data.len() - 0x10..]);
+        Ok(out_data)
+    }
+}
+
+/// Vita RC4 encrypting cipher.
+#[cfg(feature = "vita_enc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "vita_enc")))]
+pub struct Rc4Enc {
+    encryptor: Box<Rc4<U16>>,
+    key: [u8; 0x10],
+    secret: [u8; 0x10],
+}
+#[cfg(feature = "vita_enc")]
+impl Rc4Enc {
+    /// Creates a new cipher from the session key and the decrypted session secret.
+    pub fn new(key: [u8; 0x10], secret: [u8; 0x10]) -> Self {
+        use rc4::KeyInit;
+        Self {
+            encryptor: Box::new(Rc4::new(&key.into())),
+            key,
+            secret,
+        }
+    }
+    /// Returns the session key.
+    pub fn key(&self) -> &[u8; 0x10] {
+        &self.key
+    }
+    /// Returns the session secret.
+    pub fn secret(&self) -> &[u8; 0x10] {
+        &self.secret
+    }
+}
+#[cfg(feature = "vita_enc")]
+impl Encryptor for Rc4Enc {
+    fn encrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        use rc4::StreamCipher;
+        let mut data = data.to_vec();
+        self.encryptor.apply_keystream(&mut data);
+        Ok(data)
+    }
+}
+#[cfg(feature = "vita_enc")]
+impl Debug for Rc4Enc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rc4_Enc")
+    }
+}
+
+/// Vita RC4 decrypting cipher.
+#[cfg(feature = "vita_enc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "vita_enc")))]
+pub struct Rc4Dec {
+    decryptor: Box<Rc4<U16>>,
+    key: [u8; 0x10],
+    secret: [u8; 0x10],
+}
+#[cfg(feature = "vita_enc")]
+impl Rc4Dec {
+    /// Creates a new cipher from the session key and the decrypted session secret.
+    pub fn new(key: [u8; 0x10], secret: [u8; 0x10]) -> Self {
+        use rc4::KeyInit;
+        Self {
+            decryptor: Box::new(Rc4::new(&key.into())),
+            key,
+            secret,
+        }
+    }
+    /// Returns the session key.
+    pub fn key(&self) -> &[u8; 0x10] {
+        &self.key
+    }
+    /// Returns the session secret.
+    pub fn secret(&self) -> &[u8; 0x10] {
+        &self.secret
+    }
+}
+#[cfg(feature = "vita_enc")]
+impl Decryptor for Rc4Dec {
+    fn decrypt(&mut self, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+        use rc4::StreamCipher;
+        let mut data = data.to_vec();
+        self.decryptor.apply_keystream(&mut data);
+        Ok(data)
+    }
+    fn is_rc4(&self) -> bool {
+        true
+    }
+    fn get_len_type(&self) -> LengthType {
+        LengthType::Default
+    }
+}
+#[cfg(feature = "vita_enc")]
+impl Debug for Rc4Dec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Rc4_Dec")
+    }
+}
+
+/// Session key data for the client side of the encryption handshake.
+///
+/// # Usage
+/// ```no_run
+/// # use pso2packetlib::connection::codec::Codec;
+/// # use pso2packetlib::crypto::SessionKey;
+/// # use pso2packetlib::protocol::{Packet, PacketType};
+/// # use pso2packetlib::{PrivateKey, PublicKey};
+/// # #[cfg(not(feature = "ngs_enc"))]
+/// # fn main() {}
+/// # #[cfg(feature = "ngs_enc")]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let received_data = vec![];
+/// let key = PublicKey::Path("server_pubkey.pem".into());
+/// let mut codec = Codec::<Packet>::new(PacketType::NGS, PrivateKey::None, key);
+/// let session_key = SessionKey::generate(PacketType::NGS)?;
+/// // the codec encrypts the key data with the server's key
+/// let data = codec.encode(&Packet::EncryptionRequest(session_key.request()))?;
+/// // send the data and receive the response
+/// codec.feed(&received_data)?;
+/// if let Some(Packet::EncryptionResponse(response)) = codec.decode()? {
+///     session_key.verify_response(&response)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionKey {
+    packet_type: PacketType,
+    dec_data: Vec<u8>,
+}
+
+impl SessionKey {
+    /// Generates a new random session key for the packet type.
+    ///
+    /// Vita clients use RC4 key data, other clients use AES key data.
+    ///
+    /// # Errors
+    /// Returns [`EncryptionError::UnsupportedPacketType`] if the cipher of the packet type is not
+    /// enabled (`vita_enc` for Vita, `ngs_enc` for NGS and `base_enc` for other packet types).
+    #[cfg(any(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc"))]
+    #[cfg_attr(
+        docsrs,
+        doc(cfg(any(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc")))
+    )]
+    pub fn generate(packet_type: PacketType) -> Result<Self, EncryptionError> {
+        use rand::RngCore;
+
+        // the arms are constant for a feature set
+        #[allow(clippy::match_like_matches_macro)]
+        let is_supported = match packet_type {
+            PacketType::Vita => cfg!(feature = "vita_enc"),
+            PacketType::NGS => cfg!(feature = "ngs_enc"),
+            _ => cfg!(feature = "base_enc"),
+        };
+        if !is_supported {
+            return Err(EncryptionError::UnsupportedPacketType(packet_type));
+        }
+        let mut rng = rand::thread_rng();
+        let dec_data = match packet_type {
+            PacketType::Vita => {
+                // [encrypted secret; 0x10] [RC4 key; 0x10], any secret is valid
+                let mut data = vec![0u8; 0x20];
+                rng.fill_bytes(&mut data);
+                data
+            }
+            #[cfg(any(feature = "base_enc", feature = "ngs_enc"))]
+            _ => {
+                let mut key = [0u8; 0x20];
+                rng.fill_bytes(&mut key);
+                let mut secret = [0x10u8; 0x30];
+                rng.fill_bytes(&mut secret[..0x20]);
+                aes_key_data(&key, &secret)
+            }
+            #[cfg(not(any(feature = "base_enc", feature = "ngs_enc")))]
+            _ => return Err(EncryptionError::UnsupportedPacketType(packet_type)),
+        };
+        Ok(Self {
+            packet_type,
+            dec_data,
+        })
+    }
+
+    /// Creates a session key from existing key data (see [`Encryption::to_dec_data`]).
+    pub fn from_dec_data(packet_type: PacketType, dec_data: Vec<u8>) -> Self {
+        Self {
+            packet_type,
+            dec_data,
+        }
+    }
+
+    /// Returns the packet type of the session.
+    pub fn packet_type(&self) -> PacketType {
+        self.packet_type
+    }
+
+    /// Returns the unencrypted key data.
+    pub fn dec_data(&self) -> &[u8] {
+        &self.dec_data
+    }
+
+    /// Creates the session cipher.
+    pub fn encryption(&self) -> Result<Encryption, EncryptionError> {
+        Encryption::from_dec_data(&self.dec_data, self.packet_type == PacketType::NGS)
+    }
+
+    /// Returns the session secret expected in [`EncryptionResponsePacket`].
+    ///
+    /// This is the same value as returned by
+    /// [`Connection::get_key`](crate::Connection::get_key) on the client side after the request
+    /// is sent.
+    pub fn secret(&self) -> Result<Vec<u8>, EncryptionError> {
+        Ok(self.encryption()?.get_key())
+    }
+
+    /// Creates an encryption request with the unencrypted key data.
+    ///
+    /// The key data is encrypted when the packet is sent by a
+    /// [`Connection`](crate::Connection) with the server's public key.
+    pub fn request(&self) -> EncryptionRequestPacket {
+        EncryptionRequestPacket {
+            rsa_data: self.dec_data.clone().into(),
+        }
+    }
+
+    /// Creates an encryption request with the key data encrypted by the server's public key.
+    pub fn encrypted_request(
+        &self,
+        key: &PublicKey,
+    ) -> Result<EncryptionRequestPacket, EncryptionError> {
+        Ok(EncryptionRequestPacket {
+            rsa_data: encrypt(&self.dec_data, key)?.into(),
+        })
+    }
+
+    /// Checks that the server's response contains the session secret.
+    pub fn verify_response(
+        &self,
+        response: &EncryptionResponsePacket,
+    ) -> Result<(), EncryptionError> {
+        if *response.data == self.secret()? {
+            Ok(())
+        } else {
+            Err(EncryptionError::SecretMismatch)
+        }
+    }
+}
+
+/// Encrypts the key data for [`Packet::EncryptionRequest`] with the server's RSA key.
+///
+/// If no encryption features are enabled, then the data is returned as is.
+///
+/// [`Packet::EncryptionRequest`]: crate::protocol::Packet::EncryptionRequest
+#[cfg(any(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc"))]
+pub fn encrypt(packet: &[u8], out_key: &PublicKey) -> Result<Vec<u8>, EncryptionError> {
+    let out_key = match out_key.into_key()? {
+        Some(x) => x,
+        None => {
+            return Err(EncryptionError::NoPublicKey);
+        }
+    };
+    let enc_data = out_key.encrypt(&mut rand::thread_rng(), Pkcs1v15Encrypt, packet)?;
+    Ok(enc_data)
+}
+
+/// Encrypts the key data for [`Packet::EncryptionRequest`] with the server's RSA key.
+///
+/// If no encryption features are enabled, then the data is returned as is.
+///
+/// [`Packet::EncryptionRequest`]: crate::protocol::Packet::EncryptionRequest
+#[cfg(not(any(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc")))]
+pub fn encrypt(packet: &[u8], _: &PublicKey) -> Result<Vec<u8>, EncryptionError> {
+    Ok(packet.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::{Decryptor, Encryption, Encryptor, LengthType};
+
+    #[cfg(feature = "ngs_enc")]
+    #[test]
+    fn aes_ngs_vector() {
+        use super::AesNgs;
+        // NIST SP 800-38A F.2.5 (CBC-AES256)
+        let key: [u8; 0x20] = [
+            0x60, 0x3D, 0xEB, 0x10, 0x15, 0xCA, 0x71, 0xBE, 0x2B, 0x73, 0xAE, 0xF0, 0x85, 0x7D,
+            0x77, 0x81, 0x1F, 0x35, 0x2C, 0x07, 0x3B, 0x61, 0x08, 0xD7, 0x2D, 0x98, 0x10, 0xA3,
+            0x09, 0x14, 0xDF, 0xF4,
+        ];
+        let mut secret = [0u8; 0x30];
+        secret[..0x10].copy_from_slice(&core::array::from_fn::<u8, 0x10, _>(|i| i as u8));
+        let plain = [
+            0x6B, 0xC1, 0xBE, 0xE2, 0x2E, 0x40, 0x9F, 0x96, 0xE9, 0x3D, 0x7E, 0x11, 0x73, 0x93,
+            0x17, 0x2A,
+        ];
+        let cipher = [
+            0xF5, 0x8C, 0x4C, 0x04, 0xD6, 0xE5, 0xF1, 0xBA, 0x77, 0x9E, 0xAB, 0xFB, 0x5F, 0x7B,
+            0xFB, 0xD6,
+        ];
+        let mut enc = AesNgs::new(key, secret);
+        let data = enc.encrypt(&plain).unwrap();
+        assert_eq!(data.len(), 0x48 + 0x20);
+        assert_eq!(data[0x44..0x48], (data.len() as u32).to_le_bytes());
+        assert_eq!(data[0x48..0x58], cipher);
+
+        let mut dec = AesNgs::new(key, secret);
+        assert_eq!(dec.get_len_type(), LengthType::Aes);
+        assert_eq!(dec.decrypt(&data).unwrap(), plain);
+        // IV is chained
+        let data = enc.encrypt(&plain).unwrap();
+        assert_ne!(data[0x48..0x58], cipher);
+        assert_eq!(dec.decrypt(&data).unwrap(), plain);
+    }
+
+    #[cfg(all(feature = "base_enc", feature = "ngs_enc"))]
+    #[test]
+    fn aes_short_data() {
+        use super::{Aes, AesNgs};
+        let data = [0u8; 0x100];
+        for len in 0..data.len() {
+            // must not panic
+            let _ = Aes::new([0x11; 0x20], [0x22; 0x30]).decrypt(&data[..len]);
+            let _ = AesNgs::new([0x11; 0x20], [0x22; 0x30]).decrypt(&data[..len]);
+        }
+    }
+
+    #[cfg(feature = "base_enc")]
+    #[test]
+    fn aes_round_trip() {
+        use super::Aes;
+        let mut cipher = Aes::new([0x11; 0x20], [0x22; 0x30]);
+        let data = cipher.encrypt(b"test packet data").unwrap();
+        assert_eq!(data[0x44..0x48], (data.len() as u32).to_le_bytes());
+        assert_eq!(cipher.decrypt(&data).unwrap(), b"test packet data");
+    }
+
+    #[cfg(feature = "vita_enc")]
+    #[test]
+    fn rc4_vector() {
+        use super::{Rc4Dec, Rc4Enc};
+        // RFC 6229 (128-bit key, offset 0)
+        let key = core::array::from_fn(|i| i as u8 + 1);
+        let keystream = [
+            0x9A, 0xC7, 0xCC, 0x9A, 0x60, 0x9D, 0x1E, 0xF7, 0xB2, 0x93, 0x28, 0x99, 0xCD, 0xE4,
+            0x1B, 0x97,
+        ];
+        let mut enc = Rc4Enc::new(key, [0; 0x10]);
+        assert_eq!(enc.encrypt(&[0; 0x10]).unwrap(), keystream);
+        let mut dec = Rc4Dec::new(key, [0; 0x10]);
+        assert!(dec.is_rc4());
+        assert_eq!(dec.decrypt(&keystream[..5]).unwrap(), [0; 5]);
+        assert_eq!(dec.decrypt(&keystream[5..]).unwrap(), [0; 0x0B]);
+    }
+
+    #[cfg(all(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc"))]
+    #[test]
+    fn key_data_round_trip() {
+        // secret must have valid PKCS#7 padding
+        let aes_data = Encryption::Aes(super::Aes::new([0x42; 0x20], [0x10; 0x30])).to_dec_data();
+        assert_eq!(aes_data.len(), 0x50);
+        for is_ngs in [false, true] {
+            let enc = Encryption::from_dec_data(&aes_data, is_ngs).unwrap();
+            assert_eq!(enc.get_key(), [0x10; 0x30]);
+            assert_eq!(enc.to_dec_data(), aes_data);
+        }
+
+        let rc4_data: Vec<u8> = (0..0x20).collect();
+        let enc = Encryption::from_dec_data(&rc4_data, false).unwrap();
+        assert!(enc.is_rc4());
+        assert_eq!(enc.to_dec_data(), rc4_data);
+    }
+
+    #[test]
+    fn key_data_length() {
+        use super::EncryptionError;
+        let data = [0x10; 0x60];
+        for len in (0..0x60).filter(|len| !matches!(len, 0x20 | 0x50)) {
+            for is_ngs in [false, true] {
+                assert!(matches!(
+                    Encryption::from_dec_data(&data[..len], is_ngs),
+                    Err(EncryptionError::InvalidKeyLength(l)) if l == len
+                ));
+            }
+        }
+    }
+
+    #[cfg(any(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc"))]
+    #[test]
+    fn session_key_features() {
+        use super::{EncryptionError, SessionKey};
+        use crate::protocol::PacketType;
+
+        let types = [
+            (PacketType::NGS, cfg!(feature = "ngs_enc")),
+            (PacketType::NA, cfg!(feature = "base_enc")),
+            (PacketType::Vita, cfg!(feature = "vita_enc")),
+        ];
+        for (packet_type, is_enabled) in types {
+            match SessionKey::generate(packet_type) {
+                Ok(key) => {
+                    assert!(is_enabled, "{packet_type:?}");
+                    assert!(!matches!(key.encryption().unwrap(), Encryption::None));
+                }
+                Err(EncryptionError::UnsupportedPacketType(_)) => assert!(!is_enabled),
+                Err(e) => panic!("{e}"),
+            }
+        }
+    }
+
+    #[cfg(all(feature = "base_enc", feature = "ngs_enc", feature = "vita_enc"))]
+    #[test]
+    fn session_key_handshake() {
+        use super::{EncryptionError, SessionKey};
+        use crate::{
+            connection::codec::Codec,
+            protocol::{login::EncryptionResponsePacket, Packet, PacketType},
+            PrivateKey, PublicKey,
+        };
+
+        let key = rsa::RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        for packet_type in [PacketType::NGS, PacketType::NA, PacketType::Vita] {
+            let session_key = SessionKey::generate(packet_type).unwrap();
+            let public_key = PublicKey::Key(key.to_public_key());
+            let mut client = Codec::<Packet>::new(packet_type, PrivateKey::None, public_key);
+            let private_key = PrivateKey::Key(key.clone());
+            let mut server = Codec::<Packet>::new(packet_type, private_key, PublicKey::None);
+
+            let data = client
+                .encode(&Packet::EncryptionRequest(session_key.request()))
+                .unwrap();
+            assert_eq!(client.get_key(), session_key.secret().unwrap());
+            server.feed(&data).unwrap();
+            let Some(Packet::EncryptionRequest(request)) = server.decode().unwrap() else {
+                panic!("expected encryption request");
+            };
+            assert_eq!(*request.rsa_data, session_key.dec_data());
+
+            let response = EncryptionResponsePacket {
+                data: server.get_key().into(),
+            };
+            let data = server
+                .encode(&Packet::EncryptionResponse(response))
+                .unwrap();
+            client.feed(&data).unwrap();
+            let Some(Packet::EncryptionResponse(response)) = client.decode().unwrap() else {
+                panic!("expected encryption response");
+            };
+            session_key.verify_response(&response).unwrap();
+
+            let other = SessionKey::generate(packet_type).unwrap();
+            assert!(matches!(
+                other.verify_response(&response),
+                Err(EncryptionError::SecretMismatch)
+            ));
+        }
+    }
+}
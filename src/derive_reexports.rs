@@ -1,9 +1,14 @@
 pub use crate::{
     asciistring::{AsciiString, StringRW},
     protocol::{
-        read_magic, write_magic, Flags, HelperReadWrite, PacketHeader, PacketReadWrite, PacketType,
+        partial::{TraceDebug, TraceOpaque, TraceValue},
+        read_magic, write_length_prefixed, write_magic, FieldCondition, FieldSchema, FlagSchema,
+        Flags, HelperReadWrite, PacketCategory, PacketEntry, PacketHeader, PacketReadWrite,
+        PacketRegistry, PacketSchema, PacketType, PacketView, TypeDesc, TypeKind, TypeSchema,
+        VariantSchema, WriteCounter,
     },
 };
 pub use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 pub use half::f16;
 pub use std::io::{Read, Write};
+pub use std::sync::OnceLock;
@@ -1,10 +1,14 @@
 use crate::{
     asciistring::StringRW,
-    protocol::{read_magic, write_magic, HelperReadWrite, PacketError},
+    protocol::{
+        default_types::skip_bytes, read_magic, write_magic, DeviationKind, HelperReadWrite,
+        PacketError, ProtocolVersion, ReadContext, TypeDesc,
+    },
     AsciiString,
 };
 use std::{
     fmt::Display,
+    io::Read,
     marker::PhantomData,
     ops::{Deref, DerefMut},
     time::Duration,
@@ -18,6 +22,7 @@ use std::{
 #[derive(Clone, Default, Debug, Hash, PartialEq, PartialOrd)]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[cfg_attr(feature = "serde", serde(transparent))]
 pub struct FixedString<const N: usize> {
@@ -29,6 +34,7 @@ pub struct FixedString<const N: usize> {
 #[derive(Clone, Default, Debug, Hash, PartialEq, PartialOrd)]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[cfg_attr(feature = "serde", serde(transparent))]
 pub struct FixedAsciiString<const N: usize> {
@@ -40,6 +46,7 @@ pub struct FixedAsciiString<const N: usize> {
 #[derive(Clone, Default, Debug, Hash, PartialEq, PartialOrd)]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[cfg_attr(feature = "serde", serde(transparent))]
 pub struct WinTime {
@@ -50,6 +57,7 @@ pub struct WinTime {
 #[derive(Clone, Default, Debug, Hash, PartialEq, PartialOrd)]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[cfg_attr(feature = "serde", serde(transparent))]
 pub struct FixedVec<const N: usize, T> {
@@ -65,6 +73,7 @@ trait SizeProvider {
 /// Opaque type for integer sized array (with no magic).
 #[derive(Clone, Default, Debug, Hash, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[cfg_attr(feature = "serde", serde(transparent))]
 pub struct VecUSize<S, T> {
@@ -77,6 +86,7 @@ pub struct VecUSize<S, T> {
 #[derive(Clone, Default, Debug, Hash, PartialEq, PartialOrd)]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Bytes<const NO_PADDING: bool = false> {
@@ -87,6 +97,7 @@ pub struct Bytes<const NO_PADDING: bool = false> {
 #[derive(Clone, Default, Debug, Hash, PartialEq, PartialOrd)]
 #[repr(transparent)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[cfg_attr(feature = "serde", serde(transparent))]
 pub struct FixedBytes<const N: usize, const NO_PADDING: bool = false> {
@@ -158,6 +169,47 @@ impl<const N: usize> HelperReadWrite for FixedString<N> {
         })
     }
 
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, crate::protocol::PacketError> {
+        let start = ctx.position(reader).map_err(|e| PacketError::FieldError {
+            packet_name: "FixedString",
+            field_name: "str",
+            error: e,
+        })?;
+        let string = Self::read(reader, packet_type, xor, sub)?;
+        ctx.check_field(reader, start, DeviationKind::String, || {
+            StringRW::write_fixed(&string.string, N as _)
+        })
+        .map_err(|e| PacketError::FieldError {
+            packet_name: "FixedString",
+            field_name: "str",
+            error: e,
+        })?;
+        Ok(string)
+    }
+
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        _: crate::protocol::PacketType,
+        _: u32,
+        _: u32,
+        _: &mut ReadContext,
+    ) -> Result<(), crate::protocol::PacketError> {
+        reader
+            .seek(std::io::SeekFrom::Current(N as i64 * 2))
+            .map_err(|e| PacketError::FieldError {
+                packet_name: "FixedString",
+                field_name: "str",
+                error: e,
+            })?;
+        Ok(())
+    }
+
     fn write(
         &self,
         writer: &mut impl std::io::Write,
@@ -173,6 +225,14 @@ impl<const N: usize> HelperReadWrite for FixedString<N> {
                 error: e,
             })
     }
+
+    fn serialized_len(&self, _: crate::protocol::PacketType) -> usize {
+        N * 2
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::FixedString { n: N }
+    }
 }
 #[cfg(feature = "serde")]
 impl<'de, const N: usize> serde::Deserialize<'de> for FixedString<N> {
@@ -231,6 +291,47 @@ impl<const N: usize> HelperReadWrite for FixedAsciiString<N> {
         })
     }
 
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, crate::protocol::PacketError> {
+        let start = ctx.position(reader).map_err(|e| PacketError::FieldError {
+            packet_name: "FixedAsciiString",
+            field_name: "str",
+            error: e,
+        })?;
+        let string = Self::read(reader, packet_type, xor, sub)?;
+        ctx.check_field(reader, start, DeviationKind::String, || {
+            string.string.write_fixed(N as _)
+        })
+        .map_err(|e| PacketError::FieldError {
+            packet_name: "FixedAsciiString",
+            field_name: "str",
+            error: e,
+        })?;
+        Ok(string)
+    }
+
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        _: crate::protocol::PacketType,
+        _: u32,
+        _: u32,
+        _: &mut ReadContext,
+    ) -> Result<(), crate::protocol::PacketError> {
+        reader
+            .seek(std::io::SeekFrom::Current(N as i64))
+            .map_err(|e| PacketError::FieldError {
+                packet_name: "FixedAsciiString",
+                field_name: "str",
+                error: e,
+            })?;
+        Ok(())
+    }
+
     fn write(
         &self,
         writer: &mut impl std::io::Write,
@@ -246,6 +347,14 @@ impl<const N: usize> HelperReadWrite for FixedAsciiString<N> {
                 error: e,
             })
     }
+
+    fn serialized_len(&self, _: crate::protocol::PacketType) -> usize {
+        N
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::FixedAsciiString { n: N }
+    }
 }
 #[cfg(feature = "serde")]
 impl<'de, const N: usize> serde::Deserialize<'de> for FixedAsciiString<N> {
@@ -284,13 +393,13 @@ impl HelperReadWrite for WinTime {
     ) -> Result<Self, crate::protocol::PacketError> {
         Ok(Self {
             time: Duration::from_millis(
-                u64::read(reader, packet_type, 0, 0).map_err(|e| {
-                    PacketError::CompositeFieldError {
+                u64::read(reader, packet_type, 0, 0)
+                    .map_err(|e| PacketError::CompositeFieldError {
                         packet_name: "WinTime",
                         field_name: "time",
                         error: e.into(),
-                    }
-                })? - WIN_FT_TIME_TO_TIMESTAMP,
+                    })?
+                    .wrapping_sub(WIN_FT_TIME_TO_TIMESTAMP),
             ),
         })
     }
@@ -302,7 +411,8 @@ impl HelperReadWrite for WinTime {
         _: u32,
         _: u32,
     ) -> Result<(), crate::protocol::PacketError> {
-        (self.time.as_millis() as u64 + WIN_FT_TIME_TO_TIMESTAMP)
+        (self.time.as_millis() as u64)
+            .wrapping_add(WIN_FT_TIME_TO_TIMESTAMP)
             .write(writer, packet_type, 0, 0)
             .map_err(|e| PacketError::CompositeFieldError {
                 packet_name: "WinTime",
@@ -310,6 +420,14 @@ impl HelperReadWrite for WinTime {
                 error: e.into(),
             })
     }
+
+    fn serialized_len(&self, _: crate::protocol::PacketType) -> usize {
+        8
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::WinTime
+    }
 }
 
 impl<const N: usize, T> Deref for FixedVec<N, T> {
@@ -340,22 +458,51 @@ impl<const N: usize, T: HelperReadWrite + Default> HelperReadWrite for FixedVec<
         packet_type: crate::protocol::PacketType,
         xor: u32,
         sub: u32,
+    ) -> Result<Self, crate::protocol::PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, &mut Default::default())
+    }
+
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
     ) -> Result<Self, crate::protocol::PacketError> {
         let mut data = vec![];
         data.reserve_exact(N);
 
-        for _ in 0..N {
-            data.push(T::read(reader, packet_type, xor, sub).map_err(|e| {
-                PacketError::CompositeFieldError {
-                    packet_name: "FixedVec",
-                    field_name: "value",
-                    error: e.into(),
-                }
-            })?);
+        for i in 0..N {
+            data.push(
+                T::read_limited(reader, packet_type, xor, sub, ctx).map_err(|e| {
+                    PacketError::ElementError {
+                        packet_name: "FixedVec",
+                        index: i,
+                        error: e.into(),
+                    }
+                })?,
+            );
         }
         Ok(Self { data })
     }
 
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<(), crate::protocol::PacketError> {
+        for i in 0..N {
+            T::skip(reader, packet_type, xor, sub, ctx).map_err(|e| PacketError::ElementError {
+                packet_name: "FixedVec",
+                index: i,
+                error: e.into(),
+            })?;
+        }
+        Ok(())
+    }
+
     fn write(
         &self,
         writer: &mut impl std::io::Write,
@@ -363,11 +510,26 @@ impl<const N: usize, T: HelperReadWrite + Default> HelperReadWrite for FixedVec<
         xor: u32,
         sub: u32,
     ) -> Result<(), crate::protocol::PacketError> {
-        for i in self.iter().chain(std::iter::repeat(&T::default())).take(N) {
-            i.write(writer, packet_type, xor, sub).map_err(|e| {
-                PacketError::CompositeFieldError {
+        self.write_versioned(writer, &ProtocolVersion::latest(packet_type), xor, sub)
+    }
+
+    fn write_versioned(
+        &self,
+        writer: &mut impl std::io::Write,
+        version: &ProtocolVersion,
+        xor: u32,
+        sub: u32,
+    ) -> Result<(), crate::protocol::PacketError> {
+        for (index, i) in self
+            .iter()
+            .chain(std::iter::repeat(&T::default()))
+            .take(N)
+            .enumerate()
+        {
+            i.write_versioned(writer, version, xor, sub).map_err(|e| {
+                PacketError::ElementError {
                     packet_name: "FixedVec",
-                    field_name: "value",
+                    index,
                     error: e.into(),
                 }
             })?;
@@ -375,6 +537,22 @@ impl<const N: usize, T: HelperReadWrite + Default> HelperReadWrite for FixedVec<
 
         Ok(())
     }
+
+    fn serialized_len(&self, packet_type: crate::protocol::PacketType) -> usize {
+        let len: usize = self
+            .iter()
+            .take(N)
+            .map(|i| i.serialized_len(packet_type))
+            .sum();
+        len + N.saturating_sub(self.len()) * T::default().serialized_len(packet_type)
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::FixedVec {
+            n: N,
+            of: Box::new(T::type_desc()),
+        }
+    }
 }
 #[cfg(feature = "serde")]
 impl<'de, const N: usize, T: serde::Deserialize<'de>> serde::Deserialize<'de> for FixedVec<N, T> {
@@ -417,12 +595,23 @@ impl<S: SizeProvider, T: HelperReadWrite> HelperReadWrite for VecUSize<S, T> {
         packet_type: crate::protocol::PacketType,
         xor: u32,
         sub: u32,
+    ) -> Result<Self, crate::protocol::PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, &mut Default::default())
+    }
+
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
     ) -> Result<Self, crate::protocol::PacketError> {
         let len = S::to_size(reader).map_err(|e| PacketError::CompositeFieldError {
             packet_name: "VecUSize",
             field_name: "len",
             error: e.into(),
         })?;
+        ctx.allocate(len as usize, std::mem::size_of::<T>())?;
         let mut data = vec![];
         data.reserve_exact(len as usize);
 
@@ -433,14 +622,16 @@ impl<S: SizeProvider, T: HelperReadWrite> HelperReadWrite for VecUSize<S, T> {
         //         field_name: "pre_read",
         //         error: e,
         //     })?;
-        for _ in 0..len {
-            data.push(T::read(reader, packet_type, xor, sub).map_err(|e| {
-                PacketError::CompositeFieldError {
-                    packet_name: "VecUSize",
-                    field_name: "value",
-                    error: e.into(),
-                }
-            })?);
+        for i in 0..len as usize {
+            data.push(
+                T::read_limited(reader, packet_type, xor, sub, ctx).map_err(|e| {
+                    PacketError::ElementError {
+                        packet_name: "VecUSize",
+                        index: i,
+                        error: e.into(),
+                    }
+                })?,
+            );
         }
         // let seek2 = reader
         //     .stream_position()
@@ -465,12 +656,46 @@ impl<S: SizeProvider, T: HelperReadWrite> HelperReadWrite for VecUSize<S, T> {
         })
     }
 
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<(), crate::protocol::PacketError> {
+        let len = S::to_size(reader).map_err(|e| PacketError::CompositeFieldError {
+            packet_name: "VecUSize",
+            field_name: "len",
+            error: e.into(),
+        })?;
+        // nothing is allocated, but the element count is still limited
+        ctx.allocate(len as usize, 0)?;
+        for i in 0..len as usize {
+            T::skip(reader, packet_type, xor, sub, ctx).map_err(|e| PacketError::ElementError {
+                packet_name: "VecUSize",
+                index: i,
+                error: e.into(),
+            })?;
+        }
+        Ok(())
+    }
+
     fn write(
         &self,
         writer: &mut impl std::io::Write,
         packet_type: crate::protocol::PacketType,
         xor: u32,
         sub: u32,
+    ) -> Result<(), crate::protocol::PacketError> {
+        self.write_versioned(writer, &ProtocolVersion::latest(packet_type), xor, sub)
+    }
+
+    fn write_versioned(
+        &self,
+        writer: &mut impl std::io::Write,
+        version: &ProtocolVersion,
+        xor: u32,
+        sub: u32,
     ) -> Result<(), crate::protocol::PacketError> {
         writer
             .write_all(&S::to_data(self.data.len()))
@@ -480,14 +705,13 @@ impl<S: SizeProvider, T: HelperReadWrite> HelperReadWrite for VecUSize<S, T> {
                 error: e,
             })?;
         let mut buf = vec![];
-        for i in self.iter() {
-            i.write(&mut buf, packet_type, xor, sub).map_err(|e| {
-                PacketError::CompositeFieldError {
+        for (index, i) in self.iter().enumerate() {
+            i.write_versioned(&mut buf, version, xor, sub)
+                .map_err(|e| PacketError::ElementError {
                     packet_name: "VecUSize",
-                    field_name: "value",
+                    index,
                     error: e.into(),
-                }
-            })?;
+                })?;
         }
         // let len = buf.len();
         writer
@@ -507,6 +731,18 @@ impl<S: SizeProvider, T: HelperReadWrite> HelperReadWrite for VecUSize<S, T> {
 
         Ok(())
     }
+
+    fn serialized_len(&self, packet_type: crate::protocol::PacketType) -> usize {
+        let len: usize = self.iter().map(|i| i.serialized_len(packet_type)).sum();
+        std::mem::size_of::<S>() + len
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::VecUSize {
+            size: std::mem::size_of::<S>(),
+            of: Box::new(T::type_desc()),
+        }
+    }
 }
 
 impl<const NO_PADDING: bool> Deref for Bytes<NO_PADDING> {
@@ -533,24 +769,68 @@ impl<const NO_PADDING: bool> From<Bytes<NO_PADDING>> for Vec<u8> {
 }
 impl<const NO_PADDING: bool> HelperReadWrite for Bytes<NO_PADDING> {
     fn read(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+    ) -> Result<Self, crate::protocol::PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, &mut Default::default())
+    }
+
+    fn read_limited(
         reader: &mut (impl std::io::Read + std::io::Seek),
         _: crate::protocol::PacketType,
         xor: u32,
         sub: u32,
+        ctx: &mut ReadContext,
     ) -> Result<Self, crate::protocol::PacketError> {
-        let len = read_magic(reader, sub, xor).map_err(|e| PacketError::FieldLengthError {
-            packet_name: "Bytes",
-            field_name: "len",
-            error: e,
-        })?;
-        let mut bytes = vec![0; len as usize];
+        let len = ctx
+            .read_len(reader, sub, xor)
+            .map_err(|e| PacketError::FieldLengthError {
+                packet_name: "Bytes",
+                field_name: "len",
+                error: e,
+            })? as usize;
+        ctx.allocate(len, 1)?;
+        // only allocate as much as the reader actually contains
+        let mut bytes = vec![];
         reader
-            .read_exact(&mut bytes)
+            .by_ref()
+            .take(len as u64)
+            .read_to_end(&mut bytes)
+            .and_then(|read| match read == len {
+                true => Ok(()),
+                false => Err(std::io::ErrorKind::UnexpectedEof.into()),
+            })
             .map_err(|e| PacketError::FieldError {
                 packet_name: "Bytes",
                 field_name: "bytes",
                 error: e,
             })?;
+        if !NO_PADDING {
+            ctx.skip_padding(reader, (len.next_multiple_of(4) - len) as u64)
+                .map_err(|e| PacketError::PaddingError {
+                    packet_name: "Bytes",
+                    field_name: "padding",
+                    error: e,
+                })?;
+        }
+        Ok(Self { bytes })
+    }
+
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        _: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        _: &mut ReadContext,
+    ) -> Result<(), crate::protocol::PacketError> {
+        let len = read_magic(reader, sub, xor).map_err(|e| PacketError::FieldLengthError {
+            packet_name: "Bytes",
+            field_name: "len",
+            error: e,
+        })? as usize;
+        skip_bytes(reader, len as u64, "Bytes", "bytes")?;
         if !NO_PADDING {
             reader
                 .seek(std::io::SeekFrom::Current(
@@ -562,7 +842,7 @@ impl<const NO_PADDING: bool> HelperReadWrite for Bytes<NO_PADDING> {
                     error: e,
                 })?;
         }
-        Ok(Self { bytes })
+        Ok(())
     }
 
     fn write(
@@ -599,6 +879,18 @@ impl<const NO_PADDING: bool> HelperReadWrite for Bytes<NO_PADDING> {
 
         Ok(())
     }
+
+    fn serialized_len(&self, _: crate::protocol::PacketType) -> usize {
+        let len = self.bytes.len();
+        match NO_PADDING {
+            true => 4 + len,
+            false => 4 + len.next_multiple_of(4),
+        }
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::Bytes { pad: !NO_PADDING }
+    }
 }
 #[cfg(feature = "serde")]
 impl<'de, const NO_PADDING: bool> serde::Deserialize<'de> for Bytes<NO_PADDING> {
@@ -661,6 +953,28 @@ impl<const N: usize, const NO_PADDING: bool> HelperReadWrite for FixedBytes<N, N
         Ok(Self { bytes })
     }
 
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        _: crate::protocol::PacketType,
+        _: u32,
+        _: u32,
+        _: &mut ReadContext,
+    ) -> Result<(), crate::protocol::PacketError> {
+        skip_bytes(reader, N as u64, "FixedBytes", "bytes")?;
+        if !NO_PADDING {
+            reader
+                .seek(std::io::SeekFrom::Current(
+                    (N.next_multiple_of(4) - N) as i64,
+                ))
+                .map_err(|e| PacketError::PaddingError {
+                    packet_name: "FixedBytes",
+                    field_name: "padding",
+                    error: e,
+                })?;
+        }
+        Ok(())
+    }
+
     fn write(
         &self,
         writer: &mut impl std::io::Write,
@@ -697,6 +1011,20 @@ impl<const N: usize, const NO_PADDING: bool> HelperReadWrite for FixedBytes<N, N
         }
         Ok(())
     }
+
+    fn serialized_len(&self, _: crate::protocol::PacketType) -> usize {
+        match NO_PADDING {
+            true => N,
+            false => N.next_multiple_of(4),
+        }
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::FixedBytes {
+            n: N,
+            pad: !NO_PADDING,
+        }
+    }
 }
 #[cfg(feature = "serde")]
 impl<'de, const N: usize, const NO_PADDING: bool> serde::Deserialize<'de>
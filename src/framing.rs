@@ -0,0 +1,33 @@
+//! Splitting of received data into packets.
+
+use crate::protocol::{PacketError, ParseLimits};
+
+/// Checks the length of a received packet before it is buffered.
+pub(crate) fn check_length(len: usize, limits: &ParseLimits) -> Result<(), PacketError> {
+    if len < 8 {
+        return Err(PacketError::PacketLengthError {
+            error: std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid packet length {len}"),
+            ),
+        });
+    }
+    limits.check_packet_size(len)
+}
+
+/// Reads and checks the length of the first packet in the buffer. `offset` is the location of the
+/// length field (0 for plain or RC4 data, 0x44 for AES data).
+///
+/// Returns `None` if the length field isn't fully buffered yet.
+pub(crate) fn frame_length(
+    buffer: &[u8],
+    offset: usize,
+    limits: &ParseLimits,
+) -> Result<Option<usize>, PacketError> {
+    let Some(len) = buffer.get(offset..offset + 4) else {
+        return Ok(None);
+    };
+    let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+    check_length(len, limits)?;
+    Ok(Some(len))
+}
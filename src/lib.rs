@@ -6,12 +6,22 @@ pub mod asciistring;
 #[cfg(feature = "connection")]
 pub mod connection;
 #[cfg(feature = "connection")]
-pub(crate) mod encryption;
+#[cfg_attr(docsrs, doc(cfg(feature = "connection")))]
+pub mod crypto;
 pub mod fixed_types;
+#[cfg(any(feature = "connection", feature = "pcap"))]
+mod framing;
 #[cfg(feature = "ppac")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ppac")))]
 pub mod ppac;
+#[cfg(feature = "pcap")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pcap")))]
+pub mod pcap;
 pub mod protocol;
+#[cfg(feature = "proxy")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proxy")))]
+pub mod proxy;
+pub mod router;
 
 #[doc(hidden)]
 pub mod derive_reexports;
@@ -43,6 +53,10 @@ pub use asciistring::AsciiString;
 /// - `#[JP]` marks the packet as JP classic only.
 /// - `#[Vita]` marks the packet as Vita only.
 /// - `#[Category(_category_)]` sets the category of all the packets following this attribute.
+/// - `#[Since(_build_)]` marks the packet as present only since the specified game build (see
+///   [`protocol::ProtocolVersion`]).
+/// - `#[Until(_build_)]` marks the packet as present only before the specified game build. Packets
+///   with the same ID can be defined for different builds this way.
 #[cfg(feature = "derive")]
 #[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
 pub use pso2packetlib_impl::ProtocolRW;
@@ -70,6 +84,10 @@ pub use pso2packetlib_impl::ProtocolRW;
 ///   the reader packet type matches the specified packet type.
 /// - `#[NotOn(_`[`protocol::PacketType`]`_)]`. If set then the field will only be read/written if
 ///   the reader packet type differs from the specified packet type.
+/// - `#[Since(_build_)]`. If set then the field will only be read/written if the game build of
+///   the [`protocol::ProtocolVersion`] is at least the specified build.
+/// - `#[Until(_build_)]`. If set then the field will only be read/written if the game build of
+///   the [`protocol::ProtocolVersion`] is lower than the specified build.
 #[cfg(feature = "derive")]
 #[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
 pub use pso2packetlib_impl::PacketRW;
@@ -99,11 +117,18 @@ pub use pso2packetlib_impl::PacketRW;
 /// - `#[Skip]`. If applied to a field struct field, then this attribute will skip one bit of the
 ///   flags.
 /// - `#[ManualRW(_readfn_, _writefn_)]` sets the read/write functions for the variant. Specified
-///   functions must have the same prototype as the [`protocol::HelperReadWrite`] functions.
+///   functions must have the same prototype as the [`protocol::HelperReadWrite`] functions. An
+///   optional third argument sets the encoded size of the field (e.g.
+///   `#[ManualRW(read_fn, write_fn, 12)]`), which is used in the field schema and the size
+///   estimation.
 /// - `#[OnlyOn(_`[`protocol::PacketType`]`_)]`. If set then the field will only be read/written if
 ///   the reader packet type matches the specified packet type.
 /// - `#[NotOn(_`[`protocol::PacketType`]`_)]`. If set then the field will only be read/written if
 ///   the reader packet type differs from the specified packet type.
+/// - `#[Since(_build_)]`. If set then the field will only be read/written if the game build of
+///   the [`protocol::ProtocolVersion`] is at least the specified build.
+/// - `#[Until(_build_)]`. If set then the field will only be read/written if the game build of
+///   the [`protocol::ProtocolVersion`] is lower than the specified build.
 #[cfg(feature = "derive")]
 #[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
 pub use pso2packetlib_impl::HelperRW;
@@ -0,0 +1,1132 @@
+//! Conversion between PPAC files and packet captures.
+//!
+//! PPAC files can be exported to pcapng files with a synthesized TCP session, and pcap/pcapng
+//! captures of unencrypted sessions can be imported back into PPAC files. Encrypted sessions can
+//! be imported if the server's private key is known (requires the `connection` feature).
+
+#[cfg(feature = "connection")]
+use crate::{
+    connection::{offline::SessionDecryptor, ConnectionError},
+    PrivateKey,
+};
+use crate::{
+    framing::frame_length,
+    ppac::{Direction, OutputType, PPACError, PPACReader, PPACWriter},
+    protocol::{Packet, PacketError, PacketType, ParseLimits, ProtocolVersion},
+};
+use byteorder::{BigEndian, ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::{
+    collections::BTreeMap,
+    io::{ErrorKind, Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+
+/// Error type returned by the capture conversion functions.
+#[derive(Debug, thiserror::Error)]
+pub enum PcapError {
+    /// Error occurred while reading or writing the PPAC file.
+    #[error(transparent)]
+    PPACError(#[from] PPACError),
+    /// File is not a pcap or pcapng file.
+    #[error("opened file is not a pcap or pcapng file")]
+    InvalidFile,
+    /// Capture has an unsupported link type.
+    #[error("unsupported link type: {0}")]
+    UnsupportedLinkType(u32),
+    /// Session data doesn't consist of valid packets (e.g. the session is encrypted or a packet
+    /// exceeds the [`ParseLimits`]).
+    #[error("invalid packet length in session {client} -> {server}: {error}")]
+    InvalidPacketLength {
+        /// Client address of the session.
+        client: SocketAddr,
+        /// Server address of the session.
+        server: SocketAddr,
+        /// Length check error.
+        #[source]
+        error: PacketError,
+    },
+    /// Error occurred while decrypting the session.
+    #[cfg(feature = "connection")]
+    #[error("failed to decrypt the session: {0}")]
+    ConnectionError(#[from] ConnectionError),
+    /// IO error occured (i.e. [`std::io::Error`]).
+    #[error("IO error: {0}")]
+    IOError(#[from] std::io::Error),
+}
+
+/// Settings of the exported capture.
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    client: SocketAddrV4,
+    server: SocketAddrV4,
+}
+
+/// Settings of the capture import.
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    server_ports: Vec<u16>,
+    version: Option<ProtocolVersion>,
+    limits: ParseLimits,
+    #[cfg(feature = "connection")]
+    private_key: Option<PrivateKey>,
+}
+
+/// Writer of pcapng files with a synthesized TCP session.
+///
+/// Packets are written as raw IPv4 frames between the client and the server addresses from
+/// [`ExportOptions`]. The packet type and the game build are stored in the section comments.
+pub struct PcapngWriter<W: Write> {
+    writer: W,
+    options: ExportOptions,
+    /// Next sequence numbers of the client and the server.
+    seq: [u32; 2],
+    ip_id: u16,
+    is_started: bool,
+}
+
+/// Packet capture read by [`Capture::read`].
+#[derive(Debug, Clone, Default)]
+pub struct Capture {
+    /// Reassembled TCP sessions.
+    pub sessions: Vec<TcpSession>,
+    /// Protocol version stored in the capture (if it was exported by [`PcapngWriter`]).
+    pub version: Option<ProtocolVersion>,
+}
+
+/// Reassembled TCP session.
+#[derive(Debug, Clone)]
+pub struct TcpSession {
+    /// Client address.
+    pub client: SocketAddr,
+    /// Server address.
+    pub server: SocketAddr,
+    /// In-order session data of both directions in the capture order.
+    pub chunks: Vec<StreamChunk>,
+}
+
+/// Chunk of the reassembled session data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamChunk {
+    /// Capture time of the segment that completed this chunk.
+    pub time: Duration,
+    /// Direction of the data.
+    pub direction: Direction,
+    /// Session data.
+    pub data: Vec<u8>,
+}
+
+/// Single direction of a TCP session.
+#[derive(Debug, Default)]
+struct HalfStream {
+    /// Sequence number of the first data byte.
+    base: Option<u32>,
+    /// Stream offset of the next expected byte.
+    next: u64,
+    /// Out-of-order segments by their stream offset.
+    pending: BTreeMap<u64, Vec<u8>>,
+}
+
+#[derive(Debug)]
+struct Session {
+    session: TcpSession,
+    streams: [HalfStream; 2],
+}
+
+/// Parsed TCP segment.
+struct Segment<'a> {
+    src: SocketAddr,
+    dst: SocketAddr,
+    seq: u32,
+    flags: u8,
+    payload: &'a [u8],
+}
+
+const LINKTYPE_NULL: u32 = 0;
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_IPV4: u32 = 228;
+const LINKTYPE_IPV6: u32 = 229;
+const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+const TCP_FIN: u8 = 0x01;
+const TCP_SYN: u8 = 0x02;
+const TCP_PSH: u8 = 0x08;
+const TCP_ACK: u8 = 0x10;
+
+const SHB_MAGIC: u32 = 0x0A0D0D0A;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D;
+const PACKET_TYPE_COMMENT: &str = "PSO2 packet type: ";
+const BUILD_COMMENT: &str = "PSO2 game build: ";
+/// Maximum TCP payload of a synthesized segment.
+const MAX_SEGMENT: usize = 0xFFFF - 40;
+
+//--------------------------------------
+// Export
+//--------------------------------------
+
+/// Converts a PPAC file into a pcapng file. Returns the number of written packets.
+///
+/// # Usage
+/// ```no_run
+/// # use pso2packetlib::pcap::{ppac_to_pcapng, ExportOptions};
+/// # fn main() -> Result<(), pso2packetlib::pcap::PcapError> {
+/// let input = std::fs::File::open("capture.pak")?;
+/// let output = std::fs::File::create("capture.pcapng")?;
+/// ppac_to_pcapng(input, output, ExportOptions::default())?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn ppac_to_pcapng(
+    reader: impl Read,
+    writer: impl Write,
+    options: ExportOptions,
+) -> Result<u64, PcapError> {
+    let mut reader = PPACReader::<_, Packet>::open(reader)?;
+    reader.set_out_type(OutputType::Raw);
+    let mut writer = PcapngWriter::new(writer, reader.get_protocol_version(), options)?;
+    let mut count = 0;
+    while let Some(packet) = reader.read()? {
+        let Some(data) = packet.data else {
+            continue;
+        };
+        writer.write_data(packet.time, packet.direction, &data)?;
+        count += 1;
+    }
+    writer.into_inner()?;
+    Ok(count)
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            client: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 50000),
+            server: SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 2), 12000),
+        }
+    }
+}
+
+impl ExportOptions {
+    /// Creates default options (client `10.0.0.1:50000`, server `10.0.0.2:12000`).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the client address.
+    pub fn client(mut self, client: SocketAddrV4) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Sets the server address.
+    pub fn server(mut self, server: SocketAddrV4) -> Self {
+        self.server = server;
+        self
+    }
+}
+
+impl<W: Write> PcapngWriter<W> {
+    /// Creates a new pcapng file.
+    pub fn new(
+        mut writer: W,
+        version: impl Into<ProtocolVersion>,
+        options: ExportOptions,
+    ) -> Result<Self, PcapError> {
+        let version = version.into();
+        // section header
+        let mut body = vec![];
+        body.write_u32::<LittleEndian>(BYTE_ORDER_MAGIC)?;
+        body.write_u16::<LittleEndian>(1)?;
+        body.write_u16::<LittleEndian>(0)?;
+        body.write_i64::<LittleEndian>(-1)?;
+        write_option(
+            &mut body,
+            1,
+            format!("{PACKET_TYPE_COMMENT}{:?}", version.packet_type).as_bytes(),
+        )?;
+        write_option(
+            &mut body,
+            1,
+            format!("{BUILD_COMMENT}{}", version.build).as_bytes(),
+        )?;
+        write_option(&mut body, 4, b"pso2packetlib")?;
+        body.write_u32::<LittleEndian>(0)?;
+        write_block(&mut writer, SHB_MAGIC, &body)?;
+
+        // interface description
+        let mut body = vec![];
+        body.write_u16::<LittleEndian>(LINKTYPE_RAW as u16)?;
+        body.write_u16::<LittleEndian>(0)?;
+        body.write_u32::<LittleEndian>(0)?;
+        write_option(&mut body, 2, b"pso2")?;
+        // nanosecond timestamps
+        write_option(&mut body, 9, &[9])?;
+        body.write_u32::<LittleEndian>(0)?;
+        write_block(&mut writer, 1, &body)?;
+
+        Ok(Self {
+            writer,
+            options,
+            seq: [0; 2],
+            ip_id: 0,
+            is_started: false,
+        })
+    }
+
+    /// Writes packet data as a TCP segment in the specified direction.
+    pub fn write_data(
+        &mut self,
+        time: Duration,
+        direction: Direction,
+        data: &[u8],
+    ) -> Result<(), PcapError> {
+        if !self.is_started {
+            self.is_started = true;
+            self.write_segment(time, Direction::ToServer, TCP_SYN, &[])?;
+            self.write_segment(time, Direction::ToClient, TCP_SYN | TCP_ACK, &[])?;
+            self.write_segment(time, Direction::ToServer, TCP_ACK, &[])?;
+        }
+        for chunk in data.chunks(MAX_SEGMENT) {
+            self.write_segment(time, direction, TCP_PSH | TCP_ACK, chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(mut self) -> std::io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_segment(
+        &mut self,
+        time: Duration,
+        direction: Direction,
+        flags: u8,
+        payload: &[u8],
+    ) -> Result<(), PcapError> {
+        let (src, dst, side) = match direction {
+            Direction::ToServer => (self.options.client, self.options.server, 0),
+            Direction::ToClient => (self.options.server, self.options.client, 1),
+        };
+        let seq = self.seq[side];
+        let ack = self.seq[side ^ 1];
+        self.seq[side] = seq.wrapping_add(payload.len() as u32 + (flags & TCP_SYN != 0) as u32);
+
+        let mut tcp = vec![];
+        tcp.write_u16::<BigEndian>(src.port())?;
+        tcp.write_u16::<BigEndian>(dst.port())?;
+        tcp.write_u32::<BigEndian>(seq)?;
+        tcp.write_u32::<BigEndian>(if flags & TCP_ACK != 0 { ack } else { 0 })?;
+        tcp.write_u8(5 << 4)?;
+        tcp.write_u8(flags)?;
+        tcp.write_u16::<BigEndian>(0xFFFF)?;
+        tcp.write_u16::<BigEndian>(0)?;
+        tcp.write_u16::<BigEndian>(0)?;
+        tcp.extend_from_slice(payload);
+        let mut pseudo = vec![];
+        pseudo.extend_from_slice(&src.ip().octets());
+        pseudo.extend_from_slice(&dst.ip().octets());
+        pseudo.write_u16::<BigEndian>(6)?;
+        pseudo.write_u16::<BigEndian>(tcp.len() as u16)?;
+        let tcp_checksum = checksum(&[&pseudo, &tcp]);
+        BigEndian::write_u16(&mut tcp[16..18], tcp_checksum);
+
+        let mut frame = vec![];
+        frame.write_u8(0x45)?;
+        frame.write_u8(0)?;
+        frame.write_u16::<BigEndian>(20 + tcp.len() as u16)?;
+        frame.write_u16::<BigEndian>(self.ip_id)?;
+        // don't fragment
+        frame.write_u16::<BigEndian>(0x4000)?;
+        frame.write_u8(64)?;
+        frame.write_u8(6)?;
+        frame.write_u16::<BigEndian>(0)?;
+        frame.extend_from_slice(&src.ip().octets());
+        frame.extend_from_slice(&dst.ip().octets());
+        let ip_checksum = checksum(&[&frame]);
+        BigEndian::write_u16(&mut frame[10..12], ip_checksum);
+        frame.append(&mut tcp);
+        self.ip_id = self.ip_id.wrapping_add(1);
+
+        let timestamp = time.as_nanos() as u64;
+        let mut body = vec![];
+        body.write_u32::<LittleEndian>(0)?;
+        body.write_u32::<LittleEndian>((timestamp >> 32) as u32)?;
+        body.write_u32::<LittleEndian>(timestamp as u32)?;
+        body.write_u32::<LittleEndian>(frame.len() as u32)?;
+        body.write_u32::<LittleEndian>(frame.len() as u32)?;
+        body.extend_from_slice(&frame);
+        body.resize(body.len().next_multiple_of(4), 0);
+        write_block(&mut self.writer, 6, &body)?;
+        Ok(())
+    }
+}
+
+fn write_option(writer: &mut Vec<u8>, code: u16, value: &[u8]) -> std::io::Result<()> {
+    writer.write_u16::<LittleEndian>(code)?;
+    writer.write_u16::<LittleEndian>(value.len() as u16)?;
+    writer.extend_from_slice(value);
+    writer.resize(writer.len().next_multiple_of(4), 0);
+    Ok(())
+}
+
+fn write_block(writer: &mut impl Write, block_type: u32, body: &[u8]) -> std::io::Result<()> {
+    let len = body.len() as u32 + 12;
+    writer.write_u32::<LittleEndian>(block_type)?;
+    writer.write_u32::<LittleEndian>(len)?;
+    writer.write_all(body)?;
+    writer.write_u32::<LittleEndian>(len)?;
+    Ok(())
+}
+
+fn checksum(parts: &[&[u8]]) -> u16 {
+    let mut sum = 0u32;
+    for chunk in parts.concat().chunks(2) {
+        let word = match chunk {
+            [a, b] => u16::from_be_bytes([*a, *b]),
+            [a] => u16::from_be_bytes([*a, 0]),
+            _ => unreachable!(),
+        };
+        sum += word as u32;
+    }
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+//--------------------------------------
+// Import
+//--------------------------------------
+
+/// Converts a pcap or pcapng capture into a PPAC file. Returns the number of written packets.
+///
+/// Sessions are decrypted if [`ImportOptions::private_key`] is set (requires the `connection`
+/// feature).
+/// All sessions (filtered by [`ImportOptions::server_port`]) are written in the capture order.
+/// The protocol version of the PPAC file is taken from the options, then from the capture and
+/// defaults to the latest [`PacketType::NGS`] build.
+///
+/// # Usage
+/// ```no_run
+/// # use pso2packetlib::pcap::{pcap_to_ppac, ImportOptions};
+/// # fn main() -> Result<(), pso2packetlib::pcap::PcapError> {
+/// let input = std::fs::File::open("capture.pcapng")?;
+/// let output = std::fs::File::create("capture.pak")?;
+/// pcap_to_ppac(input, output, &ImportOptions::new().server_port(12000))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn pcap_to_ppac<W: Write>(
+    reader: impl Read,
+    writer: W,
+    options: &ImportOptions,
+) -> Result<u64, PcapError> {
+    let capture = Capture::read(reader, options)?;
+    let version = options.version.or(capture.version).unwrap_or_default();
+    let mut writer = PPACWriter::new(writer, version, false)?;
+    #[cfg(feature = "connection")]
+    let count = match &options.private_key {
+        Some(key) => capture.write_ppac_decrypted(&mut writer, version, key, &options.limits)?,
+        None => capture.write_ppac(&mut writer, &options.limits)?,
+    };
+    #[cfg(not(feature = "connection"))]
+    let count = capture.write_ppac(&mut writer, &options.limits)?;
+    writer.into_inner()?;
+    Ok(count)
+}
+
+impl ImportOptions {
+    /// Creates default options (all sessions are imported).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a server port. If any ports are added, then only sessions with these server ports
+    /// are imported.
+    pub fn server_port(mut self, port: u16) -> Self {
+        self.server_ports.push(port);
+        self
+    }
+
+    /// Sets the packet type of the imported data (with the latest build).
+    pub fn packet_type(mut self, packet_type: PacketType) -> Self {
+        self.version = Some(packet_type.into());
+        self
+    }
+
+    /// Sets the protocol version of the imported data.
+    pub fn version(mut self, version: ProtocolVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Sets the limits for the imported packets.
+    pub fn limits(mut self, limits: ParseLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Sets the server's private key. If set, then the sessions are decrypted.
+    #[cfg(feature = "connection")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "connection")))]
+    pub fn private_key(mut self, key: PrivateKey) -> Self {
+        self.private_key = Some(key);
+        self
+    }
+}
+
+impl Capture {
+    /// Reads a pcap or pcapng capture and reassembles the TCP sessions.
+    ///
+    /// # Note
+    ///
+    /// Only IPv4 and IPv6 (without extension headers) TCP sessions are supported. If the session
+    /// has missing segments, then the data after the gap is discarded.
+    pub fn read(mut reader: impl Read, options: &ImportOptions) -> Result<Self, PcapError> {
+        let mut capture = CaptureState {
+            options,
+            sessions: vec![],
+            version: None,
+        };
+        let magic = match reader.read_u32::<LittleEndian>() {
+            Ok(magic) => magic,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Err(PcapError::InvalidFile),
+            Err(e) => return Err(e.into()),
+        };
+        match magic {
+            SHB_MAGIC => capture.read_pcapng(reader)?,
+            0xA1B2C3D4 | 0xD4C3B2A1 | 0xA1B23C4D | 0x4D3CB2A1 => {
+                capture.read_pcap(reader, magic)?
+            }
+            _ => return Err(PcapError::InvalidFile),
+        }
+        Ok(Self {
+            sessions: capture
+                .sessions
+                .into_iter()
+                .map(|s| s.session)
+                .filter(|s| {
+                    options.server_ports.is_empty()
+                        || options.server_ports.contains(&s.server.port())
+                })
+                .collect(),
+            version: capture.version,
+        })
+    }
+
+    /// Splits the session data into packets and writes them to the PPAC file. Returns the
+    /// number of written packets.
+    ///
+    /// Packet lengths are checked against `limits`. Incomplete packets at the end of the
+    /// sessions are discarded.
+    pub fn write_ppac<W: Write>(
+        &self,
+        writer: &mut PPACWriter<W>,
+        limits: &ParseLimits,
+    ) -> Result<u64, PcapError> {
+        let mut buffers = vec![[vec![], vec![]]; self.sessions.len()];
+        let mut count = 0;
+        for (session_id, chunk) in self.ordered_chunks() {
+            let session = &self.sessions[session_id];
+            let buffer = &mut buffers[session_id][chunk.direction as usize];
+            buffer.extend_from_slice(&chunk.data);
+            let mut pointer = 0;
+            loop {
+                let length = frame_length(&buffer[pointer..], 0, limits).map_err(|error| {
+                    PcapError::InvalidPacketLength {
+                        client: session.client,
+                        server: session.server,
+                        error,
+                    }
+                })?;
+                let Some(length) = length.filter(|&l| buffer.len() - pointer >= l) else {
+                    break;
+                };
+                writer.write_data_unchecked(
+                    chunk.time,
+                    chunk.direction,
+                    &buffer[pointer..pointer + length],
+                )?;
+                pointer += length;
+                count += 1;
+            }
+            buffer.drain(..pointer);
+        }
+        Ok(count)
+    }
+
+    /// Decrypts the sessions using the server's private key and writes them to the PPAC file.
+    /// Returns the number of written packets.
+    ///
+    /// Sessions are decrypted by [`SessionDecryptor`] with the specified `limits`, unencrypted
+    /// sessions are written as is.
+    #[cfg(feature = "connection")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "connection")))]
+    pub fn write_ppac_decrypted<W: Write>(
+        &self,
+        writer: &mut PPACWriter<W>,
+        version: impl Into<ProtocolVersion>,
+        key: &PrivateKey,
+        limits: &ParseLimits,
+    ) -> Result<u64, PcapError> {
+        let version = version.into();
+        let mut decryptors: Vec<_> = self
+            .sessions
+            .iter()
+            .map(|_| {
+                let mut decryptor = SessionDecryptor::new(version, key.clone());
+                decryptor.set_limits(*limits);
+                decryptor
+            })
+            .collect();
+        let mut count = 0;
+        for (session_id, chunk) in self.ordered_chunks() {
+            let decryptor = &mut decryptors[session_id];
+            decryptor.feed(chunk.time, chunk.direction, &chunk.data)?;
+            count += decryptor.write_ppac(writer)?;
+        }
+        Ok(count)
+    }
+
+    /// Returns chunks of all sessions sorted by time.
+    fn ordered_chunks(&self) -> Vec<(usize, &StreamChunk)> {
+        let mut chunks: Vec<_> = self
+            .sessions
+            .iter()
+            .enumerate()
+            .flat_map(|(i, s)| s.chunks.iter().map(move |c| (i, c)))
+            .collect();
+        chunks.sort_by_key(|(_, c)| c.time);
+        chunks
+    }
+}
+
+/// Capture reading state.
+struct CaptureState<'a> {
+    options: &'a ImportOptions,
+    sessions: Vec<Session>,
+    version: Option<ProtocolVersion>,
+}
+
+impl CaptureState<'_> {
+    fn read_pcap(&mut self, mut reader: impl Read, magic: u32) -> Result<(), PcapError> {
+        let is_be = matches!(magic, 0xD4C3B2A1 | 0x4D3CB2A1);
+        let is_nano = matches!(magic, 0xA1B23C4D | 0x4D3CB2A1);
+        let mut header = [0; 20];
+        reader.read_exact(&mut header)?;
+        let link_type = read_u32(&header[16..], is_be) & 0x0FFF_FFFF;
+        check_link_type(link_type)?;
+        let mut record = [0; 16];
+        loop {
+            match reader.read_exact(&mut record) {
+                Ok(_) => {}
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+            let secs = read_u32(&record, is_be) as u64;
+            let frac = read_u32(&record[4..], is_be);
+            let len = read_u32(&record[8..], is_be);
+            let mut data = vec![];
+            reader.by_ref().take(len as u64).read_to_end(&mut data)?;
+            let time = match is_nano {
+                true => Duration::new(secs, frac),
+                false => Duration::new(secs, 0) + Duration::from_micros(frac as u64),
+            };
+            self.handle_frame(time, link_type, &data);
+        }
+        Ok(())
+    }
+
+    fn read_pcapng(&mut self, mut reader: impl Read) -> Result<(), PcapError> {
+        // (link type, timestamp resolution)
+        let mut interfaces: Vec<(u32, u8)> = vec![];
+        let mut is_be = false;
+        let mut block_type = SHB_MAGIC;
+        loop {
+            let mut len = [0; 4];
+            reader.read_exact(&mut len)?;
+            if block_type == SHB_MAGIC {
+                let mut magic = [0; 4];
+                reader.read_exact(&mut magic)?;
+                is_be = match LittleEndian::read_u32(&magic) {
+                    BYTE_ORDER_MAGIC => false,
+                    0x4D3C2B1A => true,
+                    _ => return Err(PcapError::InvalidFile),
+                };
+            }
+            let len = read_u32(&len, is_be) as u64;
+            let read_len = if block_type == SHB_MAGIC { 16 } else { 12 };
+            if len < read_len {
+                return Err(PcapError::InvalidFile);
+            }
+            let mut body = vec![];
+            reader
+                .by_ref()
+                .take(len - read_len)
+                .read_to_end(&mut body)?;
+            if body.len() as u64 != len - read_len {
+                return Err(std::io::Error::from(ErrorKind::UnexpectedEof).into());
+            }
+            reader.read_u32::<LittleEndian>()?;
+
+            match block_type {
+                SHB_MAGIC => {
+                    interfaces.clear();
+                    // version and section length
+                    let options = body.get(12..).unwrap_or_default();
+                    let mut packet_type = None;
+                    let mut build = ProtocolVersion::LATEST_BUILD;
+                    for (code, value) in parse_options(options, is_be) {
+                        if code != 1 {
+                            continue;
+                        }
+                        let comment = String::from_utf8_lossy(value);
+                        if let Some(value) = comment
+                            .strip_prefix(PACKET_TYPE_COMMENT)
+                            .and_then(parse_packet_type)
+                        {
+                            packet_type = Some(value);
+                        } else if let Some(value) = comment
+                            .strip_prefix(BUILD_COMMENT)
+                            .and_then(|b| b.parse().ok())
+                        {
+                            build = value;
+                        }
+                    }
+                    self.version = packet_type.map(|p| ProtocolVersion::new(p, build));
+                }
+                // interface description
+                1 if body.len() >= 8 => {
+                    let link_type = read_u16(&body, is_be) as u32;
+                    let mut resolution = 6;
+                    for (code, value) in parse_options(&body[8..], is_be) {
+                        if code == 9 && !value.is_empty() {
+                            resolution = value[0];
+                        }
+                    }
+                    interfaces.push((link_type, resolution));
+                }
+                // enhanced packet
+                6 if body.len() >= 20 => {
+                    let interface = read_u32(&body, is_be) as usize;
+                    let Some(&(link_type, resolution)) = interfaces.get(interface) else {
+                        return Err(PcapError::InvalidFile);
+                    };
+                    check_link_type(link_type)?;
+                    let timestamp = (read_u32(&body[4..], is_be) as u64) << 32
+                        | read_u32(&body[8..], is_be) as u64;
+                    let len = read_u32(&body[12..], is_be) as usize;
+                    let data = body.get(20..20 + len).ok_or(PcapError::InvalidFile)?;
+                    self.handle_frame(to_duration(timestamp, resolution), link_type, data);
+                }
+                // simple packet
+                3 if body.len() >= 4 => {
+                    let Some(&(link_type, _)) = interfaces.first() else {
+                        return Err(PcapError::InvalidFile);
+                    };
+                    check_link_type(link_type)?;
+                    let len = (read_u32(&body, is_be) as usize).min(body.len() - 4);
+                    self.handle_frame(Duration::ZERO, link_type, &body[4..4 + len]);
+                }
+                _ => {}
+            }
+
+            let mut next_type = [0; 4];
+            match reader.read_exact(&mut next_type) {
+                Ok(_) => {}
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e.into()),
+            }
+            block_type = match LittleEndian::read_u32(&next_type) {
+                SHB_MAGIC => SHB_MAGIC,
+                _ => read_u32(&next_type, is_be),
+            };
+        }
+        Ok(())
+    }
+
+    fn handle_frame(&mut self, time: Duration, link_type: u32, data: &[u8]) {
+        let Some(segment) = parse_frame(link_type, data) else {
+            return;
+        };
+        let is_syn = segment.flags & TCP_SYN != 0;
+        let is_new = is_syn && segment.flags & TCP_ACK == 0;
+        let position = self.sessions.iter().rposition(|s| {
+            let (client, server) = (s.session.client, s.session.server);
+            (segment.src, segment.dst) == (client, server)
+                || (segment.src, segment.dst) == (server, client)
+        });
+        let session = match position {
+            // new connection with the same addresses
+            Some(i) if is_new && self.sessions[i].streams[0].base.is_some() => None,
+            Some(i) => Some(&mut self.sessions[i]),
+            None => None,
+        };
+        let session = match session {
+            Some(session) => session,
+            None => {
+                let ports = &self.options.server_ports;
+                let src_is_server = if is_syn {
+                    // SYN is sent by the client, SYN-ACK by the server
+                    !is_new
+                } else if ports.contains(&segment.src.port()) {
+                    true
+                } else if ports.contains(&segment.dst.port()) {
+                    false
+                } else {
+                    segment.src.port() < segment.dst.port()
+                };
+                let (client, server) = match src_is_server {
+                    true => (segment.dst, segment.src),
+                    false => (segment.src, segment.dst),
+                };
+                self.sessions.push(Session {
+                    session: TcpSession {
+                        client,
+                        server,
+                        chunks: vec![],
+                    },
+                    streams: Default::default(),
+                });
+                self.sessions.last_mut().unwrap()
+            }
+        };
+        let direction = match segment.src == session.session.client {
+            true => Direction::ToServer,
+            false => Direction::ToClient,
+        };
+        let stream = &mut session.streams[direction as usize];
+        let seq = if is_syn {
+            segment.seq.wrapping_add(1)
+        } else {
+            segment.seq
+        };
+        let base = *stream.base.get_or_insert(seq);
+        if let Some(data) = stream.push(seq.wrapping_sub(base) as u64, segment.payload) {
+            session.session.chunks.push(StreamChunk {
+                time,
+                direction,
+                data,
+            });
+        }
+    }
+}
+
+impl HalfStream {
+    /// Adds a segment, returns newly available in-order data.
+    fn push(&mut self, offset: u64, payload: &[u8]) -> Option<Vec<u8>> {
+        let end = offset + payload.len() as u64;
+        // offsets past 2GB are treated as retransmissions of old data
+        if payload.is_empty() || end <= self.next || offset > self.next + 0x8000_0000 {
+            return None;
+        }
+        let skip = self.next.saturating_sub(offset) as usize;
+        let offset = offset.max(self.next);
+        let entry = self.pending.entry(offset).or_default();
+        if entry.len() < payload.len() - skip {
+            *entry = payload[skip..].to_vec();
+        }
+
+        let mut data = vec![];
+        while let Some(entry) = self.pending.first_entry() {
+            let offset = *entry.key();
+            if offset > self.next {
+                break;
+            }
+            let segment = entry.remove();
+            let skip = (self.next - offset) as usize;
+            if skip < segment.len() {
+                data.extend_from_slice(&segment[skip..]);
+                self.next += (segment.len() - skip) as u64;
+            }
+        }
+        (!data.is_empty()).then_some(data)
+    }
+}
+
+fn check_link_type(link_type: u32) -> Result<(), PcapError> {
+    match link_type {
+        LINKTYPE_NULL | LINKTYPE_ETHERNET | LINKTYPE_RAW | LINKTYPE_LINUX_SLL | LINKTYPE_IPV4
+        | LINKTYPE_IPV6 | LINKTYPE_LINUX_SLL2 => Ok(()),
+        _ => Err(PcapError::UnsupportedLinkType(link_type)),
+    }
+}
+
+/// Extracts a TCP segment from the frame.
+fn parse_frame(link_type: u32, data: &[u8]) -> Option<Segment<'_>> {
+    let ip = match link_type {
+        LINKTYPE_NULL => data.get(4..)?,
+        LINKTYPE_ETHERNET => {
+            let mut offset = 12;
+            // VLAN tags
+            while matches!(data.get(offset..offset + 2)?, [0x81, 0x00] | [0x88, 0xA8]) {
+                offset += 4;
+            }
+            match data.get(offset..offset + 2)? {
+                [0x08, 0x00] | [0x86, 0xDD] => data.get(offset + 2..)?,
+                _ => return None,
+            }
+        }
+        LINKTYPE_LINUX_SLL => data.get(16..)?,
+        LINKTYPE_LINUX_SLL2 => data.get(20..)?,
+        _ => data,
+    };
+    let (src, dst, tcp) = match ip.first()? >> 4 {
+        4 => {
+            let header_len = ((ip[0] & 0xF) as usize) * 4;
+            let total_len = (BigEndian::read_u16(ip.get(2..4)?) as usize).min(ip.len());
+            // fragmented packets aren't supported
+            let fragment = BigEndian::read_u16(ip.get(6..8)?);
+            if *ip.get(9)? != 6 || fragment & 0x3FFF != 0 {
+                return None;
+            }
+            let src: [u8; 4] = ip.get(12..16)?.try_into().ok()?;
+            let dst: [u8; 4] = ip.get(16..20)?.try_into().ok()?;
+            (
+                IpAddr::from(Ipv4Addr::from(src)),
+                IpAddr::from(Ipv4Addr::from(dst)),
+                ip.get(header_len..total_len)?,
+            )
+        }
+        6 => {
+            let payload_len = BigEndian::read_u16(ip.get(4..6)?) as usize;
+            if *ip.get(6)? != 6 {
+                return None;
+            }
+            let src: [u8; 16] = ip.get(8..24)?.try_into().ok()?;
+            let dst: [u8; 16] = ip.get(24..40)?.try_into().ok()?;
+            (
+                IpAddr::from(Ipv6Addr::from(src)),
+                IpAddr::from(Ipv6Addr::from(dst)),
+                ip.get(40..(40 + payload_len).min(ip.len()))?,
+            )
+        }
+        _ => return None,
+    };
+    let header_len = ((tcp.get(12)? >> 4) as usize) * 4;
+    Some(Segment {
+        src: SocketAddr::new(src, BigEndian::read_u16(tcp.get(0..2)?)),
+        dst: SocketAddr::new(dst, BigEndian::read_u16(tcp.get(2..4)?)),
+        seq: BigEndian::read_u32(tcp.get(4..8)?),
+        flags: *tcp.get(13)? & (TCP_FIN | TCP_SYN | TCP_PSH | TCP_ACK | 0x04),
+        payload: tcp.get(header_len..)?,
+    })
+}
+
+fn parse_options(mut data: &[u8], is_be: bool) -> Vec<(u16, &[u8])> {
+    let mut options = vec![];
+    while data.len() >= 4 {
+        let code = read_u16(data, is_be);
+        let len = read_u16(&data[2..], is_be) as usize;
+        if code == 0 || data.len() < 4 + len {
+            break;
+        }
+        options.push((code, &data[4..4 + len]));
+        data = &data[(4 + len).next_multiple_of(4).min(data.len())..];
+    }
+    options
+}
+
+fn parse_packet_type(name: &str) -> Option<PacketType> {
+    Some(match name {
+        "NGS" => PacketType::NGS,
+        "Classic" => PacketType::Classic,
+        "NA" => PacketType::NA,
+        "JP" => PacketType::JP,
+        "Vita" => PacketType::Vita,
+        _ => return None,
+    })
+}
+
+/// Converts a pcapng timestamp with the `if_tsresol` resolution.
+fn to_duration(timestamp: u64, resolution: u8) -> Duration {
+    let exp = (resolution & 0x7F) as u32;
+    if resolution & 0x80 != 0 {
+        let nanos = ((timestamp as u128) * 1_000_000_000) >> exp.min(127);
+        return Duration::from_nanos(nanos as u64);
+    }
+    let Some(unit) = 10u64.checked_pow(exp) else {
+        return Duration::ZERO;
+    };
+    let secs = timestamp / unit;
+    let frac = (timestamp % unit) as u128;
+    let nanos = match exp {
+        ..=9 => frac * 10u128.pow(9 - exp),
+        _ => frac / 10u128.pow(exp - 9),
+    };
+    Duration::new(secs, nanos as u32)
+}
+
+fn read_u16(data: &[u8], is_be: bool) -> u16 {
+    match is_be {
+        true => BigEndian::read_u16(data),
+        false => LittleEndian::read_u16(data),
+    }
+}
+
+fn read_u32(data: &[u8], is_be: bool) -> u32 {
+    match is_be {
+        true => BigEndian::read_u32(data),
+        false => LittleEndian::read_u32(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Capture, ExportOptions, ImportOptions, PcapError, PcapngWriter};
+    use crate::{
+        ppac::{Direction, OutputType, PPACReader, PPACWriter},
+        protocol::{Packet, PacketError, PacketType, ParseLimits, ProtocolRW, ProtocolVersion},
+    };
+    use byteorder::{BigEndian, LittleEndian, WriteBytesExt};
+    use std::{io::Cursor, time::Duration};
+
+    #[test]
+    fn round_trip() {
+        let mut writer = PPACWriter::new(Cursor::new(vec![]), PacketType::Vita, false).unwrap();
+        let packets = [
+            (Direction::ToClient, Packet::ServerPing),
+            (Direction::ToServer, Packet::ServerPong),
+            (Direction::ToServer, Packet::ClientPing(Default::default())),
+        ];
+        for (i, (direction, packet)) in packets.iter().enumerate() {
+            writer
+                .write_packet(Duration::from_millis(i as u64 * 1500), *direction, packet)
+                .unwrap();
+        }
+        let ppac = writer.into_inner().unwrap().into_inner();
+
+        let mut pcapng = vec![];
+        let count = super::ppac_to_pcapng(&ppac[..], &mut pcapng, ExportOptions::new()).unwrap();
+        assert_eq!(count, 3);
+
+        let mut output = vec![];
+        let count = super::pcap_to_ppac(&pcapng[..], &mut output, &ImportOptions::new()).unwrap();
+        assert_eq!(count, 3);
+        let mut reader = PPACReader::<_, Packet>::open(&output[..]).unwrap();
+        reader.set_out_type(OutputType::Raw);
+        assert_eq!(reader.get_protocol_type(), PacketType::Vita);
+        for (i, (direction, packet)) in packets.into_iter().enumerate() {
+            let data = reader.read().unwrap().unwrap();
+            assert_eq!(data.time, Duration::from_millis(i as u64 * 1500));
+            assert_eq!(data.direction, direction);
+            assert_eq!(data.data, Some(packet.write(PacketType::Vita)));
+        }
+        assert!(reader.read().unwrap().is_none());
+    }
+
+    /// Builds an Ethernet frame with a TCP segment.
+    fn frame(src_port: u16, dst_port: u16, seq: u32, flags: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0; 12];
+        frame.extend_from_slice(&[0x08, 0x00]);
+        frame.extend_from_slice(&[0x45, 0]);
+        frame
+            .write_u16::<BigEndian>(40 + payload.len() as u16)
+            .unwrap();
+        frame.extend_from_slice(&[0, 0, 0x40, 0, 64, 6, 0, 0]);
+        frame.extend_from_slice(&[192, 168, 0, (src_port % 2) as u8 + 1]);
+        frame.extend_from_slice(&[192, 168, 0, (dst_port % 2) as u8 + 1]);
+        frame.write_u16::<BigEndian>(src_port).unwrap();
+        frame.write_u16::<BigEndian>(dst_port).unwrap();
+        frame.write_u32::<BigEndian>(seq).unwrap();
+        frame.write_u32::<BigEndian>(0).unwrap();
+        frame.extend_from_slice(&[5 << 4, flags, 0xFF, 0xFF, 0, 0, 0, 0]);
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    fn pcap(frames: &[Vec<u8>]) -> Vec<u8> {
+        let mut pcap = vec![];
+        pcap.write_u32::<LittleEndian>(0xA1B2C3D4).unwrap();
+        pcap.extend_from_slice(&[2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        pcap.write_u32::<LittleEndian>(0xFFFF).unwrap();
+        pcap.write_u32::<LittleEndian>(1).unwrap();
+        for (i, frame) in frames.iter().enumerate() {
+            pcap.write_u32::<LittleEndian>(i as u32).unwrap();
+            pcap.write_u32::<LittleEndian>(0).unwrap();
+            pcap.write_u32::<LittleEndian>(frame.len() as u32).unwrap();
+            pcap.write_u32::<LittleEndian>(frame.len() as u32).unwrap();
+            pcap.extend_from_slice(frame);
+        }
+        pcap
+    }
+
+    #[test]
+    fn reassembly() {
+        let data = Packet::ClientPing(Default::default()).write(PacketType::NGS);
+        let pong = Packet::ServerPong.write(PacketType::NGS);
+        let (first, second) = data.split_at(5);
+        let frames = [
+            frame(50001, 12000, 99, 0x02, &[]),
+            // out of order
+            frame(50001, 12000, 100 + first.len() as u32, 0x18, second),
+            frame(50001, 12000, 100, 0x18, first),
+            // retransmission
+            frame(50001, 12000, 100, 0x18, first),
+            frame(12000, 50001, 7, 0x18, &pong),
+            // unrelated session
+            frame(443, 50002, 7, 0x18, &[1, 0, 0, 0, 2, 3, 4, 5]),
+        ];
+        let capture = Capture::read(&pcap(&frames)[..], &ImportOptions::new()).unwrap();
+        assert_eq!(capture.sessions.len(), 2);
+        let session = &capture.sessions[0];
+        assert_eq!(session.server.port(), 12000);
+        assert_eq!(session.chunks.len(), 2);
+        assert_eq!(session.chunks[0].data, data);
+        assert_eq!(session.chunks[0].time, Duration::from_secs(2));
+        assert_eq!(session.chunks[1].direction, Direction::ToClient);
+
+        // unrelated session can't be converted
+        let mut writer = PPACWriter::new(Cursor::new(vec![]), PacketType::NGS, false).unwrap();
+        assert!(matches!(
+            capture.write_ppac(&mut writer, &ParseLimits::default()),
+            Err(PcapError::InvalidPacketLength { .. })
+        ));
+        let options = ImportOptions::new().server_port(12000);
+        let capture = Capture::read(&pcap(&frames)[..], &options).unwrap();
+        assert_eq!(capture.sessions.len(), 1);
+        let mut writer = PPACWriter::new(Cursor::new(vec![]), PacketType::NGS, false).unwrap();
+        let limits = ParseLimits::default();
+        assert_eq!(capture.write_ppac(&mut writer, &limits).unwrap(), 2);
+
+        // packets exceeding the limits are rejected
+        let limits = ParseLimits {
+            max_packet_size: 8,
+            ..Default::default()
+        };
+        let mut writer = PPACWriter::new(Cursor::new(vec![]), PacketType::NGS, false).unwrap();
+        assert!(matches!(
+            capture.write_ppac(&mut writer, &limits),
+            Err(PcapError::InvalidPacketLength {
+                error: PacketError::LimitError { .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn pcapng_comment() {
+        let writer = PcapngWriter::new(vec![], PacketType::JP, ExportOptions::new()).unwrap();
+        let data = writer.into_inner().unwrap();
+        let capture = Capture::read(&data[..], &ImportOptions::new()).unwrap();
+        assert_eq!(
+            capture.version,
+            Some(ProtocolVersion::latest(PacketType::JP))
+        );
+        assert!(capture.sessions.is_empty());
+
+        // the build survives the round trip through a PPAC file
+        let version = ProtocolVersion::new(PacketType::NGS, 1234);
+        let writer = PcapngWriter::new(vec![], version, ExportOptions::new()).unwrap();
+        let data = writer.into_inner().unwrap();
+        let capture = Capture::read(&data[..], &ImportOptions::new()).unwrap();
+        assert_eq!(capture.version, Some(version));
+        let mut ppac = vec![];
+        super::pcap_to_ppac(&data[..], &mut ppac, &ImportOptions::new()).unwrap();
+        let reader = PPACReader::<_, Packet>::open(&ppac[..]).unwrap();
+        assert_eq!(reader.get_protocol_version(), version);
+    }
+}
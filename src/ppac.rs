@@ -1,9 +1,12 @@
 //! Packet storage file format.
 
-use crate::protocol::{Packet, PacketError, PacketType, ProtocolRW};
+use crate::protocol::{
+    Packet, PacketCategory, PacketError, PacketType, PacketView, ProtocolRW, ProtocolVersion,
+};
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::{
     io::{BufReader, ErrorKind, Read, Seek, SeekFrom, Write},
+    ops::{Bound, RangeBounds},
     time::Duration,
 };
 use zstd::stream::{Decoder, Encoder};
@@ -29,6 +32,9 @@ pub enum PPACError {
     /// IO error occured (i.e. [`std::io::Error`]).
     #[error("IO error: {0}")]
     IOError(#[from] std::io::Error),
+    /// File is not a PPAC index file.
+    #[error("opened file is not a PPAC index file")]
+    InvalidIndex,
 }
 
 /// Possible types of packet data output.
@@ -41,9 +47,20 @@ pub enum OutputType {
     Both,
 }
 
+/// Behavior of [`PPACReader`] on packet parsing errors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorMode {
+    /// Parsing errors are returned as errors and stop the iteration.
+    #[default]
+    Stop,
+    /// Parsing errors are stored in [`PacketData::parse_error`] and the iteration continues.
+    /// Only IO and framing errors stop the iteration.
+    Continue,
+}
+
 /// Direction of the packet.
 #[repr(u8)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Direction {
     ToServer,
     ToClient,
@@ -64,22 +81,80 @@ enum WriterWrapper<W: Write> {
     Zstd(Encoder<'static, W>),
 }
 
+/// Writer that counts written bytes.
+struct CountingWriter<W: Write> {
+    writer: W,
+    position: u64,
+}
+
 /// Reader for the `ppac` packet files.
 pub struct PPACReader<R: Read, P: ProtocolRW> {
-    reader: ReaderWrapper<R>,
+    reader: Option<ReaderWrapper<R>>,
     version: u8,
     packet_buffer: Vec<P>,
     data_buffer: Vec<Vec<u8>>,
     protocol_type: PacketType,
+    build: u32,
     last_header: Header,
     out_type: OutputType,
+    data_start: u64,
+    record: u64,
+    pending: Option<(Header, Vec<u8>)>,
+    index: Option<PPACIndex>,
+    error_mode: ErrorMode,
+    is_done: bool,
 }
 
 /// Writer of the `ppac` packet files.
 #[derive(Debug)]
 pub struct PPACWriter<W: Write> {
-    writer: Option<WriterWrapper<W>>,
-    packet_type: PacketType,
+    writer: Option<WriterWrapper<CountingWriter<W>>>,
+    version: ProtocolVersion,
+    record: u64,
+    index_interval: Option<u64>,
+    index: PPACIndex,
+    write_buffer: Vec<u8>,
+}
+
+/// Index of seek points in a PPAC file.
+///
+/// The index allows [`PPACReader`] to jump to a specific record or time without reading all the
+/// preceding records. It can be collected by [`PPACWriter`] and stored in a sidecar file or built
+/// by [`PPACReader::build_index`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PPACIndex {
+    entries: Vec<IndexEntry>,
+}
+
+/// Filter for the records returned by [`PPACReader::query`].
+///
+/// All specified conditions must match. If multiple IDs or categories are specified, then the
+/// packet must match any of them.
+#[derive(Debug, Clone)]
+pub struct PPACFilter {
+    direction: Option<Direction>,
+    start: Bound<Duration>,
+    end: Bound<Duration>,
+    ids: Vec<(u8, u16)>,
+    categories: Vec<PacketCategory>,
+}
+
+/// Iterator over the filtered records of the PPAC file.
+pub struct PPACQuery<'a, R: Read, P: ProtocolRW> {
+    reader: &'a mut PPACReader<R, P>,
+    filter: PPACFilter,
+}
+
+/// Single seek point in a PPAC file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndexEntry {
+    /// Ordinal of the record stored at this seek point.
+    pub record: u64,
+    /// Timestamp of the record stored at this seek point.
+    pub time: Duration,
+    /// Offset in the underlying file where reading can be started (i.e. start of the record or
+    /// start of the zstd frame).
+    pub offset: u64,
 }
 
 /// Packet data.
@@ -111,6 +186,22 @@ impl<R: Read> ReaderWrapper<R> {
     }
 }
 
+impl<R: Read + Seek> ReaderWrapper<R> {
+    fn seek_to(self, offset: u64) -> std::io::Result<Self> {
+        match self {
+            ReaderWrapper::NoEnc(mut r) => {
+                r.seek(SeekFrom::Start(offset))?;
+                Ok(ReaderWrapper::NoEnc(r))
+            }
+            ReaderWrapper::Zstd(d) => {
+                let mut r = d.finish().into_inner();
+                r.seek(SeekFrom::Start(offset))?;
+                Ok(ReaderWrapper::Zstd(Decoder::new(r)?))
+            }
+        }
+    }
+}
+
 impl<R: Read> Read for ReaderWrapper<R> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         match self {
@@ -127,10 +218,10 @@ impl<W: Write> WriterWrapper<W> {
             WriterWrapper::Zstd(e) => e.finish(),
         }
     }
-    fn write_u8_raw(&mut self, byte: u8) -> std::io::Result<()> {
+    fn write_all_raw(&mut self, data: &[u8]) -> std::io::Result<()> {
         match self {
-            WriterWrapper::NoEnc(w) => w.write_u8(byte),
-            WriterWrapper::Zstd(e) => e.get_mut().write_u8(byte),
+            WriterWrapper::NoEnc(w) => w.write_all(data),
+            WriterWrapper::Zstd(e) => e.get_mut().write_all(data),
         }
     }
 }
@@ -166,10 +257,29 @@ impl<W: Write> std::fmt::Debug for WriterWrapper<W> {
     }
 }
 
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.position += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<W: Write + Seek> Seek for CountingWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.position = self.writer.seek(pos)?;
+        Ok(self.position)
+    }
+}
+
 //--------------------------------------
 // PPAC reader wrapper implementation
 //--------------------------------------
-const MAX_VERSION: u8 = 4;
+const MAX_VERSION: u8 = 5;
 
 impl<R: Read, P: ProtocolRW> PPACReader<R, P> {
     /// Opens a PPAC file.
@@ -196,6 +306,11 @@ impl<R: Read, P: ProtocolRW> PPACReader<R, P> {
         } else {
             PacketType::NGS
         };
+        let build = if version >= 5 {
+            reader.read_u32::<LittleEndian>()?
+        } else {
+            ProtocolVersion::LATEST_BUILD
+        };
         let reader = if version >= 4 {
             let enc_flag = reader.read_u8()?;
             match enc_flag {
@@ -205,17 +320,30 @@ impl<R: Read, P: ProtocolRW> PPACReader<R, P> {
         } else {
             ReaderWrapper::NoEnc(reader)
         };
+        let data_start = match version {
+            ..=2 => 5,
+            3 => 6,
+            4 => 7,
+            _ => 11,
+        };
         Ok(Self {
-            reader,
+            reader: Some(reader),
             version,
             packet_buffer: vec![],
             data_buffer: vec![],
             protocol_type,
+            build,
             last_header: Header {
                 time: Duration::new(0, 0),
                 direction: Direction::ToServer,
             },
             out_type: OutputType::Packet,
+            data_start,
+            record: 0,
+            pending: None,
+            index: None,
+            error_mode: ErrorMode::Stop,
+            is_done: false,
         })
     }
 
@@ -224,23 +352,26 @@ impl<R: Read, P: ProtocolRW> PPACReader<R, P> {
         self.out_type = out_type;
     }
 
+    /// Sets the behavior on packet parsing errors.
+    pub fn set_error_mode(&mut self, error_mode: ErrorMode) {
+        self.error_mode = error_mode;
+    }
+
     /// Returns the readers protocol type..
     pub fn get_protocol_type(&self) -> PacketType {
         self.protocol_type
     }
 
+    /// Returns the protocol version of the stored packets. Files created before the build was
+    /// recorded use [`ProtocolVersion::LATEST_BUILD`].
+    pub fn get_protocol_version(&self) -> ProtocolVersion {
+        ProtocolVersion::new(self.protocol_type, self.build)
+    }
+
     /// Reads a packet from the PPAC.
     pub fn read(&mut self) -> Result<Option<PacketData<P>>, PPACError> {
-        let packet = if !self.packet_buffer.is_empty() {
-            self.packet_buffer.drain(0..1).next()
-        } else {
-            None
-        };
-        let data = if !self.data_buffer.is_empty() {
-            self.data_buffer.drain(0..1).next()
-        } else {
-            None
-        };
+        let packet = take_first(&mut self.packet_buffer);
+        let data = take_first(&mut self.data_buffer);
         if packet.is_some() || data.is_some() {
             return Ok(Some(PacketData {
                 time: self.last_header.time,
@@ -251,40 +382,36 @@ impl<R: Read, P: ProtocolRW> PPACReader<R, P> {
                 parse_error: None,
             }));
         }
-        let time = match self.read_time() {
-            Ok(time) => time,
-            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
-            Err(e) => return Err(e.into()),
+        let Some((header, data)) = self.next_record()? else {
+            return Ok(None);
         };
-        let direction = match self.reader.read_u8()? {
-            0 => Direction::ToServer,
-            _ => Direction::ToClient,
-        };
-        self.last_header = Header { time, direction };
-        let len = self.reader.read_u64::<LittleEndian>()?;
-        let mut data = vec![];
-        self.reader.by_ref().take(len).read_to_end(&mut data)?;
+        let Header { time, direction } = header;
+        self.last_header = header;
         let mut parse_error = None;
         let (packet, data) = match self.out_type {
-            OutputType::Packet => {
-                self.read_packet(&data)?;
-                (self.packet_buffer.drain(0..1).next(), None)
-            }
+            OutputType::Packet => match self.read_packet(&data) {
+                Ok(_) => (take_first(&mut self.packet_buffer), None),
+                Err(e) if self.error_mode == ErrorMode::Continue => {
+                    parse_error = Some(e);
+                    (None, None)
+                }
+                Err(e) => return Err(e.into()),
+            },
             OutputType::Raw => {
                 self.read_data(&data)?;
-                (None, self.data_buffer.drain(0..1).next())
+                (None, take_first(&mut self.data_buffer))
             }
             OutputType::Both => {
                 let output = self.read_packet(&data);
                 let packet_data = match output {
-                    Ok(_) => self.packet_buffer.drain(0..1).next(),
+                    Ok(_) => take_first(&mut self.packet_buffer),
                     Err(e) => {
                         parse_error = Some(e);
                         None
                     }
                 };
                 self.read_data(&data)?;
-                (packet_data, self.data_buffer.drain(0..1).next())
+                (packet_data, take_first(&mut self.data_buffer))
             }
         };
         Ok(Some(PacketData {
@@ -298,13 +425,66 @@ impl<R: Read, P: ProtocolRW> PPACReader<R, P> {
     }
 
     // Returns the underlying reader.
-    pub fn into_inner(self) -> R {
-        self.reader.into_inner()
+    pub fn into_inner(mut self) -> R {
+        self.reader.take().unwrap().into_inner()
+    }
+
+    /// Returns the index used for seeking (if any).
+    pub fn index(&self) -> Option<&PPACIndex> {
+        self.index.as_ref()
+    }
+
+    /// Sets the index used for seeking. The index must be created for the same file.
+    pub fn set_index(&mut self, index: PPACIndex) {
+        self.index = Some(index);
+    }
+
+    /// Returns an iterator over the packets matching the filter, starting from the current
+    /// position.
+    ///
+    /// Only the packet headers are read for the records that don't match the filter. Packets of
+    /// a matching record that don't match the ID or category filters are skipped.
+    pub fn query(&mut self, filter: PPACFilter) -> PPACQuery<'_, R, P> {
+        PPACQuery {
+            reader: self,
+            filter,
+        }
+    }
+
+    fn has_buffered(&self) -> bool {
+        !self.packet_buffer.is_empty() || !self.data_buffer.is_empty()
+    }
+
+    /// Returns the pending record or reads the next one.
+    fn next_record(&mut self) -> Result<Option<(Header, Vec<u8>)>, PPACError> {
+        match self.pending.take() {
+            Some(record) => Ok(Some(record)),
+            None => self.read_record(),
+        }
+    }
+
+    /// Reads a single stored record.
+    fn read_record(&mut self) -> Result<Option<(Header, Vec<u8>)>, PPACError> {
+        let time = match self.read_time() {
+            Ok(time) => time,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let reader = self.reader.as_mut().unwrap();
+        let direction = match reader.read_u8()? {
+            0 => Direction::ToServer,
+            _ => Direction::ToClient,
+        };
+        let len = reader.read_u64::<LittleEndian>()?;
+        let mut data = vec![];
+        reader.by_ref().take(len).read_to_end(&mut data)?;
+        self.record += 1;
+        Ok(Some((Header { time, direction }, data)))
     }
 
     fn read_packet(&mut self, buf: &[u8]) -> Result<(), PacketError> {
         self.packet_buffer
-            .append(&mut P::read(buf, self.protocol_type)?);
+            .append(&mut P::read_versioned(buf, self.get_protocol_version())?);
         Ok(())
     }
 
@@ -320,47 +500,417 @@ impl<R: Read, P: ProtocolRW> PPACReader<R, P> {
     }
 
     fn read_time(&mut self) -> std::io::Result<Duration> {
+        let reader = self.reader.as_mut().unwrap();
         if (2..).contains(&self.version) {
             Ok(Duration::from_nanos(
-                self.reader.read_u128::<LittleEndian>()? as u64,
+                reader.read_u128::<LittleEndian>()? as u64
             ))
         } else {
-            Ok(Duration::from_secs(self.reader.read_u64::<LittleEndian>()?))
+            Ok(Duration::from_secs(reader.read_u64::<LittleEndian>()?))
         }
     }
 }
 
+impl<R: Read + Seek, P: ProtocolRW> PPACReader<R, P> {
+    /// Builds an index with a seek point every `interval` records and sets it as the current
+    /// index. The reader is rewound to the first record.
+    ///
+    /// # Note
+    ///
+    /// Offsets of records inside a zstd stream are unknown, so for packed files only the first
+    /// record is used as a seek point. Index collected by [`PPACWriter`] should be used instead.
+    pub fn build_index(&mut self, interval: u64) -> Result<&PPACIndex, PPACError> {
+        let interval = interval.max(1);
+        let is_packed = matches!(self.reader, Some(ReaderWrapper::Zstd(_)));
+        let time_size = if self.version >= 2 { 16 } else { 8 };
+        self.seek_raw(self.data_start, 0)?;
+        let mut index = PPACIndex::default();
+        let mut offset = self.data_start;
+        while let Some((header, data)) = self.read_record()? {
+            let record = self.record - 1;
+            if record % interval == 0 && (!is_packed || record == 0) {
+                index.entries.push(IndexEntry {
+                    record,
+                    time: header.time,
+                    offset,
+                });
+            }
+            offset += time_size + 1 + 8 + data.len() as u64;
+        }
+        self.seek_raw(self.data_start, 0)?;
+        Ok(self.index.insert(index))
+    }
+
+    /// Moves the reader to the specified record (i.e. the next [`PPACReader::read`] will return
+    /// the `record`th stored packet). If the file has less records, then the reader is moved to
+    /// the end.
+    pub fn seek_to_index(&mut self, record: u64) -> Result<(), PPACError> {
+        let entry = self.index.as_ref().and_then(|i| i.find_record(record));
+        self.seek_entry(entry, |r, _| r >= record)
+    }
+
+    /// Moves the reader to the first record with a timestamp not less than `time`. If no such
+    /// record exists, then the reader is moved to the end.
+    pub fn seek_to_time(&mut self, time: Duration) -> Result<(), PPACError> {
+        let entry = self.index.as_ref().and_then(|i| i.find_time(time));
+        self.seek_entry(entry, |_, h| h.time >= time)
+    }
+
+    fn seek_entry(
+        &mut self,
+        entry: Option<IndexEntry>,
+        is_target: impl Fn(u64, &Header) -> bool,
+    ) -> Result<(), PPACError> {
+        let (offset, record) = entry
+            .map(|e| (e.offset, e.record))
+            .unwrap_or((self.data_start, 0));
+        self.seek_raw(offset, record)?;
+        while let Some((header, data)) = self.read_record()? {
+            if is_target(self.record - 1, &header) {
+                self.pending = Some((header, data));
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+
+    fn seek_raw(&mut self, offset: u64, record: u64) -> Result<(), PPACError> {
+        let reader = self.reader.take().unwrap().seek_to(offset)?;
+        self.reader = Some(reader);
+        self.record = record;
+        self.is_done = false;
+        self.pending = None;
+        self.packet_buffer.clear();
+        self.data_buffer.clear();
+        Ok(())
+    }
+}
+
+/// Returns packets until the end of file or the first error.
+impl<R: Read, P: ProtocolRW> Iterator for PPACReader<R, P> {
+    type Item = Result<PacketData<P>, PPACError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+        let result = self.read().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.is_done = true;
+        }
+        result
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<R, P> PPACReader<R, P>
+where
+    R: Read + Send + 'static,
+    P: ProtocolRW + Send + 'static,
+{
+    /// Converts the reader into an asynchronous stream. The file is read in a separate thread.
+    pub fn into_stream(mut self) -> PPACStream<P> {
+        let (send, recv) = tokio::sync::mpsc::channel(64);
+        std::thread::spawn(move || {
+            for packet in &mut self {
+                if send.blocking_send(packet).is_err() {
+                    break;
+                }
+            }
+        });
+        PPACStream { recv }
+    }
+}
+
+/// Asynchronous stream of the packets read by [`PPACReader`].
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Debug)]
+pub struct PPACStream<P: ProtocolRW> {
+    recv: tokio::sync::mpsc::Receiver<Result<PacketData<P>, PPACError>>,
+}
+
+#[cfg(feature = "tokio")]
+impl<P: ProtocolRW> futures_core::Stream for PPACStream<P> {
+    type Item = Result<PacketData<P>, PPACError>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.recv.poll_recv(cx)
+    }
+}
+
+//--------------------------------------
+// PPAC filter implementation
+//--------------------------------------
+
+impl Default for PPACFilter {
+    fn default() -> Self {
+        Self {
+            direction: None,
+            start: Bound::Unbounded,
+            end: Bound::Unbounded,
+            ids: vec![],
+            categories: vec![],
+        }
+    }
+}
+
+impl PPACFilter {
+    /// Creates a filter that matches all records.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches only the records with the specified direction.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Matches only the records stored in the specified time range.
+    pub fn time_range(mut self, range: impl RangeBounds<Duration>) -> Self {
+        self.start = range.start_bound().cloned();
+        self.end = range.end_bound().cloned();
+        self
+    }
+
+    /// Matches the packets with the specified ID.
+    pub fn id(mut self, id: u8, subid: u16) -> Self {
+        self.ids.push((id, subid));
+        self
+    }
+
+    /// Matches the packets with the specified category.
+    pub fn category(mut self, category: PacketCategory) -> Self {
+        self.categories.push(category);
+        self
+    }
+
+    fn matches_header(&self, header: &Header) -> bool {
+        if self.direction.is_some_and(|d| d != header.direction) {
+            return false;
+        }
+        (self.start, self.end).contains(&header.time)
+    }
+
+    fn matches_all_packets(&self) -> bool {
+        self.ids.is_empty() && self.categories.is_empty()
+    }
+
+    fn matches_id<P: ProtocolRW>(&self, id: u8, subid: u16, packet_type: PacketType) -> bool {
+        let id_match = self.ids.is_empty() || self.ids.contains(&(id, subid));
+        let category_match = self.categories.is_empty()
+            || self
+                .categories
+                .contains(&P::category_for(id, subid, packet_type));
+        id_match && category_match
+    }
+
+    /// Returns `true` if any packet in the record matches the filter.
+    fn matches_data<P: ProtocolRW>(&self, data: &[u8], packet_type: PacketType) -> bool {
+        if self.matches_all_packets() {
+            return true;
+        }
+        PacketView::iter(data, packet_type)
+            .filter_map(Result::ok)
+            .any(|view| {
+                let header = view.header();
+                self.matches_id::<P>(header.id, header.subid, packet_type)
+            })
+    }
+
+    /// Returns `true` if a single packet of the accepted record matches the filter.
+    fn matches_packet<P: ProtocolRW>(&self, packet: &PacketData<P>) -> bool {
+        if self.matches_all_packets() {
+            return true;
+        }
+        let packet_type = packet.protocol_type;
+        let header = match (&packet.packet, &packet.data) {
+            (_, Some(data)) => PacketView::new(data, packet_type)
+                .ok()
+                .map(|view| view.header().clone()),
+            (Some(packet), None) => packet.header(packet_type),
+            // the record failed to parse, but some of its packets matched
+            (None, None) => return true,
+        };
+        header.is_some_and(|header| self.matches_id::<P>(header.id, header.subid, packet_type))
+    }
+}
+
+impl<R: Read, P: ProtocolRW> Iterator for PPACQuery<'_, R, P> {
+    type Item = Result<PacketData<P>, PPACError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // the rest of the accepted record is already buffered
+            if !self.reader.has_buffered() {
+                let (header, data) = match self.reader.next_record() {
+                    Ok(Some(record)) => record,
+                    Ok(None) => return None,
+                    Err(e) => return Some(Err(e)),
+                };
+                if !self.filter.matches_header(&header)
+                    || !self
+                        .filter
+                        .matches_data::<P>(&data, self.reader.protocol_type)
+                {
+                    continue;
+                }
+                self.reader.pending = Some((header, data));
+            }
+            match self.reader.read() {
+                Ok(Some(packet)) if !self.filter.matches_packet(&packet) => continue,
+                result => return result.transpose(),
+            }
+        }
+    }
+}
+
+//--------------------------------------
+// PPAC index implementation
+//--------------------------------------
+
+impl PPACIndex {
+    /// Returns all seek points.
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    /// Returns the last seek point before or at the specified record.
+    pub fn find_record(&self, record: u64) -> Option<IndexEntry> {
+        let pos = self.entries.partition_point(|e| e.record <= record);
+        pos.checked_sub(1).map(|i| self.entries[i])
+    }
+
+    /// Returns the last seek point before the specified time.
+    pub fn find_time(&self, time: Duration) -> Option<IndexEntry> {
+        let pos = self.entries.partition_point(|e| e.time < time);
+        pos.checked_sub(1).map(|i| self.entries[i])
+    }
+
+    /// Reads an index file.
+    pub fn read(mut reader: impl Read) -> Result<Self, PPACError> {
+        let mut header = [0u8; 4];
+        reader.read_exact(&mut header)?;
+        if &header != b"PPIX" {
+            return Err(PPACError::InvalidIndex);
+        }
+        let version = reader.read_u8()?;
+        if version != 1 {
+            return Err(PPACError::UnsupportedVersion(version));
+        }
+        let len = reader.read_u64::<LittleEndian>()?;
+        let mut entries = vec![];
+        for _ in 0..len {
+            let record = reader.read_u64::<LittleEndian>()?;
+            let time = Duration::from_nanos(reader.read_u128::<LittleEndian>()? as u64);
+            let offset = reader.read_u64::<LittleEndian>()?;
+            entries.push(IndexEntry {
+                record,
+                time,
+                offset,
+            });
+        }
+        Ok(Self { entries })
+    }
+
+    /// Writes an index file.
+    pub fn write(&self, mut writer: impl Write) -> Result<(), PPACError> {
+        writer.write_all(b"PPIX")?;
+        writer.write_u8(1)?;
+        writer.write_u64::<LittleEndian>(self.entries.len() as u64)?;
+        for entry in &self.entries {
+            writer.write_u64::<LittleEndian>(entry.record)?;
+            writer.write_u128::<LittleEndian>(entry.time.as_nanos())?;
+            writer.write_u64::<LittleEndian>(entry.offset)?;
+        }
+        Ok(())
+    }
+}
+
 //--------------------------------------
 // PPAC writer wrapper implementation
 //--------------------------------------
 
 impl<W: Write> PPACWriter<W> {
-    /// Creates a new PPAC file.
+    /// Creates a new PPAC file. The packet type and the game build of `version` are stored in
+    /// the file header.
     pub fn new(
         mut writer: W,
-        packet_type: PacketType,
+        version: impl Into<ProtocolVersion>,
         is_enc: bool,
     ) -> Result<PPACWriter<W>, PPACError> {
+        let version = version.into();
         writer.write_all(b"PPAC")?;
-        writer.write_u8(4)?;
-        writer.write_u8(match packet_type {
-            PacketType::Classic => 0,
-            PacketType::NGS => 1,
-            PacketType::NA => 2,
-            PacketType::JP => 3,
-            PacketType::Vita => 4,
-            PacketType::Raw => return Err(PPACError::InvalidPacketType(5)),
-        })?;
+        writer.write_u8(MAX_VERSION)?;
+        writer.write_u8(packet_type_id(version.packet_type)?)?;
+        writer.write_u32::<LittleEndian>(version.build)?;
         writer.write_u8(is_enc as u8)?;
+        let writer = CountingWriter {
+            writer,
+            position: 11,
+        };
         let writer = Some(match is_enc {
             true => WriterWrapper::Zstd(Encoder::new(writer, 3)?),
             false => WriterWrapper::NoEnc(writer),
         });
         Ok(Self {
             writer,
-            packet_type,
+            version,
+            record: 0,
+            index_interval: None,
+            index: PPACIndex::default(),
+            write_buffer: Vec::new(),
         })
     }
+
+    /// Enables collection of an index with a seek point every `interval` records, starting from
+    /// the next written record.
+    ///
+    /// If the file is packed, then a new zstd frame is started at every seek point.
+    pub fn enable_index(&mut self, interval: u64) {
+        self.index_interval = Some(interval.max(1));
+    }
+
+    /// Returns the collected index. It should be stored alongside the file (e.g. using
+    /// [`PPACIndex::write`]).
+    pub fn index(&self) -> &PPACIndex {
+        &self.index
+    }
+
+    fn add_seek_point(&mut self, time: Duration) -> Result<(), PPACError> {
+        let Some(interval) = self.index_interval else {
+            return Ok(());
+        };
+        let is_due = match self.index.entries.last() {
+            Some(entry) => self.record - entry.record >= interval,
+            None => true,
+        };
+        if !is_due {
+            return Ok(());
+        }
+        let writer = match self.writer.take().unwrap() {
+            WriterWrapper::Zstd(e) if self.record != 0 => {
+                WriterWrapper::Zstd(Encoder::new(e.finish()?, 3)?)
+            }
+            writer => writer,
+        };
+        let offset = match &writer {
+            WriterWrapper::NoEnc(w) => w.position,
+            WriterWrapper::Zstd(e) => e.get_ref().position,
+        };
+        self.writer = Some(writer);
+        self.index.entries.push(IndexEntry {
+            record: self.record,
+            time,
+            offset,
+        });
+        Ok(())
+    }
     fn write_header(
         &mut self,
         time: Duration,
@@ -383,8 +933,10 @@ impl<W: Write> PPACWriter<W> {
         direction: Direction,
         input: &[u8],
     ) -> Result<(), PPACError> {
+        self.add_seek_point(time)?;
         self.write_header(time, direction, input.len() as u64)?;
         self.writer.as_mut().unwrap().write_all(input)?;
+        self.record += 1;
         Ok(())
     }
     /// Writes data (must be valid packet data).
@@ -420,36 +972,42 @@ impl<W: Write> PPACWriter<W> {
         direction: Direction,
         input: &impl ProtocolRW,
     ) -> Result<(), PPACError> {
-        let data = input.write(self.packet_type);
-        self.write_data_unchecked(time, direction, &data)?;
-        Ok(())
+        let mut data = std::mem::take(&mut self.write_buffer);
+        data.clear();
+        let result = input
+            .write_versioned(&mut data, &self.version)
+            .map_err(PPACError::from)
+            .and_then(|_| self.write_data_unchecked(time, direction, &data));
+        self.write_buffer = data;
+        result
     }
 
     // Returns the underlying writer.
     pub fn into_inner(mut self) -> std::io::Result<W> {
-        self.writer.take().unwrap().into_inner()
+        Ok(self.writer.take().unwrap().into_inner()?.writer)
     }
 }
 
 impl<W: Write + Seek> PPACWriter<W> {
     /// Changes stored client type.
     pub fn change_packet_type(&mut self, packet_type: PacketType) -> Result<(), PPACError> {
-        if matches!(packet_type, PacketType::Raw) {
-            return Err(PPACError::InvalidPacketType(5));
-        }
+        self.change_version(ProtocolVersion {
+            packet_type,
+            ..self.version
+        })
+    }
+
+    /// Changes stored client type and game build.
+    pub fn change_version(&mut self, version: ProtocolVersion) -> Result<(), PPACError> {
+        let mut header = [0; 5];
+        header[0] = packet_type_id(version.packet_type)?;
+        header[1..].copy_from_slice(&version.build.to_le_bytes());
         let writer = self.writer.as_mut().unwrap();
         let curr_pos = writer.stream_position()?;
         writer.seek(SeekFrom::Start(5))?;
-        writer.write_u8_raw(match packet_type {
-            PacketType::Classic => 0,
-            PacketType::NGS => 1,
-            PacketType::NA => 2,
-            PacketType::JP => 3,
-            PacketType::Vita => 4,
-            PacketType::Raw => unreachable!(),
-        })?;
+        writer.write_all_raw(&header)?;
         writer.seek(SeekFrom::Start(curr_pos))?;
-        self.packet_type = packet_type;
+        self.version = version;
         Ok(())
     }
 }
@@ -462,9 +1020,232 @@ impl<W: Write> Drop for PPACWriter<W> {
     }
 }
 
+/// Returns the ID of the packet type stored in the file header.
+fn packet_type_id(packet_type: PacketType) -> Result<u8, PPACError> {
+    match packet_type {
+        PacketType::Classic => Ok(0),
+        PacketType::NGS => Ok(1),
+        PacketType::NA => Ok(2),
+        PacketType::JP => Ok(3),
+        PacketType::Vita => Ok(4),
+        PacketType::Raw => Err(PPACError::InvalidPacketType(5)),
+    }
+}
+
+/// Removes the first element of the buffer (the buffer is empty if a record contains no packets).
+fn take_first<T>(buffer: &mut Vec<T>) -> Option<T> {
+    (!buffer.is_empty()).then(|| buffer.remove(0))
+}
+
 #[cfg(feature = "connection")]
 pub(crate) fn get_now() -> Duration {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, ErrorMode, OutputType, PPACFilter, PPACIndex, PPACReader, PPACWriter};
+    use crate::protocol::{
+        Packet, PacketCategory, PacketType, PacketView, ProtocolRW, ProtocolVersion,
+    };
+    use std::{io::Cursor, time::Duration};
+
+    fn write_file(is_enc: bool) -> (Vec<u8>, PPACIndex) {
+        let mut writer = PPACWriter::new(Cursor::new(vec![]), PacketType::NGS, is_enc).unwrap();
+        writer.enable_index(3);
+        for i in 0..10 {
+            let time = Duration::from_secs(i);
+            writer
+                .write_packet(time, Direction::ToServer, &Packet::ServerPing)
+                .unwrap();
+        }
+        let index = writer.index().clone();
+        (writer.into_inner().unwrap().into_inner(), index)
+    }
+
+    #[test]
+    fn seek() {
+        for is_enc in [false, true] {
+            let (data, index) = write_file(is_enc);
+            assert_eq!(index.entries().len(), 4);
+            let mut stored = vec![];
+            index.write(&mut stored).unwrap();
+            let index = PPACIndex::read(&stored[..]).unwrap();
+
+            let mut reader = PPACReader::<_, Packet>::open(Cursor::new(data)).unwrap();
+            reader.set_index(index);
+            reader.seek_to_index(7).unwrap();
+            assert_eq!(reader.read().unwrap().unwrap().time, Duration::from_secs(7));
+            reader.seek_to_time(Duration::from_millis(4500)).unwrap();
+            assert_eq!(reader.read().unwrap().unwrap().time, Duration::from_secs(5));
+            reader.seek_to_index(1).unwrap();
+            assert_eq!(reader.read().unwrap().unwrap().time, Duration::from_secs(1));
+            reader.seek_to_index(10).unwrap();
+            assert!(reader.read().unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn build_index() {
+        let (data, index) = write_file(false);
+        let mut reader = PPACReader::<_, Packet>::open(Cursor::new(data)).unwrap();
+        assert_eq!(reader.build_index(3).unwrap(), &index);
+        assert_eq!(reader.read().unwrap().unwrap().time, Duration::ZERO);
+    }
+
+    #[test]
+    fn query() {
+        let mut writer = PPACWriter::new(Cursor::new(vec![]), PacketType::NGS, false).unwrap();
+        for i in 0..10 {
+            let (direction, packet) = match i % 3 {
+                0 => (Direction::ToClient, Packet::ServerPing),
+                1 => (Direction::ToServer, Packet::ServerPong),
+                _ => (Direction::ToServer, Packet::ClientPing(Default::default())),
+            };
+            writer
+                .write_packet(Duration::from_secs(i), direction, &packet)
+                .unwrap();
+        }
+        let data = writer.into_inner().unwrap().into_inner();
+        let mut reader = PPACReader::<_, Packet>::open(Cursor::new(data)).unwrap();
+        let times = |reader: &mut PPACReader<_, Packet>, filter| {
+            reader.seek_to_index(0).unwrap();
+            reader
+                .query(filter)
+                .map(|p| p.unwrap().time.as_secs())
+                .collect::<Vec<_>>()
+        };
+
+        let filter = PPACFilter::new().direction(Direction::ToClient);
+        assert_eq!(times(&mut reader, filter), [0, 3, 6, 9]);
+        let filter = PPACFilter::new().category(PacketCategory::Login);
+        assert_eq!(times(&mut reader, filter), [2, 5, 8]);
+        let filter = PPACFilter::new()
+            .id(0x03, 0x0C)
+            .time_range(Duration::from_secs(2)..Duration::from_secs(7));
+        assert_eq!(times(&mut reader, filter), [4]);
+    }
+
+    #[test]
+    fn query_multi_packet_record() {
+        let mut writer = PPACWriter::new(Cursor::new(vec![]), PacketType::NGS, false).unwrap();
+        let mut data = Packet::ServerPing.write(PacketType::NGS);
+        data.append(&mut Packet::ClientPing(Default::default()).write(PacketType::NGS));
+        data.append(&mut Packet::ServerPong.write(PacketType::NGS));
+        writer
+            .write_data_unchecked(Duration::ZERO, Direction::ToServer, &data)
+            .unwrap();
+        let data = writer.into_inner().unwrap().into_inner();
+        let mut reader = PPACReader::<_, Packet>::open(Cursor::new(data)).unwrap();
+        for out_type in [OutputType::Packet, OutputType::Raw, OutputType::Both] {
+            let has_packet = !matches!(out_type, OutputType::Raw);
+            let has_data = !matches!(out_type, OutputType::Packet);
+            reader.set_out_type(out_type);
+            reader.seek_to_index(0).unwrap();
+            let filter = PPACFilter::new().category(PacketCategory::Login);
+            let packets: Vec<_> = reader.query(filter).map(|p| p.unwrap()).collect();
+            assert_eq!(packets.len(), 1);
+            if has_packet {
+                assert!(matches!(packets[0].packet, Some(Packet::ClientPing(_))));
+            }
+            if has_data {
+                let data = packets[0].data.as_ref().unwrap();
+                let view = PacketView::new(data, PacketType::NGS).unwrap();
+                assert_eq!((view.header().id, view.header().subid), (0x11, 0x0D));
+            }
+        }
+    }
+
+    fn corrupted_file() -> Vec<u8> {
+        let mut writer = PPACWriter::new(Cursor::new(vec![]), PacketType::NGS, false).unwrap();
+        let time = Duration::ZERO;
+        let ping = Packet::ClientPing(Default::default()).write(PacketType::NGS);
+        writer
+            .write_packet(time, Direction::ToServer, &Packet::ServerPing)
+            .unwrap();
+        writer
+            .write_data_unchecked(time, Direction::ToServer, &ping[..8])
+            .unwrap();
+        writer
+            .write_packet(time, Direction::ToServer, &Packet::ServerPong)
+            .unwrap();
+        writer.into_inner().unwrap().into_inner()
+    }
+
+    #[test]
+    fn iterator() {
+        let reader = PPACReader::<_, Packet>::open(Cursor::new(corrupted_file())).unwrap();
+        let results: Vec<_> = reader.map(|p| p.is_ok()).collect();
+        assert_eq!(results, [true, false]);
+
+        let mut reader = PPACReader::<_, Packet>::open(Cursor::new(corrupted_file())).unwrap();
+        reader.set_error_mode(ErrorMode::Continue);
+        let results: Vec<_> = reader.map(|p| p.unwrap().packet).collect();
+        assert_eq!(
+            results,
+            [Some(Packet::ServerPing), None, Some(Packet::ServerPong)]
+        );
+    }
+
+    #[test]
+    fn protocol_version() {
+        for is_enc in [false, true] {
+            let version = ProtocolVersion::new(PacketType::NGS, 1000);
+            let mut writer = PPACWriter::new(Cursor::new(vec![]), version, is_enc).unwrap();
+            writer
+                .write_packet(Duration::ZERO, Direction::ToServer, &Packet::ServerPing)
+                .unwrap();
+            let version = ProtocolVersion::new(PacketType::NGS, 2000);
+            writer.change_version(version).unwrap();
+            writer
+                .write_packet(Duration::ZERO, Direction::ToServer, &Packet::ServerPong)
+                .unwrap();
+            let data = writer.into_inner().unwrap().into_inner();
+
+            let mut reader = PPACReader::<_, Packet>::open(Cursor::new(data)).unwrap();
+            assert_eq!(reader.get_protocol_version(), version);
+            assert_eq!(
+                reader.read().unwrap().unwrap().packet,
+                Some(Packet::ServerPing)
+            );
+            assert_eq!(
+                reader.read().unwrap().unwrap().packet,
+                Some(Packet::ServerPong)
+            );
+        }
+
+        // files without a stored build use the newest layout
+        let mut data = b"PPAC\x04\x01\x00".to_vec();
+        data.extend_from_slice(&[0; 16]);
+        data.push(0);
+        data.extend_from_slice(&8u64.to_le_bytes());
+        data.extend_from_slice(&Packet::ServerPing.write(PacketType::NGS));
+        let mut reader = PPACReader::<_, Packet>::open(Cursor::new(data)).unwrap();
+        assert_eq!(
+            reader.get_protocol_version(),
+            ProtocolVersion::latest(PacketType::NGS)
+        );
+        assert_eq!(
+            reader.read().unwrap().unwrap().packet,
+            Some(Packet::ServerPing)
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn stream() {
+        use futures_core::Stream;
+
+        let reader = PPACReader::<_, Packet>::open(Cursor::new(corrupted_file())).unwrap();
+        let mut stream = reader.into_stream();
+        let mut results = vec![];
+        while let Some(packet) =
+            std::future::poll_fn(|cx| std::pin::Pin::new(&mut stream).poll_next(cx)).await
+        {
+            results.push(packet.is_ok());
+        }
+        assert_eq!(results, [true, false]);
+    }
+}
@@ -12,6 +12,7 @@ use super::{HelperReadWrite, ObjectHeader, PacketReadWrite};
 /// Response to: [`crate::protocol::Packet::ChatMessage`] (C->S)
 /// Respond with: [`crate::protocol::Packet::ChatMessage`] (S->C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x07, 0x00)]
@@ -43,6 +44,7 @@ pub struct ChatMessage {
 
 /// Possible message channels.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, Default, PartialEq, HelperReadWrite)]
 pub enum MessageChannel {
@@ -0,0 +1,265 @@
+use super::{write_magic, FieldTrace, PacketError, ParseBudget, ProtocolVersion};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io::{Read, Seek, SeekFrom};
+
+/// State of a single packet read.
+///
+/// The context is passed to
+/// [`HelperReadWrite::read_limited`](super::HelperReadWrite::read_limited) and carries the
+/// [`ParseBudget`] and the game build (see [`ProtocolVersion`]) of the packet. Lenient contexts (see [`ReadContext::lenient`]) additionally
+/// accept some non-canonical encodings and record them as [`Deviation`]s. Tracing contexts (see
+/// [`ReadContext::with_trace`]) record every read packet field in a [`FieldTrace`].
+#[derive(Debug, Clone)]
+pub struct ReadContext {
+    budget: ParseBudget,
+    build: u32,
+    deviations: Option<Vec<Deviation>>,
+    end: Option<u64>,
+    trace: Option<FieldTrace>,
+}
+
+/// Non-canonical encoding of a single field found by a lenient read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deviation {
+    /// Kind of the deviation.
+    pub kind: DeviationKind,
+    /// Offset of the field data.
+    pub offset: usize,
+    /// Field data as it was received.
+    pub original: Vec<u8>,
+    /// Field data as it would be written by the library.
+    pub canonical: Vec<u8>,
+}
+
+/// Kind of the non-canonical encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DeviationKind {
+    /// Length was zero instead of a magicked zero.
+    Length,
+    /// String contained data after the null terminator or had a different length.
+    String,
+    /// Padding wasn't zeroed.
+    Padding,
+}
+
+impl ReadContext {
+    /// Creates a new context.
+    pub fn new(budget: ParseBudget) -> Self {
+        Self {
+            budget,
+            build: ProtocolVersion::LATEST_BUILD,
+            deviations: None,
+            end: None,
+            trace: None,
+        }
+    }
+
+    /// Creates a context that records non-canonical encodings.
+    pub fn lenient(budget: ParseBudget) -> Self {
+        Self {
+            deviations: Some(vec![]),
+            ..Self::new(budget)
+        }
+    }
+
+    /// Sets the game build of the parsed packet.
+    pub fn with_build(mut self, build: u32) -> Self {
+        self.build = build;
+        self
+    }
+
+    /// Changes the game build of the parsed packet.
+    pub fn set_build(&mut self, build: u32) {
+        self.build = build;
+    }
+
+    /// Enables recording of the read packet fields.
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(FieldTrace::new());
+        self
+    }
+
+    /// Returns `true` if the context records non-canonical encodings.
+    pub fn is_lenient(&self) -> bool {
+        self.deviations.is_some()
+    }
+
+    /// Returns the allocation budget of the packet.
+    pub fn budget(&self) -> &ParseBudget {
+        &self.budget
+    }
+
+    /// Returns the game build of the parsed packet.
+    pub fn build(&self) -> u32 {
+        self.build
+    }
+
+    /// Reserves space for `count` elements of `size` bytes each (see [`ParseBudget::allocate`]).
+    pub fn allocate(&mut self, count: usize, size: usize) -> Result<(), PacketError> {
+        self.budget.allocate(count, size)
+    }
+
+    /// Returns the recorded deviations in the order of their offsets.
+    pub fn deviations(&self) -> &[Deviation] {
+        self.deviations.as_deref().unwrap_or_default()
+    }
+
+    /// Removes and returns the recorded deviations.
+    pub fn take_deviations(&mut self) -> Vec<Deviation> {
+        self.deviations
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Returns the recorded packet fields (if tracing is enabled).
+    pub fn trace(&self) -> Option<&FieldTrace> {
+        self.trace.as_ref()
+    }
+
+    /// Removes and returns the recorded packet fields (if tracing is enabled).
+    pub fn take_trace(&mut self) -> Option<FieldTrace> {
+        self.trace.as_mut().map(std::mem::take)
+    }
+
+    #[doc(hidden)]
+    pub fn trace_start(&self, reader: &mut impl Seek) -> Option<u64> {
+        self.trace
+            .as_ref()
+            .map(|_| reader.stream_position().unwrap_or_default())
+    }
+
+    #[doc(hidden)]
+    pub fn trace_field(
+        &mut self,
+        name: &'static str,
+        start: Option<u64>,
+        reader: &mut impl Seek,
+        value: impl FnOnce() -> Option<String>,
+    ) {
+        if let (Some(trace), Some(start)) = (&mut self.trace, start) {
+            trace.push(name, start, reader, value());
+        }
+    }
+
+    /// Returns the position where the packet data ended (if recorded).
+    pub fn end(&self) -> Option<u64> {
+        self.end
+    }
+
+    /// Records the current position as the end of the packet data.
+    pub fn record_end(&mut self, reader: &mut impl Seek) -> std::io::Result<()> {
+        if self.is_lenient() {
+            self.end = Some(reader.stream_position()?);
+        }
+        Ok(())
+    }
+
+    /// Reads a magicked length (see [`read_magic`](super::read_magic)).
+    ///
+    /// Lenient contexts also accept zero instead of a magicked zero.
+    pub fn read_len(
+        &mut self,
+        reader: &mut (impl Read + Seek),
+        sub: u32,
+        xor: u32,
+    ) -> std::io::Result<u32> {
+        let offset = self.position(reader)?;
+        let value = reader.read_u32::<LittleEndian>()?;
+        let zero = write_magic(0, sub, xor);
+        if let Some(offset) = offset.filter(|_| value == 0 && zero != 0) {
+            self.push(Deviation {
+                kind: DeviationKind::Length,
+                offset: offset as usize,
+                original: vec![0; 4],
+                canonical: zero.to_le_bytes().to_vec(),
+            });
+            return Ok(0);
+        }
+        (value ^ xor).checked_sub(sub).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid magic value")
+        })
+    }
+
+    /// Skips `len` bytes of padding.
+    ///
+    /// Lenient contexts read the padding and record it if it isn't zeroed.
+    pub fn skip_padding(
+        &mut self,
+        reader: &mut (impl Read + Seek),
+        len: u64,
+    ) -> std::io::Result<()> {
+        let Some(offset) = self.position(reader)? else {
+            reader.seek(SeekFrom::Current(len as i64))?;
+            return Ok(());
+        };
+        let mut original = vec![];
+        reader.by_ref().take(len).read_to_end(&mut original)?;
+        // padding may extend past the end of the data
+        reader.seek(SeekFrom::Start(offset + len))?;
+        if original.iter().any(|&b| b != 0) {
+            self.push(Deviation {
+                kind: DeviationKind::Padding,
+                offset: offset as usize,
+                canonical: vec![0; original.len()],
+                original,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the position of the reader if the context is lenient. The returned position is
+    /// passed to [`ReadContext::check_field`] after the field is read.
+    pub fn position(&self, reader: &mut impl Seek) -> std::io::Result<Option<u64>> {
+        match self.is_lenient() {
+            true => reader.stream_position().map(Some),
+            false => Ok(None),
+        }
+    }
+
+    /// Compares the field data read since `start` with its canonical encoding and records the
+    /// field if they differ. Deviations recorded inside the field are replaced.
+    ///
+    /// Does nothing if `start` is `None`.
+    pub fn check_field(
+        &mut self,
+        reader: &mut (impl Read + Seek),
+        start: Option<u64>,
+        kind: DeviationKind,
+        canonical: impl FnOnce() -> Vec<u8>,
+    ) -> std::io::Result<()> {
+        let Some(start) = start else {
+            return Ok(());
+        };
+        let end = reader.stream_position()?;
+        let mut original = vec![0; (end - start) as usize];
+        reader.seek(SeekFrom::Start(start))?;
+        reader.read_exact(&mut original)?;
+        if let Some(deviations) = &mut self.deviations {
+            deviations.retain(|d| (d.offset as u64) < start);
+        }
+        let canonical = canonical();
+        if original != canonical {
+            self.push(Deviation {
+                kind,
+                offset: start as usize,
+                original,
+                canonical,
+            });
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, deviation: Deviation) {
+        if let Some(deviations) = &mut self.deviations {
+            deviations.push(deviation);
+        }
+    }
+}
+
+impl Default for ReadContext {
+    fn default() -> Self {
+        Self::new(ParseBudget::default())
+    }
+}
@@ -1,11 +1,14 @@
 use crate::{asciistring::StringRW, AsciiString};
 
-use super::{read_magic, write_magic, HelperReadWrite, PacketError};
+use super::{
+    read_magic, write_magic, DeviationKind, HelperReadWrite, PacketError, ProtocolVersion,
+    ReadContext, TypeDesc,
+};
 use half::f16;
-use std::{net::Ipv4Addr, time::Duration};
+use std::{io::Read, net::Ipv4Addr, time::Duration};
 
 macro_rules! helper_int {
-    ($name:ty; $read:ident, $write:ident) => {
+    ($name:ty; $read:ident, $write:ident; $desc:expr) => {
         impl HelperReadWrite for $name {
             fn read(
                 reader: &mut (impl std::io::Read + std::io::Seek),
@@ -38,8 +41,15 @@ macro_rules! helper_int {
                     error: e,
                 })
             }
+
+            fn type_desc() -> TypeDesc {
+                $desc
+            }
         }
     };
+    ($name:ty; $read:ident, $write:ident) => {
+        helper_int!($name; $read, $write; TypeDesc::Primitive(stringify!($name)));
+    };
     ($name:ty, $($name_r:ty),+;$read:ident, $write:ident) => {
         helper_int!($name; $read, $write);
         helper_int!($($name_r),+; $read, $write);
@@ -47,7 +57,7 @@ macro_rules! helper_int {
 }
 
 helper_int!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f16, f32, f64; from_le_bytes, to_le_bytes);
-helper_int!(Ipv4Addr; from, octets);
+helper_int!(Ipv4Addr; from, octets; TypeDesc::Ipv4Addr);
 
 impl<T: HelperReadWrite> HelperReadWrite for Box<T> {
     fn read(
@@ -56,7 +66,27 @@ impl<T: HelperReadWrite> HelperReadWrite for Box<T> {
         xor: u32,
         sub: u32,
     ) -> Result<Self, PacketError> {
-        T::read(reader, packet_type, xor, sub).map(Box::new)
+        Self::read_limited(reader, packet_type, xor, sub, &mut Default::default())
+    }
+
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: super::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, PacketError> {
+        T::read_limited(reader, packet_type, xor, sub, ctx).map(Box::new)
+    }
+
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: super::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<(), PacketError> {
+        T::skip(reader, packet_type, xor, sub, ctx)
     }
 
     fn write(
@@ -66,7 +96,25 @@ impl<T: HelperReadWrite> HelperReadWrite for Box<T> {
         xor: u32,
         sub: u32,
     ) -> Result<(), PacketError> {
-        self.as_ref().write(writer, packet_type, xor, sub)
+        self.write_versioned(writer, &ProtocolVersion::latest(packet_type), xor, sub)
+    }
+
+    fn write_versioned(
+        &self,
+        writer: &mut impl std::io::Write,
+        version: &ProtocolVersion,
+        xor: u32,
+        sub: u32,
+    ) -> Result<(), PacketError> {
+        self.as_ref().write_versioned(writer, version, xor, sub)
+    }
+
+    fn serialized_len(&self, packet_type: super::PacketType) -> usize {
+        self.as_ref().serialized_len(packet_type)
+    }
+
+    fn type_desc() -> TypeDesc {
+        T::type_desc()
     }
 }
 
@@ -76,18 +124,30 @@ impl<T: HelperReadWrite, const N: usize> HelperReadWrite for [T; N] {
         packet_type: super::PacketType,
         xor: u32,
         sub: u32,
+    ) -> Result<Self, PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, &mut Default::default())
+    }
+
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: super::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
     ) -> Result<Self, PacketError> {
         let mut arr = vec![];
         arr.reserve_exact(N);
 
-        for _ in 0..N {
-            arr.push(T::read(reader, packet_type, xor, sub).map_err(|e| {
-                PacketError::CompositeFieldError {
-                    packet_name: "array",
-                    field_name: "value",
-                    error: e.into(),
-                }
-            })?);
+        for i in 0..N {
+            arr.push(
+                T::read_limited(reader, packet_type, xor, sub, ctx).map_err(|e| {
+                    PacketError::ElementError {
+                        packet_name: "array",
+                        index: i,
+                        error: e.into(),
+                    }
+                })?,
+            );
         }
 
         if let Ok(arr) = arr.try_into() {
@@ -97,6 +157,23 @@ impl<T: HelperReadWrite, const N: usize> HelperReadWrite for [T; N] {
         }
     }
 
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: super::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<(), PacketError> {
+        for i in 0..N {
+            T::skip(reader, packet_type, xor, sub, ctx).map_err(|e| PacketError::ElementError {
+                packet_name: "array",
+                index: i,
+                error: e.into(),
+            })?;
+        }
+        Ok(())
+    }
+
     fn write(
         &self,
         writer: &mut impl std::io::Write,
@@ -104,17 +181,38 @@ impl<T: HelperReadWrite, const N: usize> HelperReadWrite for [T; N] {
         xor: u32,
         sub: u32,
     ) -> Result<(), PacketError> {
-        for i in self {
-            i.write(writer, packet_type, xor, sub).map_err(|e| {
-                PacketError::CompositeFieldError {
+        self.write_versioned(writer, &ProtocolVersion::latest(packet_type), xor, sub)
+    }
+
+    fn write_versioned(
+        &self,
+        writer: &mut impl std::io::Write,
+        version: &ProtocolVersion,
+        xor: u32,
+        sub: u32,
+    ) -> Result<(), PacketError> {
+        for (index, i) in self.iter().enumerate() {
+            i.write_versioned(writer, version, xor, sub).map_err(|e| {
+                PacketError::ElementError {
                     packet_name: "array",
-                    field_name: "value",
+                    index,
                     error: e.into(),
                 }
             })?;
         }
         Ok(())
     }
+
+    fn serialized_len(&self, packet_type: super::PacketType) -> usize {
+        self.iter().map(|i| i.serialized_len(packet_type)).sum()
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::Array {
+            n: N,
+            of: Box::new(T::type_desc()),
+        }
+    }
 }
 
 impl HelperReadWrite for Duration {
@@ -148,20 +246,44 @@ impl HelperReadWrite for Duration {
                 error: e.into(),
             })
     }
+
+    fn serialized_len(&self, _: crate::protocol::PacketType) -> usize {
+        4
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::Duration
+    }
 }
 
 impl HelperReadWrite for String {
     fn read(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+    ) -> Result<Self, crate::protocol::PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, &mut Default::default())
+    }
+
+    fn read_limited(
         reader: &mut (impl std::io::Read + std::io::Seek),
         _: crate::protocol::PacketType,
         xor: u32,
         sub: u32,
+        ctx: &mut ReadContext,
     ) -> Result<Self, crate::protocol::PacketError> {
-        <String as StringRW>::read_variable(reader, sub, xor).map_err(|e| PacketError::FieldError {
-            packet_name: "String",
-            field_name: "str",
-            error: e,
-        })
+        read_string(reader, xor, sub, ctx, "String", 2)
+    }
+
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        _: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        _: &mut ReadContext,
+    ) -> Result<(), crate::protocol::PacketError> {
+        skip_string::<Self>(reader, xor, sub, "String", 2)
     }
 
     fn write(
@@ -179,22 +301,44 @@ impl HelperReadWrite for String {
                 error: e,
             })
     }
+
+    fn serialized_len(&self, _: crate::protocol::PacketType) -> usize {
+        string_len(self.encode_utf16().count(), 2)
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::String
+    }
 }
 
 impl HelperReadWrite for AsciiString {
     fn read(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+    ) -> Result<Self, crate::protocol::PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, &mut Default::default())
+    }
+
+    fn read_limited(
         reader: &mut (impl std::io::Read + std::io::Seek),
         _: crate::protocol::PacketType,
         xor: u32,
         sub: u32,
+        ctx: &mut ReadContext,
     ) -> Result<Self, crate::protocol::PacketError> {
-        <AsciiString as StringRW>::read_variable(reader, sub, xor).map_err(|e| {
-            PacketError::FieldError {
-                packet_name: "AsciiString",
-                field_name: "str",
-                error: e,
-            }
-        })
+        read_string(reader, xor, sub, ctx, "AsciiString", 1)
+    }
+
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        _: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        _: &mut ReadContext,
+    ) -> Result<(), crate::protocol::PacketError> {
+        skip_string::<Self>(reader, xor, sub, "AsciiString", 1)
     }
 
     fn write(
@@ -212,6 +356,14 @@ impl HelperReadWrite for AsciiString {
                 error: e,
             })
     }
+
+    fn serialized_len(&self, _: crate::protocol::PacketType) -> usize {
+        string_len(self.len(), 1)
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::AsciiString
+    }
 }
 
 impl<T: HelperReadWrite> HelperReadWrite for Vec<T> {
@@ -221,13 +373,76 @@ impl<T: HelperReadWrite> HelperReadWrite for Vec<T> {
         xor: u32,
         sub: u32,
     ) -> Result<Self, crate::protocol::PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, &mut Default::default())
+    }
+
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, crate::protocol::PacketError> {
+        let len = ctx
+            .read_len(reader, sub, xor)
+            .map_err(|e| PacketError::FieldError {
+                packet_name: "Vec",
+                field_name: "len",
+                error: e,
+            })?;
+        ctx.allocate(len as usize, std::mem::size_of::<T>())?;
+        let mut data = vec![];
+        data.reserve_exact(len as usize);
+
+        let seek1 = reader
+            .stream_position()
+            .map_err(|e| PacketError::PaddingError {
+                packet_name: "Vec",
+                field_name: "pre_read",
+                error: e,
+            })?;
+        for i in 0..len as usize {
+            data.push(
+                T::read_limited(reader, packet_type, xor, sub, ctx).map_err(|e| {
+                    PacketError::ElementError {
+                        packet_name: "Vec",
+                        index: i,
+                        error: e.into(),
+                    }
+                })?,
+            );
+        }
+        let seek2 = reader
+            .stream_position()
+            .map_err(|e| PacketError::PaddingError {
+                packet_name: "Vec",
+                field_name: "post_read",
+                error: e,
+            })?;
+        let len = (seek2 - seek1) as usize;
+        ctx.skip_padding(reader, (len.next_multiple_of(4) - len) as u64)
+            .map_err(|e| PacketError::PaddingError {
+                packet_name: "Vec",
+                field_name: "padding",
+                error: e,
+            })?;
+        Ok(data)
+    }
+
+    fn skip(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: crate::protocol::PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<(), crate::protocol::PacketError> {
         let len = read_magic(reader, sub, xor).map_err(|e| PacketError::FieldError {
             packet_name: "Vec",
             field_name: "len",
             error: e,
         })?;
-        let mut data = vec![];
-        data.reserve_exact(len as usize);
+        // nothing is allocated, but the element count is still limited
+        ctx.allocate(len as usize, 0)?;
 
         let seek1 = reader
             .stream_position()
@@ -236,14 +451,12 @@ impl<T: HelperReadWrite> HelperReadWrite for Vec<T> {
                 field_name: "pre_read",
                 error: e,
             })?;
-        for _ in 0..len {
-            data.push(T::read(reader, packet_type, xor, sub).map_err(|e| {
-                PacketError::CompositeFieldError {
-                    packet_name: "Vec",
-                    field_name: "value",
-                    error: e.into(),
-                }
-            })?);
+        for i in 0..len as usize {
+            T::skip(reader, packet_type, xor, sub, ctx).map_err(|e| PacketError::ElementError {
+                packet_name: "Vec",
+                index: i,
+                error: e.into(),
+            })?;
         }
         let seek2 = reader
             .stream_position()
@@ -262,7 +475,7 @@ impl<T: HelperReadWrite> HelperReadWrite for Vec<T> {
                 field_name: "padding",
                 error: e,
             })?;
-        Ok(data)
+        Ok(())
     }
 
     fn write(
@@ -271,23 +484,32 @@ impl<T: HelperReadWrite> HelperReadWrite for Vec<T> {
         packet_type: crate::protocol::PacketType,
         xor: u32,
         sub: u32,
+    ) -> Result<(), crate::protocol::PacketError> {
+        self.write_versioned(writer, &ProtocolVersion::latest(packet_type), xor, sub)
+    }
+
+    fn write_versioned(
+        &self,
+        writer: &mut impl std::io::Write,
+        version: &ProtocolVersion,
+        xor: u32,
+        sub: u32,
     ) -> Result<(), crate::protocol::PacketError> {
         (write_magic(self.len() as u32, sub, xor))
-            .write(writer, packet_type, xor, sub)
+            .write(writer, version.packet_type, xor, sub)
             .map_err(|e| PacketError::CompositeFieldError {
                 packet_name: "Vec",
                 field_name: "len",
                 error: e.into(),
             })?;
         let mut buf = vec![];
-        for i in self.iter() {
-            i.write(&mut buf, packet_type, xor, sub).map_err(|e| {
-                PacketError::CompositeFieldError {
+        for (index, i) in self.iter().enumerate() {
+            i.write_versioned(&mut buf, version, xor, sub)
+                .map_err(|e| PacketError::ElementError {
                     packet_name: "Vec",
-                    field_name: "value",
+                    index,
                     error: e.into(),
-                }
-            })?;
+                })?;
         }
         let len = buf.len();
         writer
@@ -307,4 +529,110 @@ impl<T: HelperReadWrite> HelperReadWrite for Vec<T> {
 
         Ok(())
     }
+
+    fn serialized_len(&self, packet_type: crate::protocol::PacketType) -> usize {
+        let len: usize = self.iter().map(|i| i.serialized_len(packet_type)).sum();
+        4 + len.next_multiple_of(4)
+    }
+
+    fn type_desc() -> TypeDesc {
+        TypeDesc::Vec(Box::new(T::type_desc()))
+    }
+}
+
+/// Reads a variable length string, charging its length to the budget of `ctx`.
+fn read_string<S: StringRW>(
+    reader: &mut (impl std::io::Read + std::io::Seek),
+    xor: u32,
+    sub: u32,
+    ctx: &mut ReadContext,
+    packet_name: &'static str,
+    char_size: usize,
+) -> Result<S, PacketError> {
+    let start = ctx.position(reader).map_err(|e| PacketError::FieldError {
+        packet_name,
+        field_name: "str",
+        error: e,
+    })?;
+    let len = ctx
+        .read_len(reader, sub, xor)
+        .map_err(|e| PacketError::FieldLengthError {
+            packet_name,
+            field_name: "len",
+            error: e,
+        })?;
+    ctx.allocate(len as usize, char_size)?;
+    // empty strings can only deviate in the length
+    let start = start.filter(|_| len != 0);
+    let string = S::read_with_len(reader, len as u64).map_err(|e| PacketError::FieldError {
+        packet_name,
+        field_name: "str",
+        error: e,
+    })?;
+    ctx.check_field(reader, start, DeviationKind::String, || {
+        string.write_variable(sub, xor)
+    })
+    .map_err(|e| PacketError::FieldError {
+        packet_name,
+        field_name: "str",
+        error: e,
+    })?;
+    Ok(string)
+}
+
+/// Skips a variable length string.
+fn skip_string<S: StringRW>(
+    reader: &mut (impl std::io::Read + std::io::Seek),
+    xor: u32,
+    sub: u32,
+    packet_name: &'static str,
+    char_size: u64,
+) -> Result<(), PacketError> {
+    let len = read_magic(reader, sub, xor).map_err(|e| PacketError::FieldLengthError {
+        packet_name,
+        field_name: "len",
+        error: e,
+    })? as u64;
+    if len == 0 {
+        return Ok(());
+    }
+    skip_bytes(reader, len * char_size, packet_name, "str")?;
+    reader
+        .seek(std::io::SeekFrom::Current(S::get_padding(len) as i64))
+        .map_err(|e| PacketError::PaddingError {
+            packet_name,
+            field_name: "padding",
+            error: e,
+        })?;
+    Ok(())
+}
+
+/// Skips `len` bytes, failing if the reader ends early.
+pub(crate) fn skip_bytes(
+    reader: &mut impl std::io::Read,
+    len: u64,
+    packet_name: &'static str,
+    field_name: &'static str,
+) -> Result<(), PacketError> {
+    std::io::copy(&mut Read::take(reader, len), &mut std::io::sink())
+        .and_then(|read| match read == len {
+            true => Ok(()),
+            false => Err(std::io::ErrorKind::UnexpectedEof.into()),
+        })
+        .map_err(|e| PacketError::FieldError {
+            packet_name,
+            field_name,
+            error: e,
+        })
+}
+
+/// Returns the size of a variable length string with `len` characters.
+fn string_len(len: usize, char_size: usize) -> usize {
+    if len == 0 {
+        return 4;
+    }
+    // null terminator
+    #[cfg(not(test))]
+    let len = len + 1;
+    4 + (len * char_size).next_multiple_of(4)
 }
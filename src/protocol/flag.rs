@@ -10,6 +10,7 @@ use crate::fixed_types::{FixedAsciiString, FixedBytes, FixedVec};
 ///
 /// (C -> S) Sent when a client sets any flag.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x02)]
@@ -26,6 +27,7 @@ pub struct SetFlagPacket {
 ///
 /// (S -> C) Sent when a server sets any flag for a client.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x04)]
@@ -43,6 +45,7 @@ pub struct ServerSetFlagPacket {
 ///
 /// (S -> C) Sent when a server sets any flag parameter for a client.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x05)]
@@ -61,6 +64,7 @@ pub struct ServerSetParamPacket {
 ///
 /// Response to: [`crate::protocol::Packet::StartGame`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x06)]
@@ -81,6 +85,7 @@ pub struct AccountFlagsPacket {
 ///
 /// Response to: [`crate::protocol::Packet::StartGame`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x07)]
@@ -99,6 +104,7 @@ pub struct CharacterFlagsPacket {
 ///
 /// (C -> S) Sent when a cutscene ends.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x0A)]
@@ -118,6 +124,7 @@ pub struct CutsceneEndPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::SkitItemAddResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x0B)]
@@ -133,6 +140,7 @@ pub struct SkitItemAddRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SkitItemAddRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x0C)]
@@ -146,6 +154,7 @@ pub struct SkitItemAddResponsePacket {
 ///
 /// (C -> S)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x0D)]
@@ -157,6 +166,7 @@ pub struct Unk230DPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x0E)]
@@ -172,6 +182,7 @@ pub struct Unk230EPacket {
 ///
 /// Response to: [`crate::protocol::Packet::StartGame`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x23, 0x15)]
@@ -188,6 +199,7 @@ pub struct Unk2315Packet {
 // ----------------------------------------------------------------
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Copy, Default, PartialEq, HelperReadWrite)]
 pub struct Unk230EThing {
     pub unk1: u16,
@@ -198,6 +210,7 @@ pub struct Unk230EThing {
 
 /// Flag type.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Copy, Default, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum FlagType {
@@ -13,6 +13,7 @@ use super::{items::Item, ObjectHeader, PacketReadWrite};
 ///
 /// Respond with: [`crate::protocol::Packet::FriendAvatarDataResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x26, 0x00)]
@@ -27,6 +28,7 @@ pub struct FriendAvatarDataRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::FriendAvatarDataRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x26, 0x08)]
@@ -14,6 +14,7 @@ use std::time::Duration;
 ///
 /// Respond with: [`crate::protocol::Packet::FriendList`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x18, 0x14)]
@@ -27,6 +28,7 @@ pub struct FriendListRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::FriendListRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x18, 0x15)]
@@ -48,6 +50,7 @@ pub struct FriendListPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::AddedRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x18, 0x18)]
@@ -67,6 +70,7 @@ pub struct SendFriendRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SendFriendRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x18, 0x1A)]
@@ -99,6 +103,7 @@ pub struct AddedRequestPacket {
 
 /// Friend entry in friend list.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, HelperReadWrite)]
 pub struct FriendListEntry {
@@ -143,6 +148,7 @@ pub struct FriendListEntry {
 bitflags::bitflags! {
     /// Friend flags.
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
     #[cfg_attr(feature = "serde", serde(default))]
     #[derive(Debug, Copy, Clone, Default, PartialEq, HelperReadWrite)]
     #[BitFlags(u8)]
@@ -160,6 +166,7 @@ bitflags::bitflags! {
 
 /// Friend map location.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u16)]
 #[derive(Debug, Copy, Clone, Default, PartialEq, HelperReadWrite)]
 pub enum FriendLocation {
@@ -3,10 +3,11 @@ use crate::fixed_types::{Bytes, FixedString, FixedVec};
 
 use super::{
     models::{character::HSVColor, Position},
-    HelperReadWrite, ObjectHeader, PacketError, PacketReadWrite, PacketType,
+    FieldSchema, HelperReadWrite, ObjectHeader, PacketError, PacketReadWrite, PacketType,
+    ProtocolVersion, ReadContext, TypeDesc, TypeKind, TypeSchema,
 };
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
-use std::{io::SeekFrom, time::Duration};
+use std::{io::SeekFrom, sync::OnceLock, time::Duration};
 
 // ----------------------------------------------------------------
 // Items packets
@@ -20,6 +21,7 @@ use std::{io::SeekFrom, time::Duration};
 ///
 /// Following: [`crate::protocol::Packet::LoginResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x00)]
@@ -46,6 +48,7 @@ pub struct ItemAttributesPacket {
 /// [`crate::protocol::Packet::DespawnObject`] (if the item was actually picked up),
 /// [`crate::protocol::Packet::ItemPickupResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x01)]
@@ -62,6 +65,7 @@ pub struct ItemPickupRequestPacket {
 /// Response to:
 /// [`crate::protocol::Packet::ItemPickupRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x02)]
@@ -81,6 +85,7 @@ pub struct ItemPickupResponsePacket {
 ///
 /// Following: [`crate::protocol::Packet::ObjectSpawn`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x04)]
@@ -107,6 +112,7 @@ pub struct NewItemDropPacket {
 /// (S -> C) Sent when a new item is added to the inventory (e.g. picking up an item, completing an
 /// emergency).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x05)]
@@ -121,6 +127,7 @@ pub struct AddedItemPacket {
 /// (S -> C) Sent when items in the inventory are updated in some way (e.g. discarding an item,
 /// modifying the consumables amount, using an item).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x06)]
@@ -137,6 +144,7 @@ pub struct UpdateInventoryPacket {
 ///
 /// (C -> S) Sent when a player equips an item.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x08)]
@@ -152,6 +160,7 @@ pub struct EquipItemRequestPacket {
 ///
 /// (S -> C) Sent in response to the request.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x09)]
@@ -180,6 +189,7 @@ pub struct EquipItemPacket {
 ///
 /// (C -> S) Sent when a player unequips an item.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x0A)]
@@ -195,6 +205,7 @@ pub struct UnequipItemRequestPacket {
 ///
 /// (S -> C) Sent in response to the request.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x0B)]
@@ -221,6 +232,7 @@ pub struct UnequipItemPacket {
 /// (S -> C) Sent when player's equiped items change, when the player object is created or when map
 /// changes.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x0C)]
@@ -249,6 +261,7 @@ pub struct LoadEquipedPacket {
 ///
 /// Response to: [`crate::protocol::Packet::StartGame`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x0D)]
@@ -273,6 +286,7 @@ pub struct LoadPlayerInventoryPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::MoveToStorage`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x0F)]
@@ -289,6 +303,7 @@ pub struct MoveToStorageRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MoveToStorageRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x10)]
@@ -309,6 +324,7 @@ pub struct MoveToStoragePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::MoveToInventory`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x11)]
@@ -325,6 +341,7 @@ pub struct MoveToInventoryRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MoveToInventoryRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x12)]
@@ -343,6 +360,7 @@ pub struct MoveToInventoryPacket {
 ///
 /// Response to: [`crate::protocol::Packet::StartGame`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x13)]
@@ -362,6 +380,7 @@ pub struct LoadStoragesPacket {
 ///
 /// (S -> C) Sent when the players held meseta amount is changed (e.g. when meseta is picked up).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x14)]
@@ -378,6 +397,7 @@ pub struct InventoryMesetaPacket {
 /// [`crate::protocol::Packet::StorageMeseta`]
 /// [`crate::protocol::Packet::InventoryMeseta`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x15)]
@@ -392,6 +412,7 @@ pub struct MoveMesetaPacket {
 ///
 /// (S -> C) Sent when the players storage meseta amount is changed.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x16)]
@@ -406,6 +427,7 @@ pub struct StorageMesetaPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::UpdateInventory`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x17)]
@@ -422,6 +444,7 @@ pub struct DiscardItemRequestPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::MoveStorages`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x18)]
@@ -442,6 +465,7 @@ pub struct MoveStoragesRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MoveStoragesRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x19)]
@@ -462,6 +486,7 @@ pub struct MoveStoragesPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::LoadItemDescription`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x1C)]
@@ -476,6 +501,7 @@ pub struct GetItemDescriptionPacket {
 ///
 /// Response to: [`crate::protocol::Packet::GetItemDescription`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x1D)]
@@ -497,6 +523,7 @@ pub struct LoadItemDescriptionPacket {
 /// [`crate::protocol::Packet::StartGame`]
 /// [`crate::protocol::Packet::SetPalette`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x21)]
@@ -512,6 +539,7 @@ pub struct EquipedWeaponPacket {
 /// (S -> C) Sent when items in the storages are updated in some way (e.g. auto item moving to
 /// storage on full inventory).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x22)]
@@ -533,6 +561,7 @@ pub struct UpdateStoragePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::UpdateStorage`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x25)]
@@ -547,6 +576,7 @@ pub struct DiscardStorageItemRequestPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x2B)]
@@ -561,6 +591,7 @@ pub struct Unk0F2BPacket {
 ///
 /// (S -> C) Sent when the client sees an item for the first time since logging in.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct LoadItemPacket {
@@ -574,6 +605,7 @@ pub struct LoadItemPacket {
 ///
 /// Following: [`crate::protocol::Packet::LoadPAs`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x33)]
@@ -592,6 +624,7 @@ pub struct LearnedPAPacket {
 ///
 /// (S -> C) Sent when a player wants to switch a potential of an item.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x65)]
@@ -615,6 +648,7 @@ pub struct PotentialListPacket {
 ///
 /// Response to: [`crate::protocol::Packet::AccountCampaignsRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x70)]
@@ -634,6 +668,7 @@ pub struct AccountCampaignsPacket {
 ///
 /// Response to: [`crate::protocol::Packet::AccountCampaigns`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x71)]
@@ -650,6 +685,7 @@ pub struct CampaignItemsRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::CampaignItemsRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x72)]
@@ -667,6 +703,7 @@ pub struct CampaignItemListPacket {
 ///
 /// Respond with: (0x0F, 0x74)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x73)]
@@ -679,6 +716,7 @@ pub struct ReceiveCampaignRequestPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x9C)]
@@ -694,6 +732,7 @@ pub struct Unk0F9CPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SetPalette`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xBC)]
@@ -712,6 +751,7 @@ pub struct ChangeWeaponPalettePacket {
 ///
 /// Response to: [`crate::protocol::Packet::StartGame`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xDF)]
@@ -731,6 +771,7 @@ pub struct LoadMaterialStoragePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::MoveToMatStorage`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xE0)]
@@ -747,6 +788,7 @@ pub struct MoveToMatStorageRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MoveToMatStorageRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xE1)]
@@ -765,6 +807,7 @@ pub struct MoveToMatStoragePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::MoveFromMatStorage`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xE2)]
@@ -781,6 +824,7 @@ pub struct MoveFromMatStorageRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MoveFromMatStorageRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xE3)]
@@ -799,6 +843,7 @@ pub struct MoveFromMatStoragePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::MoveMSToStorage`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xE8)]
@@ -817,6 +862,7 @@ pub struct MoveMSToStorageRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MoveMSToStorageRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xE9)]
@@ -835,6 +881,7 @@ pub struct MoveMSToStoragePacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xEF)]
@@ -848,6 +895,7 @@ pub struct Unk0FEFPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0xFC)]
@@ -864,6 +912,7 @@ pub struct Unk0FFCPacket {
 
 // 0x0F, 0x30
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0F, 0x30)]
@@ -881,6 +930,7 @@ struct LoadItemInternal {
 
 /// Item ID with a name.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct NamedId {
@@ -893,6 +943,7 @@ pub struct NamedId {
 
 /// In game item.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Item {
@@ -912,6 +963,7 @@ pub struct Item {
 
 /// Items UUID and amount.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct UUIDAmount {
@@ -924,6 +976,7 @@ pub struct UUIDAmount {
 
 /// Player equiped item.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct EquipedItem {
@@ -933,6 +986,7 @@ pub struct EquipedItem {
 
 /// Moved item to/from storage.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct MoveStorageItemRequest {
@@ -947,6 +1001,7 @@ pub struct MoveStorageItemRequest {
 
 /// New item in the storage.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct NewStorageItem {
@@ -957,6 +1012,7 @@ pub struct NewStorageItem {
 
 /// New item in the inventory.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct NewInventoryItem {
@@ -969,6 +1025,7 @@ pub struct NewInventoryItem {
 
 /// Updated storage item.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct UpdatedItem {
@@ -982,6 +1039,7 @@ pub struct UpdatedItem {
 
 /// Updated inventory item.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct UpdatedInventoryItem {
@@ -995,6 +1053,7 @@ pub struct UpdatedInventoryItem {
 
 /// Updated storage item.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct UpdatedStorageItem {
@@ -1010,6 +1069,7 @@ pub struct UpdatedStorageItem {
 
 /// Item types.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum ItemType {
     #[default]
@@ -1046,6 +1106,7 @@ pub enum ItemType {
 
 /// Weapon type item data.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct WeaponItem {
@@ -1074,6 +1135,7 @@ pub struct WeaponItem {
 #[cfg(feature = "ngs_packets")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ngs_packets")))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct WeaponItemNGS {
@@ -1100,6 +1162,7 @@ pub struct WeaponItemNGS {
 
 /// Unit type item data.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct UnitItem {
@@ -1111,7 +1174,7 @@ pub struct UnitItem {
     pub enh_percent: u8,
     pub unk1: u8,
     /// Item affix IDs (ranging from 0 to 4095).
-    #[ManualRW(read_packed_affixes, write_packed_affixes)]
+    #[ManualRW(read_packed_affixes, write_packed_affixes, 12)]
     pub affixes: [u16; 8],
     /// Item potential.
     #[SeekAfter(0x7)]
@@ -1127,6 +1190,7 @@ pub struct UnitItem {
 #[cfg(feature = "ngs_packets")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ngs_packets")))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct UnitItemNGS {
@@ -1147,6 +1211,7 @@ pub struct UnitItemNGS {
 
 /// Clothing type item data.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct ClothingItem {
@@ -1163,6 +1228,7 @@ pub struct ClothingItem {
 #[cfg(feature = "ngs_packets")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ngs_packets")))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct ClothingNGSItem {
@@ -1185,6 +1251,7 @@ pub struct ClothingNGSItem {
 
 /// Consumable type item data.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct ConsumableItem {
@@ -1199,6 +1266,7 @@ pub struct ConsumableItem {
 #[cfg(feature = "ngs_packets")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ngs_packets")))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct ConsumableNGSItem {
@@ -1211,6 +1279,7 @@ pub struct ConsumableNGSItem {
 
 /// Camouflage type item data.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct CamoItem {
@@ -1225,6 +1294,7 @@ pub struct CamoItem {
 #[cfg(feature = "ngs_packets")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ngs_packets")))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct CamoNGSItem {
@@ -1237,6 +1307,7 @@ pub struct CamoNGSItem {
 
 /// Item ID.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Copy, Clone, PartialEq, HelperReadWrite)]
 pub struct ItemId {
@@ -1252,6 +1323,7 @@ pub struct ItemId {
 
 /// Short item ID.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Copy, Clone, PartialEq, HelperReadWrite)]
 pub struct ShortItemId {
@@ -1265,6 +1337,7 @@ pub struct ShortItemId {
 
 /// Campaign definition.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct Campaign {
@@ -1282,6 +1355,7 @@ pub struct Campaign {
 
 /// Campaign item list definition.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct CampaignItemDefinition {
@@ -1295,6 +1369,7 @@ pub struct CampaignItemDefinition {
 
 /// Campaign item definition.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Copy, Clone, PartialEq, HelperReadWrite)]
 pub struct CampaignItem {
@@ -1307,6 +1382,7 @@ pub struct CampaignItem {
 
 /// Storage information.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct StorageInfo {
@@ -1325,6 +1401,7 @@ pub struct StorageInfo {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct Unk0f9c {
@@ -1337,6 +1414,7 @@ pub struct Unk0f9c {
 
 /// Material storage item definition.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct MaterialStorageItem {
@@ -1350,6 +1428,7 @@ pub struct MaterialStorageItem {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct Unk0ffc {
@@ -1361,6 +1440,7 @@ pub struct Unk0ffc {
 
 /// Meseta transfer direction (for [`MoveMesetaPacket`]).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum MesetaDirection {
@@ -1382,16 +1462,26 @@ impl PacketReadWrite for LoadItemPacket {
         flags: &super::Flags,
         packet_type: PacketType,
     ) -> Result<Self, PacketError> {
-        let packet = LoadItemInternal::read(reader, flags, packet_type).map_err(|e| {
-            PacketError::CompositeFieldError {
-                packet_name: "LoadItemPacket",
-                field_name: "internal",
-                error: Box::new(e),
-            }
-        })?;
+        Self::read_limited(reader, flags, packet_type, &mut Default::default())
+    }
+
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        flags: &super::Flags,
+        packet_type: PacketType,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, PacketError> {
+        let packet =
+            LoadItemInternal::read_limited(reader, flags, packet_type, ctx).map_err(|e| {
+                PacketError::CompositeFieldError {
+                    packet_name: "LoadItemPacket",
+                    field_name: "internal",
+                    error: Box::new(e),
+                }
+            })?;
         let mut names = packet.names.chars();
         let mut items = vec![];
-        for (id, name_length) in packet.ids.into_iter().zip(packet.name_length.into_iter()) {
+        for (id, name_length) in packet.ids.into_iter().zip(packet.name_length) {
             let name = names.by_ref().take(name_length as usize).collect();
             items.push(NamedId { name, id });
         }
@@ -1419,6 +1509,10 @@ impl PacketReadWrite for LoadItemPacket {
             error: Box::new(e),
         })
     }
+
+    fn flags(&self) -> super::Flags {
+        super::Flags::PACKED
+    }
 }
 
 impl HelperReadWrite for Item {
@@ -1427,6 +1521,15 @@ impl HelperReadWrite for Item {
         packet_type: PacketType,
         xor: u32,
         sub: u32,
+    ) -> Result<Self, PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, &mut ReadContext::default())
+    }
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        packet_type: PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
     ) -> Result<Self, PacketError> {
         let uuid = reader
             .read_u64::<LittleEndian>()
@@ -1435,7 +1538,7 @@ impl HelperReadWrite for Item {
                 field_name: "uuid",
                 error: e,
             })?;
-        let id = ItemId::read(reader, packet_type, xor, sub).map_err(|e| {
+        let id = ItemId::read_limited(reader, packet_type, xor, sub, ctx).map_err(|e| {
             PacketError::CompositeFieldError {
                 packet_name: "Item",
                 field_name: "id",
@@ -1482,6 +1585,16 @@ impl HelperReadWrite for Item {
         xor: u32,
         sub: u32,
     ) -> Result<(), PacketError> {
+        self.write_versioned(writer, &ProtocolVersion::latest(packet_type), xor, sub)
+    }
+    fn write_versioned(
+        &self,
+        writer: &mut impl std::io::Write,
+        version: &ProtocolVersion,
+        xor: u32,
+        sub: u32,
+    ) -> Result<(), PacketError> {
+        let packet_type = version.packet_type;
         writer
             .write_u64::<LittleEndian>(self.uuid)
             .map_err(|e| PacketError::FieldError {
@@ -1489,13 +1602,13 @@ impl HelperReadWrite for Item {
                 field_name: "uuid",
                 error: e,
             })?;
-        self.id.write(writer, packet_type, xor, sub).map_err(|e| {
-            PacketError::CompositeFieldError {
+        self.id
+            .write_versioned(writer, version, xor, sub)
+            .map_err(|e| PacketError::CompositeFieldError {
                 packet_name: "Item",
                 field_name: "id",
                 error: Box::new(e),
-            }
-        })?;
+            })?;
         self.data
             .write(writer, packet_type)
             .map_err(|e| PacketError::CompositeFieldError {
@@ -1517,6 +1630,59 @@ impl HelperReadWrite for Item {
         }
         Ok(())
     }
+    fn serialized_len(&self, packet_type: PacketType) -> usize {
+        let data_len = ItemType::data_size(packet_type);
+        // trailing NGS data
+        #[cfg(feature = "ngs_packets")]
+        let data_len = match packet_type {
+            PacketType::NGS => data_len + 0x18,
+            _ => data_len,
+        };
+        8 + self.id.serialized_len(packet_type) + data_len
+    }
+
+    fn schema() -> Option<&'static TypeSchema> {
+        static SCHEMA: OnceLock<TypeSchema> = OnceLock::new();
+        Some(SCHEMA.get_or_init(|| {
+            let mut fields = vec![
+                FieldSchema::new("uuid", "u64", u64::type_desc),
+                FieldSchema::new("id", "ItemId", ItemId::type_desc),
+            ];
+            // layout of the data depends on the item type
+            #[cfg(not(feature = "ngs_packets"))]
+            fields.push(FieldSchema::new("data", "ItemType", || {
+                TypeDesc::FixedBytes {
+                    n: 0x28,
+                    pad: false,
+                }
+            }));
+            #[cfg(feature = "ngs_packets")]
+            fields.extend([
+                FieldSchema {
+                    condition: super::FieldCondition::NotOn(vec![PacketType::NGS]),
+                    ..FieldSchema::new("data", "ItemType", || TypeDesc::FixedBytes {
+                        n: 0x28,
+                        pad: false,
+                    })
+                },
+                FieldSchema {
+                    condition: super::FieldCondition::OnlyOn(vec![PacketType::NGS]),
+                    ..FieldSchema::new("data_ngs", "ItemType", || TypeDesc::FixedBytes {
+                        n: 0x38,
+                        pad: false,
+                    })
+                },
+                FieldSchema {
+                    condition: super::FieldCondition::OnlyOn(vec![PacketType::NGS]),
+                    ..FieldSchema::new("unk", "[u16; 12]", <[u16; 12]>::type_desc)
+                },
+            ]);
+            TypeSchema {
+                name: "Item",
+                kind: TypeKind::Struct(fields),
+            }
+        }))
+    }
 }
 
 impl ItemType {
@@ -1599,67 +1765,50 @@ impl ItemType {
         writer: &mut impl std::io::Write,
         packet_type: PacketType,
     ) -> Result<(), PacketError> {
+        let mut data = vec![];
         match self {
-            Self::NoItem => {
-                writer
-                    .write_all(&[0; 0x28])
-                    .map_err(|e| PacketError::FieldError {
-                        packet_name: "ItemType",
-                        field_name: "field_0",
-                        error: e,
-                    })?;
-            }
-            Self::Weapon(x) => x.write(writer, packet_type, 0, 0)?,
-            Self::Clothing(x) => x.write(writer, packet_type, 0, 0)?,
-            Self::Consumable(x) => x.write(writer, packet_type, 0, 0)?,
-            Self::Camo(x) => x.write(writer, packet_type, 0, 0)?,
-            Self::Unit(x) => x.write(writer, packet_type, 0, 0)?,
-            Self::Unknown(x) => {
-                let mut data = x.to_vec();
-                data.resize(0x28, 0);
-                writer
-                    .write_all(&data)
-                    .map_err(|e| PacketError::FieldError {
-                        packet_name: "ItemType",
-                        field_name: "field_0",
-                        error: e,
-                    })?;
-            }
+            Self::NoItem => {}
+            Self::Weapon(x) => x.write(&mut data, packet_type, 0, 0)?,
+            Self::Clothing(x) => x.write(&mut data, packet_type, 0, 0)?,
+            Self::Consumable(x) => x.write(&mut data, packet_type, 0, 0)?,
+            Self::Camo(x) => x.write(&mut data, packet_type, 0, 0)?,
+            Self::Unit(x) => x.write(&mut data, packet_type, 0, 0)?,
+            Self::Unknown(x) => data.extend_from_slice(x),
             #[cfg(feature = "ngs_packets")]
-            Self::NoItemNGS => {
-                writer
-                    .write_all(&[0; 0x38])
-                    .map_err(|e| PacketError::FieldError {
-                        packet_name: "ItemType",
-                        field_name: "field_0",
-                        error: e,
-                    })?;
-            }
+            Self::NoItemNGS => {}
             #[cfg(feature = "ngs_packets")]
-            Self::WeaponNGS(x) => x.write(writer, packet_type, 0, 0)?,
+            Self::WeaponNGS(x) => x.write(&mut data, packet_type, 0, 0)?,
             #[cfg(feature = "ngs_packets")]
-            Self::ClothingNGS(x) => x.write(writer, packet_type, 0, 0)?,
+            Self::ClothingNGS(x) => x.write(&mut data, packet_type, 0, 0)?,
             #[cfg(feature = "ngs_packets")]
-            Self::ConsumableNGS(x) => x.write(writer, packet_type, 0, 0)?,
+            Self::ConsumableNGS(x) => x.write(&mut data, packet_type, 0, 0)?,
             #[cfg(feature = "ngs_packets")]
-            Self::CamoNGS(x) => x.write(writer, packet_type, 0, 0)?,
+            Self::CamoNGS(x) => x.write(&mut data, packet_type, 0, 0)?,
             #[cfg(feature = "ngs_packets")]
-            Self::UnitNGS(x) => x.write(writer, packet_type, 0, 0)?,
+            Self::UnitNGS(x) => x.write(&mut data, packet_type, 0, 0)?,
             #[cfg(feature = "ngs_packets")]
-            Self::UnknownNGS(x) => {
-                let mut data = x.to_vec();
-                data.resize(0x38, 0);
-                writer
-                    .write_all(&data)
-                    .map_err(|e| PacketError::FieldError {
-                        packet_name: "ItemType",
-                        field_name: "field_0",
-                        error: e,
-                    })?;
-            }
+            Self::UnknownNGS(x) => data.extend_from_slice(x),
         }
+        // variants of the other packet types have a different size
+        data.resize(Self::data_size(packet_type), 0);
+        writer
+            .write_all(&data)
+            .map_err(|e| PacketError::FieldError {
+                packet_name: "ItemType",
+                field_name: "field_0",
+                error: e,
+            })?;
         Ok(())
     }
+
+    /// Returns the size of the item data in the packet type.
+    fn data_size(packet_type: PacketType) -> usize {
+        match packet_type {
+            #[cfg(feature = "ngs_packets")]
+            PacketType::NGS => 0x38,
+            _ => 0x28,
+        }
+    }
 }
 
 fn read_packed_affixes(
@@ -1711,3 +1860,30 @@ fn write_packed_affixes(
         })?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Item, ItemType, WeaponItem};
+    use crate::protocol::{HelperReadWrite, PacketType};
+
+    #[test]
+    fn item_data_size() {
+        // data of the other packet type variants is resized to the layout of the packet type
+        let item = Item {
+            data: ItemType::Weapon(WeaponItem::default()),
+            ..Default::default()
+        };
+        for packet_type in [PacketType::NGS, PacketType::NA, PacketType::Vita] {
+            let mut data = vec![];
+            item.write(&mut data, packet_type, 0, 0).unwrap();
+            assert_eq!(
+                item.serialized_len(packet_type),
+                data.len(),
+                "{packet_type:?}"
+            );
+            let mut reader = std::io::Cursor::new(&data);
+            Item::read(&mut reader, packet_type, 0, 0).unwrap();
+            assert_eq!(reader.position() as usize, data.len(), "{packet_type:?}");
+        }
+    }
+}
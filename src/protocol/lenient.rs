@@ -0,0 +1,348 @@
+use super::{
+    Deviation, PacketCategory, PacketEncryption, PacketError, PacketHeader, PacketType, PacketView,
+    ParseBudget, ProtocolRW, ProtocolVersion, ReadContext,
+};
+use std::{
+    io::Write,
+    ops::{Deref, DerefMut},
+};
+
+/// Packet wrapper that preserves the original encoding.
+///
+/// Some packets sent by the official server can't be reproduced byte-exactly, because they
+/// contain extra data at the end, uncleared string buffers, unzeroed padding or zero lengths
+/// instead of magicked zeros. These fields are recorded as [`Deviation`]s while the packet is
+/// read and are written back as long as the field is left unmodified (and stays at the same
+/// place). The trailing data (if any) is always appended.
+///
+/// Only packets implementing [`ProtocolRW::read_view_with`] (e.g. the derived ones) record
+/// deviations. Packets that are encoded canonically don't store anything extra.
+///
+/// As the wrapper implements [`ProtocolRW`], it can be used anywhere a packet enum is expected
+/// (e.g. `Connection<Lenient<Packet>>`).
+///
+/// # Usage
+/// ```
+/// # use pso2packetlib::protocol::{Lenient, Packet, PacketType, ProtocolRW};
+/// # fn main() -> Result<(), pso2packetlib::protocol::PacketError> {
+/// let mut data = Packet::ServerPing.write(PacketType::NGS);
+/// // extra data at the end
+/// data.extend_from_slice(&[1, 2, 3, 4]);
+/// data[0] += 4;
+///
+/// let packets = Lenient::<Packet>::read(&data, PacketType::NGS)?;
+/// assert_eq!(*packets[0], Packet::ServerPing);
+/// assert_eq!(packets[0].trailing(), &[1, 2, 3, 4]);
+/// assert_eq!(packets[0].write(PacketType::NGS), data);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Lenient<P> {
+    packet: P,
+    original: Option<Original>,
+}
+
+/// Original encoding of a non-canonical packet.
+#[derive(Debug, Clone, PartialEq)]
+struct Original {
+    version: ProtocolVersion,
+    /// Non-canonically encoded fields.
+    deviations: Vec<Deviation>,
+    /// Offset where the packet fields ended.
+    end: usize,
+    /// Data after `end` as it was received (i.e. padding and trailing data).
+    tail: Vec<u8>,
+}
+
+impl<P: ProtocolRW> Lenient<P> {
+    /// Wraps a packet without any original data.
+    pub fn new(packet: P) -> Self {
+        Self {
+            packet,
+            original: None,
+        }
+    }
+
+    /// Returns a reference to the packet.
+    pub fn packet(&self) -> &P {
+        &self.packet
+    }
+
+    /// Returns a mutable reference to the packet.
+    pub fn packet_mut(&mut self) -> &mut P {
+        &mut self.packet
+    }
+
+    /// Unwraps the packet, discarding the original data.
+    pub fn into_inner(self) -> P {
+        self.packet
+    }
+
+    /// Returns `true` if the packet was read from canonically encoded data (or wasn't read at
+    /// all).
+    pub fn is_canonical(&self) -> bool {
+        self.original.is_none()
+    }
+
+    /// Returns the non-canonically encoded fields. Offsets are counted from the start of the
+    /// packet (including the length field).
+    pub fn deviations(&self) -> &[Deviation] {
+        match &self.original {
+            Some(o) => &o.deviations,
+            None => &[],
+        }
+    }
+
+    /// Returns the data that was found after the canonical encoding of the packet.
+    pub fn trailing(&self) -> &[u8] {
+        match &self.original {
+            Some(o) => o
+                .tail
+                .get(o.end.next_multiple_of(4) - o.end..)
+                .unwrap_or_default(),
+            None => &[],
+        }
+    }
+
+    /// Discards the original data, so that the packet is written canonically.
+    pub fn clear_original(&mut self) {
+        self.original = None;
+    }
+
+    /// Reapplies the original encoding to the canonically written `data`.
+    fn restore(&self, mut data: Vec<u8>, version: &ProtocolVersion) -> Vec<u8> {
+        let Some(original) = &self.original else {
+            return data;
+        };
+        // empty packets have no header to attach data to
+        if original.version != *version || data.is_empty() {
+            return data;
+        }
+        // deviations are applied in order, so the offsets are shifted by the length differences of
+        // the previous ones
+        let mut shift = 0isize;
+        let mut is_modified = false;
+        for deviation in &original.deviations {
+            let start = deviation.offset.wrapping_add_signed(shift);
+            let end = start.saturating_add(deviation.canonical.len());
+            if data.get(start..end) != Some(&deviation.canonical) {
+                is_modified = true;
+                continue;
+            }
+            data.splice(start..end, deviation.original.iter().copied());
+            shift += deviation.original.len() as isize - deviation.canonical.len() as isize;
+        }
+        let end = original.end;
+        let is_padding =
+            data.len() >= end && data.len() - end < 4 && data[end..].iter().all(|&b| b == 0);
+        if !is_modified && is_padding {
+            // the original padding might be missing or unzeroed
+            data.truncate(end);
+            data.extend_from_slice(&original.tail);
+        } else {
+            data.extend_from_slice(self.trailing());
+        }
+        let len = (data.len() as u32).to_le_bytes();
+        data[..4].copy_from_slice(&len);
+        data
+    }
+}
+
+impl<P> Deref for Lenient<P> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        &self.packet
+    }
+}
+
+impl<P> DerefMut for Lenient<P> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.packet
+    }
+}
+
+impl<P: ProtocolRW> From<P> for Lenient<P> {
+    fn from(value: P) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<P: PacketEncryption> PacketEncryption for Lenient<P> {
+    fn is_enc_data(&self) -> bool {
+        self.packet.is_enc_data()
+    }
+    fn as_enc_data(&self) -> Option<&[u8]> {
+        self.packet.as_enc_data()
+    }
+    fn mut_enc_data(&mut self) -> Option<&mut Vec<u8>> {
+        self.packet.mut_enc_data()
+    }
+}
+
+impl<P: ProtocolRW> ProtocolRW for Lenient<P> {
+    fn read(input: &[u8], packet_type: PacketType) -> Result<Vec<Self>, PacketError> {
+        PacketView::iter(input, packet_type)
+            .map(|view| Self::read_view(&view?))
+            .collect()
+    }
+
+    fn read_view(view: &PacketView<'_>) -> Result<Self, PacketError> {
+        let mut ctx = ReadContext::lenient(ParseBudget::default());
+        let packet = P::read_view_with(view, &mut ctx)?;
+        let data = view.data();
+        // read positions don't include the length field
+        let end = ctx
+            .end()
+            .map_or(data.len(), |end| end as usize + 4)
+            .min(data.len());
+        let mut deviations = ctx.take_deviations();
+        for deviation in &mut deviations {
+            deviation.offset += 4;
+        }
+        let tail = &data[end..];
+        let is_padding =
+            tail.len() == end.next_multiple_of(4) - end && tail.iter().all(|&b| b == 0);
+        if deviations.is_empty() && is_padding {
+            return Ok(Self::new(packet));
+        }
+        Ok(Self {
+            packet,
+            original: Some(Original {
+                version: view.version(),
+                deviations,
+                end,
+                tail: tail.to_vec(),
+            }),
+        })
+    }
+
+    fn write(&self, packet_type: PacketType) -> Vec<u8> {
+        let data = self.packet.write(packet_type);
+        self.restore(data, &ProtocolVersion::latest(packet_type))
+    }
+
+    fn write_versioned(
+        &self,
+        writer: &mut impl Write,
+        version: &ProtocolVersion,
+    ) -> Result<usize, PacketError> {
+        let mut data = vec![];
+        self.packet.write_versioned(&mut data, version)?;
+        let data = self.restore(data, version);
+        writer
+            .write_all(&data)
+            .map_err(|error| PacketError::FieldError {
+                packet_name: self.name(),
+                field_name: "data",
+                error,
+            })?;
+        Ok(data.len())
+    }
+
+    fn get_category(&self) -> PacketCategory {
+        self.packet.get_category()
+    }
+
+    fn category_for(id: u8, subid: u16, packet_type: PacketType) -> PacketCategory {
+        P::category_for(id, subid, packet_type)
+    }
+
+    fn header(&self, packet_type: PacketType) -> Option<PacketHeader> {
+        self.packet.header(packet_type)
+    }
+
+    fn name(&self) -> &'static str {
+        self.packet.name()
+    }
+
+    fn is_supported_on(&self, packet_type: PacketType) -> bool {
+        self.packet.is_supported_on(packet_type)
+    }
+
+    fn name_for(id: u8, subid: u16, packet_type: PacketType) -> Option<&'static str> {
+        P::name_for(id, subid, packet_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lenient;
+    use crate::protocol::{
+        friends::SendFriendRequestPacket, items::MoveStoragesRequestPacket, DeviationKind, Packet,
+        PacketType, PacketView, ProtocolRW,
+    };
+
+    #[test]
+    fn round_trip() {
+        let mut data = Packet::ServerPing.write(PacketType::NGS);
+        data.extend_from_slice(&[1, 2, 3, 4]);
+        data[0] += 4;
+        let view = PacketView::new(&data, PacketType::NGS).unwrap();
+        let mut packet: Lenient<Packet> = view.parse().unwrap();
+        assert!(!packet.is_canonical());
+        assert_eq!(packet.write(PacketType::NGS), data);
+        // different packet type can't reuse the data
+        assert_eq!(
+            packet.write(PacketType::Classic),
+            Packet::ServerPing.write(PacketType::Classic)
+        );
+
+        // modified packets keep the trailing data
+        *packet = Packet::ServerPong;
+        let mut expected = Packet::ServerPong.write(PacketType::NGS);
+        expected.extend_from_slice(&[1, 2, 3, 4]);
+        expected[0] += 4;
+        assert_eq!(packet.write(PacketType::NGS), expected);
+
+        packet.clear_original();
+        assert_eq!(
+            packet.write(PacketType::NGS),
+            Packet::ServerPong.write(PacketType::NGS)
+        );
+
+        // canonical packets store nothing
+        let data = Packet::MoveStoragesRequest(MoveStoragesRequestPacket::default())
+            .write(PacketType::NGS);
+        let packet = Lenient::<Packet>::read(&data, PacketType::NGS).unwrap();
+        assert!(packet[0].is_canonical());
+        assert_eq!(packet[0].write(PacketType::NGS), data);
+    }
+
+    #[test]
+    fn deviations() {
+        let packet = Packet::SendFriendRequest(SendFriendRequestPacket::default());
+        let mut data = packet.write(PacketType::NGS);
+        // unzeroed padding after the ID
+        data[0x0C..0x10].copy_from_slice(&[1, 2, 3, 4]);
+        // unmagicked zero length of the message
+        data[0x10..0x14].fill(0);
+        let mut packet = Lenient::<Packet>::read(&data, PacketType::NGS)
+            .unwrap()
+            .remove(0);
+        let deviations: Vec<_> = packet
+            .deviations()
+            .iter()
+            .map(|d| (d.kind, d.offset))
+            .collect();
+        assert_eq!(
+            deviations,
+            [
+                (DeviationKind::Padding, 0x0C),
+                (DeviationKind::Length, 0x10)
+            ]
+        );
+        assert!(packet.trailing().is_empty());
+        assert_eq!(packet.write(PacketType::NGS), data);
+
+        // modified fields are written canonically
+        let Packet::SendFriendRequest(inner) = &mut *packet else {
+            unreachable!()
+        };
+        inner.msg = "hi".to_string();
+        let mut expected = packet.clone().into_inner().write(PacketType::NGS);
+        expected[0x0C..0x10].copy_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(packet.write(PacketType::NGS), expected);
+    }
+}
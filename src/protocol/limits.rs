@@ -0,0 +1,171 @@
+use super::PacketError;
+
+/// Limits applied while parsing untrusted data.
+///
+/// Every limit violation is reported as [`PacketError::LimitError`]. The default limits are
+/// generous enough for all known packets while keeping the memory used by a single malicious
+/// packet bounded.
+///
+/// # Usage
+/// ```
+/// # use pso2packetlib::protocol::{Packet, PacketError, PacketType, ParseLimits, ProtocolRW};
+/// let limits = ParseLimits {
+///     max_packet_size: 0x10,
+///     ..Default::default()
+/// };
+/// let data = Packet::ServerPing.write(PacketType::NGS);
+/// assert!(Packet::read_limited(&data, PacketType::NGS, &limits).is_ok());
+///
+/// let data = Packet::ChatMessage(Default::default()).write(PacketType::NGS);
+/// assert!(matches!(
+///     Packet::read_limited(&data, PacketType::NGS, &limits),
+///     Err(PacketError::LimitError { .. })
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Maximum size of a single packet (including the length field and the header).
+    pub max_packet_size: usize,
+    /// Maximum number of elements in a single variable length collection (e.g. `Vec`,
+    /// [`Bytes`](crate::fixed_types::Bytes) or string).
+    pub max_elements: usize,
+    /// Maximum number of bytes allocated for variable length fields of a single packet.
+    pub max_allocation: usize,
+}
+
+/// Kind of the exceeded limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    /// [`ParseLimits::max_packet_size`].
+    PacketSize,
+    /// [`ParseLimits::max_elements`].
+    Elements,
+    /// [`ParseLimits::max_allocation`].
+    Allocation,
+}
+
+/// Allocation budget of a single packet. Created from [`ParseLimits`] for each parsed packet and
+/// passed to [`HelperReadWrite::read_limited`](super::HelperReadWrite::read_limited).
+#[derive(Debug, Clone)]
+pub struct ParseBudget {
+    limits: ParseLimits,
+    allocated: usize,
+}
+
+impl ParseLimits {
+    /// Default limits.
+    pub const DEFAULT: Self = Self {
+        max_packet_size: 0x100_0000,
+        max_elements: 0x10_0000,
+        max_allocation: 0x400_0000,
+    };
+
+    /// Checks the size of a packet.
+    pub fn check_packet_size(&self, size: usize) -> Result<(), PacketError> {
+        check(LimitKind::PacketSize, size, self.max_packet_size)
+    }
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl std::fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::PacketSize => "packet size",
+            Self::Elements => "element count",
+            Self::Allocation => "allocation size",
+        })
+    }
+}
+
+impl ParseBudget {
+    /// Creates a new budget.
+    pub fn new(limits: ParseLimits) -> Self {
+        Self {
+            limits,
+            allocated: 0,
+        }
+    }
+
+    /// Returns the limits of the budget.
+    pub fn limits(&self) -> &ParseLimits {
+        &self.limits
+    }
+
+    /// Returns the number of already allocated bytes.
+    pub fn allocated(&self) -> usize {
+        self.allocated
+    }
+
+    /// Reserves space for `count` elements of `size` bytes each.
+    ///
+    /// This should be called before the collection is allocated.
+    pub fn allocate(&mut self, count: usize, size: usize) -> Result<(), PacketError> {
+        check(LimitKind::Elements, count, self.limits.max_elements)?;
+        let allocated = count
+            .checked_mul(size)
+            .and_then(|bytes| bytes.checked_add(self.allocated))
+            .unwrap_or(usize::MAX);
+        check(LimitKind::Allocation, allocated, self.limits.max_allocation)?;
+        self.allocated = allocated;
+        Ok(())
+    }
+}
+
+impl Default for ParseBudget {
+    fn default() -> Self {
+        Self::new(ParseLimits::DEFAULT)
+    }
+}
+
+fn check(kind: LimitKind, value: usize, max: usize) -> Result<(), PacketError> {
+    if value > max {
+        return Err(PacketError::LimitError { kind, value, max });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LimitKind, ParseBudget, ParseLimits};
+    use crate::protocol::PacketError;
+
+    #[test]
+    fn budget() {
+        let mut budget = ParseBudget::new(ParseLimits {
+            max_packet_size: 0,
+            max_elements: 4,
+            max_allocation: 10,
+        });
+        budget.allocate(4, 2).unwrap();
+        assert!(matches!(
+            budget.allocate(5, 0),
+            Err(PacketError::LimitError {
+                kind: LimitKind::Elements,
+                value: 5,
+                max: 4
+            })
+        ));
+        assert!(matches!(
+            budget.allocate(3, 1),
+            Err(PacketError::LimitError {
+                kind: LimitKind::Allocation,
+                value: 11,
+                max: 10
+            })
+        ));
+        assert!(matches!(
+            budget.allocate(1, usize::MAX),
+            Err(PacketError::LimitError {
+                kind: LimitKind::Allocation,
+                ..
+            })
+        ));
+        budget.allocate(2, 1).unwrap();
+        assert_eq!(budget.allocated(), 10);
+    }
+}
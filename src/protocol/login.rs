@@ -33,6 +33,7 @@ use std::{
 ///
 /// Response to: [`crate::protocol::Packet::EncryptionResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x00)]
@@ -93,6 +94,7 @@ pub struct SegaIDLoginPacket {
 /// [`crate::protocol::Packet::VitaLogin`],
 /// [`crate::protocol::Packet::BlockLogin`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x01)]
@@ -147,6 +149,7 @@ pub struct LoginResponsePacket {
 /// Response to:
 /// [`crate::protocol::Packet::CharacterListRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct CharacterListPacket {
@@ -174,6 +177,7 @@ pub struct CharacterListPacket {
 /// Respond with:
 /// [`crate::protocol::Packet::LoadingScreenTransition`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x04)]
@@ -191,6 +195,7 @@ pub struct StartGamePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::CharacterCreateResponse`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x05)]
@@ -205,6 +210,7 @@ pub struct CharacterCreatePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::CharacterDeletion`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x06)]
@@ -219,6 +225,7 @@ pub struct CharacterDeletionRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::CharacterCreate`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x07)]
@@ -235,6 +242,7 @@ pub struct CharacterCreateResponsePacket {
 ///
 /// Response to: [`crate::protocol::Packet::CharacterDeletionRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x08)]
@@ -257,6 +265,7 @@ pub struct CharacterDeletionPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::EncryptionResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct EncryptionRequestPacket {
@@ -276,6 +285,7 @@ pub struct EncryptionRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::EncryptionRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct EncryptionResponsePacket {
@@ -290,6 +300,7 @@ pub struct EncryptionResponsePacket {
 /// Respond with:
 /// [`crate::protocol::Packet::ClientPong`],
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x0D)]
@@ -305,6 +316,7 @@ pub struct ClientPingPacket {
 /// Response to:
 /// [`crate::protocol::Packet::ClientPing`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x0E)]
@@ -323,6 +335,7 @@ pub struct ClientPongPacket {
 /// Response to:
 /// [`crate::protocol::Packet::BlockListRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x10)]
@@ -337,6 +350,7 @@ pub struct BlockListPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::BlockSwitchResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Default, Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x11)]
@@ -355,6 +369,7 @@ pub struct BlockSwitchRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::BlockSwitchRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x13)]
@@ -383,6 +398,7 @@ pub struct BlockSwitchResponsePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::LoginResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, Default, PacketReadWrite)]
 #[Id(0x11, 0x14)]
@@ -412,6 +428,7 @@ pub struct BlockLoginPacket {
 ///
 /// Response to: [`crate::protocol::Packet::LoginResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x1B)]
@@ -461,6 +478,7 @@ pub struct UserInfoPacket {
 #[cfg(feature = "ngs_packets")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ngs_packets")))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x1B)]
@@ -502,6 +520,7 @@ pub struct UserInfoNGSPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::NicknameResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x1E)]
@@ -517,6 +536,7 @@ pub struct NicknameRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::NicknameRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x1D)]
@@ -529,6 +549,7 @@ pub struct NicknameResponsePacket {
 ///
 /// (S -> C) Sent by the block balancer to transfer a client to a block.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x2C)]
@@ -547,6 +568,7 @@ pub struct BlockBalancePacket {
 ///
 /// (C -> S) Sent after logging in, contains information about players device.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x2D)]
@@ -582,6 +604,7 @@ pub struct SystemInformationPacket {
 ///
 /// (S -> C) Sent by a ship list server when a client connects.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x3D)]
@@ -603,6 +626,7 @@ pub struct ShipListPacket {
 ///
 /// Response to: [`crate::protocol::Packet::CreateCharacter1`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x42)]
@@ -621,6 +645,7 @@ pub struct CreateCharacter1ResponsePacket {
 ///
 /// Response to: [`crate::protocol::Packet::CreateCharacter2`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x55)]
@@ -639,6 +664,7 @@ pub struct CreateCharacter2ResponsePacket {
 ///
 /// Response to: [`crate::protocol::Packet::EncryptionResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x63)]
@@ -693,6 +719,7 @@ pub struct VitaLoginPacket {
 ///
 /// Response to: [`crate::protocol::Packet::AllBlocksListRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x65)]
@@ -708,6 +735,7 @@ pub struct AllBlocksListPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SalonEntryRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x67)]
@@ -730,6 +758,7 @@ pub struct SalonResponse {
 ///
 /// Respond with: [`crate::protocol::Packet::ChallengeResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x68)]
@@ -746,6 +775,7 @@ pub struct ChallengeRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::ChallengeRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x69)]
@@ -760,6 +790,7 @@ pub struct ChallengeResponsePacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x6F)]
@@ -778,6 +809,7 @@ pub struct Unk116FPacket {
 ///
 /// (S -> C) Sent when a client has unchecked notifications.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x71)]
@@ -797,6 +829,7 @@ pub struct NotificationStatusPacket {
 ///
 /// Response to: [`crate::protocol::Packet::LoginHistoryRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x87)]
@@ -814,6 +847,7 @@ pub struct LoginHistoryPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::SecondPwdOperation`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x8B)]
@@ -830,6 +864,7 @@ pub struct SecondPwdOperationRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SecondPwdOperationRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x8C)]
@@ -852,6 +887,7 @@ pub struct SecondPwdOperationPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::CharacterUndeletion`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x90)]
@@ -866,6 +902,7 @@ pub struct CharacterUndeletionRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::CharacterUndeletionRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x91)]
@@ -880,6 +917,7 @@ pub struct CharacterUndeletionPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::CharacterRename`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x97)]
@@ -896,6 +934,7 @@ pub struct CharacterRenameRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::CharacterRenameRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x98)]
@@ -918,6 +957,7 @@ pub struct CharacterRenamePacket {
 ///
 /// Response to: [`crate::protocol::Packet::CharacterRename`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x9B)]
@@ -934,6 +974,7 @@ pub struct CharacterNewNameRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::CharacterNewNameRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0x9C)]
@@ -950,6 +991,7 @@ pub struct CharacterNewNamePacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xAF)]
@@ -964,6 +1006,7 @@ pub struct Unk11AFPacket {
 ///
 /// (C -> S)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xB0)]
@@ -979,6 +1022,7 @@ pub struct Unk11B0Packet {
 ///
 /// Respond with: [`crate::protocol::Packet::CharacterMove`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xB8)]
@@ -994,6 +1038,7 @@ pub struct CharacterMoveRequestPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::CharacterMove`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xB9)]
@@ -1011,6 +1056,7 @@ pub struct CharacterMovePacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xD7)]
@@ -1025,6 +1071,7 @@ pub struct Unk11D7Packet {
 ///
 /// (C -> S) Sent when a player has reported other player.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xDE)]
@@ -1044,6 +1091,7 @@ pub struct PlayerReportedPacket {
 ///
 /// (C -> S) Sent when a player has entered a prohibited nickname (e.g. "sega").
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xEA)]
@@ -1059,6 +1107,7 @@ pub struct NicknameErrorPacket {
 ///
 /// (S -> C) Sent when a player has logged in.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xED)]
@@ -1082,6 +1131,7 @@ pub struct BannerListPacket {
 ///
 /// (S -> C) Sent to verify a login when a suspicious one has occured.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xEE)]
@@ -1097,6 +1147,7 @@ pub struct EmailCodeRequestPacket {
 ///
 /// (? -> ?)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x11, 0xFF)]
@@ -1119,6 +1170,7 @@ pub struct Unk11FFPacket {
 
 /// Client network interface.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct NetInterface {
@@ -1130,6 +1182,7 @@ pub struct NetInterface {
 
 /// Ship information.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, HelperReadWrite)]
 pub struct ShipEntry {
@@ -1149,6 +1202,7 @@ pub struct ShipEntry {
 
 /// Ship status.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u16)]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 pub enum ShipStatus {
@@ -1170,6 +1224,7 @@ pub enum ShipStatus {
 
 /// Login attempt in login history.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, HelperReadWrite)]
 pub struct LoginAttempt {
@@ -1184,6 +1239,7 @@ pub struct LoginAttempt {
 
 /// Login history result.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum LoginResult {
@@ -1211,6 +1267,7 @@ pub enum LoginResult {
 
 /// Login status.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum LoginStatus {
@@ -1226,6 +1283,7 @@ pub enum LoginStatus {
 
 /// Information about a block.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, HelperReadWrite)]
 pub struct BlockInfo {
@@ -1253,6 +1311,7 @@ pub struct BlockInfo {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct SalonThing1 {
@@ -1263,6 +1322,7 @@ pub struct SalonThing1 {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct SalonThing2 {
@@ -1284,6 +1344,7 @@ pub struct SalonThing2 {
 
 /// Game languages.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum Language {
@@ -1295,6 +1356,7 @@ pub enum Language {
 
 /// Deletion request status.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum DeletionStatus {
@@ -1308,6 +1370,7 @@ pub enum DeletionStatus {
 
 /// Undeletion request status.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum UndeletionStatus {
@@ -1321,6 +1384,7 @@ pub enum UndeletionStatus {
 
 /// Rename rights status.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum RenameRequestStatus {
@@ -1340,6 +1404,7 @@ pub enum RenameRequestStatus {
 
 /// Renaming status.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum NewNameStatus {
@@ -1353,6 +1418,7 @@ pub enum NewNameStatus {
 
 /// Character creation status.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum CharacterCreationStatus {
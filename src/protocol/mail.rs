@@ -14,6 +14,7 @@ use std::time::Duration;
 ///
 /// Respond with: [`crate::protocol::Packet::MailList`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x1A, 0x00)]
@@ -29,6 +30,7 @@ pub struct MailListRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MailListRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x1A, 0x01)]
@@ -55,6 +57,7 @@ pub struct MailListPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::DeletedMail`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x1A, 0x02)]
@@ -71,6 +74,7 @@ pub struct DeleteMailRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::DeleteMailRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x1A, 0x03)]
@@ -88,6 +92,7 @@ pub struct DeletedMailPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::MailBody`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x1A, 0x06)]
@@ -102,6 +107,7 @@ pub struct MailBodyRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MailBodyRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x1A, 0x07)]
@@ -121,6 +127,7 @@ pub struct MailBodyPacket {
 
 /// Mail ID with extra information.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct MailId {
@@ -132,6 +139,7 @@ pub struct MailId {
 
 /// Mail header.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct MailHeader {
@@ -12,6 +12,7 @@ use super::{items::Item, HelperReadWrite, PacketReadWrite};
 ///
 /// Response to: [`crate::protocol::Packet::MissionPassInfoRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x4D, 0x01)]
@@ -25,6 +26,7 @@ pub struct MissionPassInfoPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MissionPassRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x4D, 0x03)]
@@ -97,6 +99,7 @@ pub struct MissionPassPacket {
 
 /// Item in the mission pass.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct MissionPassItem {
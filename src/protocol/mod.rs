@@ -7,6 +7,27 @@ use std::io::{Read, Seek};
 // Packet traits
 mod traits;
 pub use traits::*;
+// Borrowed packet views
+mod view;
+pub use view::*;
+// Lenient packet wrapper
+mod lenient;
+pub use lenient::*;
+
+mod limits;
+pub use limits::*;
+// Read state
+mod context;
+pub use context::*;
+// Per-build protocol versions
+mod version;
+pub use version::*;
+// Partially decoded packets
+pub(crate) mod partial;
+pub use partial::{FieldTrace, PartialField, PartialPacket};
+// Packet layout reflection
+mod schema;
+pub use schema::*;
 
 // Packet definitions modules
 pub mod chat;
@@ -69,7 +90,11 @@ use unk34::*;
 // Code is getting really messy.
 
 /// Error type returned by packet parsing operations.
+///
+/// New variants may be added in minor releases, so matches on this type must have a wildcard
+/// arm.
 #[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum PacketError {
     /// Failed to read or write packet field.
     #[error("failed to read/write field {field_name} from {packet_name}: {error}")]
@@ -124,12 +149,132 @@ pub enum PacketError {
         #[source]
         error: std::io::Error,
     },
+    /// Failed to read or write an element of a collection.
+    #[error("failed to read/write element {index} of {packet_name}: {error}")]
+    ElementError {
+        packet_name: &'static str,
+        index: usize,
+        #[source]
+        error: Box<Self>,
+    },
+    /// Failed to read a packet. Contains the location of the failure.
+    #[error(
+        "failed to read {path} of packet ({id:#04X}, {subid:#06X}) as {packet_type:?} at offset \
+         {offset:#X} ({remaining} bytes remaining): {error}",
+        path = .error.field_path()
+    )]
+    ReadContextError {
+        id: u8,
+        subid: u16,
+        packet_type: PacketType,
+        /// Offset of the failed read from the start of the packet (including the length field).
+        offset: usize,
+        /// Number of bytes left in the packet after `offset`.
+        remaining: usize,
+        #[source]
+        error: Box<Self>,
+    },
+    /// Parsed data exceeded one of the [`ParseLimits`].
+    #[error("{kind} limit exceeded ({value} > {max})")]
+    LimitError {
+        kind: LimitKind,
+        value: usize,
+        max: usize,
+    },
     // #[error(transparent)]
     // Io(#[from] std::io::Error),
 }
 
+impl PacketError {
+    /// Returns the dotted path to the field that failed (e.g. `LoadLevelPacket.thing5[3].unk2`).
+    pub fn field_path(&self) -> String {
+        let mut path = String::new();
+        let mut error = self;
+        let mut is_root = true;
+        loop {
+            match error {
+                Self::ReadContextError { error: e, .. } => {
+                    error = e;
+                    continue;
+                }
+                Self::CompositeFieldError {
+                    packet_name,
+                    field_name,
+                    error: e,
+                } => {
+                    if is_root {
+                        path.push_str(packet_name);
+                    }
+                    path.push('.');
+                    path.push_str(field_name);
+                    error = e;
+                }
+                Self::ElementError {
+                    packet_name,
+                    index,
+                    error: e,
+                } => {
+                    if is_root {
+                        path.push_str(packet_name);
+                    }
+                    path.push_str(&format!("[{index}]"));
+                    error = e;
+                }
+                // leaf errors of nested types only describe their internals
+                Self::FieldError {
+                    packet_name,
+                    field_name,
+                    ..
+                }
+                | Self::FieldLengthError {
+                    packet_name,
+                    field_name,
+                    ..
+                }
+                | Self::PaddingError {
+                    packet_name,
+                    field_name,
+                    ..
+                } => {
+                    if is_root {
+                        path.push_str(packet_name);
+                        path.push('.');
+                        path.push_str(field_name);
+                    }
+                    break;
+                }
+                Self::ValueError { packet_name, .. } | Self::ConstantError { packet_name, .. } => {
+                    if is_root {
+                        path.push_str(packet_name);
+                    }
+                    break;
+                }
+                Self::PacketLengthError { .. } | Self::LimitError { .. } => break,
+            }
+            is_root = false;
+        }
+        path
+    }
+
+    /// Returns the offset of the failed read from the start of the packet, if known.
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Self::ReadContextError { offset, .. } => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// Renders a hexdump of the packet data with the failed read marked.
+    ///
+    /// `data` should be the full packet data (e.g. [`PacketView::data`]).
+    pub fn hexdump(&self, data: &[u8]) -> String {
+        hexdump(data, self.offset())
+    }
+}
+
 /// Type of the packet.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u8)]
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub enum PacketType {
@@ -150,6 +295,7 @@ pub enum PacketType {
 
 /// All known packets
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, PartialEq, ProtocolReadWrite)]
 #[non_exhaustive]
 pub enum Packet {
@@ -1443,7 +1589,7 @@ pub enum Packet {
 }
 
 /// Known packet categories
-#[derive(Debug, Default, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum PacketCategory {
     #[default]
     /// Category is unspecified or packet is unknown
@@ -1535,6 +1681,7 @@ impl PacketEncryption for Packet {
 
 /// Packet header.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct PacketHeader {
@@ -1592,24 +1739,51 @@ impl PacketHeader {
         Ok(Self { id, subid, flag })
     }
     pub fn write(&self, packet_type: PacketType) -> Vec<u8> {
-        let mut buf = vec![];
+        let mut buf = Vec::with_capacity(4);
+        self.write_into(&mut buf, packet_type)
+            .expect("Writing to a Vec shouldn't fail");
+        buf
+    }
+    /// Writes the header to a stream.
+    pub fn write_into(
+        &self,
+        writer: &mut impl std::io::Write,
+        packet_type: PacketType,
+    ) -> Result<(), PacketError> {
+        let id_error = |e| PacketError::FieldError {
+            packet_name: "PacketHeader",
+            field_name: "id",
+            error: e,
+        };
+        let subid_error = |e| PacketError::FieldError {
+            packet_name: "PacketHeader",
+            field_name: "subid",
+            error: e,
+        };
         if !matches!(packet_type, PacketType::NGS) {
-            buf.write_u8(self.id).unwrap();
-            buf.write_u8(self.subid as u8).unwrap();
-            self.flag.write(&mut buf, packet_type, 0, 0).unwrap();
-            buf.write_u8(0).unwrap();
+            writer.write_u8(self.id).map_err(id_error)?;
+            writer.write_u8(self.subid as u8).map_err(subid_error)?;
+            self.flag.write(writer, packet_type, 0, 0)?;
+            writer.write_u8(0).map_err(|e| PacketError::PaddingError {
+                packet_name: "PacketHeader",
+                field_name: "flag",
+                error: e,
+            })?;
         } else {
-            self.flag.write(&mut buf, packet_type, 0, 0).unwrap();
-            buf.write_u8(self.id).unwrap();
-            buf.write_u16::<LittleEndian>(self.subid).unwrap();
+            self.flag.write(writer, packet_type, 0, 0)?;
+            writer.write_u8(self.id).map_err(id_error)?;
+            writer
+                .write_u16::<LittleEndian>(self.subid)
+                .map_err(subid_error)?;
         }
-        buf
+        Ok(())
     }
 }
 
 bitflags::bitflags! {
     /// Packet flags.
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
     #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
     #[BitFlags(u8)]
     pub struct Flags: u8 {
@@ -1625,6 +1799,7 @@ bitflags::bitflags! {
 
 /// Known object types.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u16)]
 pub enum ObjectType {
@@ -1652,6 +1827,7 @@ pub enum ObjectType {
 
 /// Information about the targeted object.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 pub struct ObjectHeader {
@@ -1672,7 +1848,9 @@ pub struct ObjectHeader {
 #[doc(hidden)]
 #[inline(always)]
 pub fn read_magic(reader: &mut impl Read, sub: u32, xor: u32) -> std::io::Result<u32> {
-    Ok((reader.read_u32::<LittleEndian>()? ^ xor) - sub)
+    (reader.read_u32::<LittleEndian>()? ^ xor)
+        .checked_sub(sub)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid magic value"))
 }
 #[doc(hidden)]
 #[inline(always)]
@@ -1680,6 +1858,72 @@ pub fn write_magic(num: u32, sub: u32, xor: u32) -> u32 {
     (num + sub) ^ xor
 }
 
+/// Writer that counts the number of written bytes.
+#[doc(hidden)]
+pub struct WriteCounter<W> {
+    writer: W,
+    count: usize,
+}
+
+impl<W: std::io::Write> WriteCounter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer, count: 0 }
+    }
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for WriteCounter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.writer.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Writes a packet with its length field, returning the number of written bytes.
+///
+/// The packet is written to a reused buffer, so the length is patched in after the body is
+/// written instead of measuring the body beforehand.
+#[doc(hidden)]
+pub fn write_length_prefixed(
+    writer: &mut impl std::io::Write,
+    packet_name: &'static str,
+    write_body: impl FnOnce(&mut Vec<u8>) -> Result<(), PacketError>,
+) -> Result<usize, PacketError> {
+    // buffers of unusually large packets are not kept around
+    const MAX_KEPT_CAPACITY: usize = 0x10000;
+    thread_local! {
+        static BUFFER: std::cell::Cell<Vec<u8>> = const { std::cell::Cell::new(Vec::new()) };
+    }
+
+    // the buffer is taken out, so packets written by `write_body` get their own buffer
+    let mut buf = BUFFER.take();
+    buf.clear();
+    buf.extend_from_slice(&[0; 4]);
+    let result = write_body(&mut buf).and_then(|_| {
+        let len = buf.len().next_multiple_of(4);
+        buf.resize(len, 0);
+        buf[..4].copy_from_slice(&(len as u32).to_le_bytes());
+        writer
+            .write_all(&buf)
+            .map_err(|error| PacketError::FieldError {
+                packet_name,
+                field_name: "data",
+                error,
+            })?;
+        Ok(len)
+    });
+    if buf.capacity() <= MAX_KEPT_CAPACITY {
+        BUFFER.set(buf);
+    }
+    result
+}
+
 // ----------------------------------------------------------------
 // Tests
 // ----------------------------------------------------------------
@@ -1688,7 +1932,7 @@ pub fn write_magic(num: u32, sub: u32, xor: u32) -> u32 {
 mod tests {
     use super::Packet;
     use crate::ppac::PPACReader;
-    use crate::protocol::ProtocolRW;
+    use crate::protocol::{Lenient, ProtocolRW, ProtocolVersion};
     use std::{fs, io::BufReader, io::Write};
 
     #[test]
@@ -1716,10 +1960,12 @@ mod tests {
                     None => continue,
                 }
                 let reader = BufReader::new(fs::File::open(&entry).unwrap());
-                let mut reader = PPACReader::open(reader).unwrap();
+                let mut reader = PPACReader::<_, Lenient<Packet>>::open(reader).unwrap();
                 reader.set_out_type(crate::ppac::OutputType::Both);
+                let build = reader.get_protocol_version().build;
 
-                while let Some(packet) = reader.read().unwrap() {
+                for packet in reader {
+                    let packet = packet.unwrap();
                     let in_data = match packet.data {
                         Some(data) => data,
                         None => continue,
@@ -1729,7 +1975,7 @@ mod tests {
                         packet.time.as_nanos(),
                         u32::from_be_bytes(in_data[4..8].try_into().unwrap())
                     );
-                    let out_type = packet.protocol_type;
+                    let version = ProtocolVersion::new(packet.protocol_type, build);
                     let packet = match packet.packet {
                         Some(x) => x,
                         None => {
@@ -1749,6 +1995,25 @@ mod tests {
                         }
                     };
 
+                    // unmodified packets should always be written back as is
+                    let mut lenient_data = vec![];
+                    packet.write_versioned(&mut lenient_data, &version).unwrap();
+                    if lenient_data != in_data {
+                        println!("{entry:?}, {id} - FAIL (lenient different data)");
+                        *is_failed = true;
+                        let path = format!(
+                            "failed_tests/{}/{id}_lenient",
+                            entry.file_name().unwrap().to_string_lossy()
+                        );
+                        create_dir(&path).unwrap();
+                        fs::File::create(format!("{path}/in.bin"))
+                            .unwrap()
+                            .write_all(&in_data)
+                            .unwrap();
+                        continue;
+                    }
+                    let packet = packet.into_inner();
+
                     // failing packets
                     if matches!(
                         packet,
@@ -1780,7 +2045,8 @@ mod tests {
                         continue;
                     }
 
-                    let out_data = packet.write(out_type);
+                    let mut out_data = vec![];
+                    packet.write_versioned(&mut out_data, &version).unwrap();
                     if in_data.len() != out_data.len() {
                         println!(
                             "{entry:?}, {id} - FAIL (different length - in: 0x{:X}, out: 0x{:X})",
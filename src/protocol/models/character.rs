@@ -1,10 +1,17 @@
 //! Character related structures.
 use crate::{
     asciistring::StringRW,
-    protocol::{HelperReadWrite, PacketError, PacketType},
+    fixed_types::FixedString,
+    protocol::{
+        FieldCondition, FieldSchema, HelperReadWrite, PacketError, PacketType, ProtocolVersion,
+        ReadContext, TypeDesc, TypeKind, TypeSchema,
+    },
 };
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
-use std::io::{Read, Seek, Write};
+use std::{
+    io::{Read, Seek, Write},
+    sync::OnceLock,
+};
 
 // ----------------------------------------------------------------
 // Structures
@@ -12,6 +19,7 @@ use std::io::{Read, Seek, Write};
 
 /// Character data. (Classic only)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct Character {
@@ -31,6 +39,7 @@ pub struct Character {
 
 /// HSV color data.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct HSVColor {
@@ -41,17 +50,20 @@ pub struct HSVColor {
 
 /// Character's figure data.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 // I'm unsure if we need to name these fields
 pub struct Figure(pub u16, pub u16, pub u16);
 
 /// Character's accessory data. Represented by three sliders during editing.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct AccessoryData(pub i8, pub i8, pub i8);
 
 /// Character race.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u16)]
 pub enum Race {
@@ -67,6 +79,7 @@ pub enum Race {
 
 /// Character gender.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u16)]
 pub enum Gender {
@@ -80,6 +93,7 @@ pub enum Gender {
 
 /// Character's look data.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, HelperReadWrite)]
 pub struct Look {
@@ -213,6 +227,7 @@ pub struct Look {
 
 /// Character's run animation.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u16)]
 pub enum RunAnimation {
@@ -225,6 +240,7 @@ pub enum RunAnimation {
 
 /// Character's skin color type
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u8)]
 pub enum SkinColor {
@@ -238,6 +254,7 @@ pub enum SkinColor {
 
 /// Character class.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u8)]
 pub enum Class {
@@ -265,6 +282,7 @@ pub enum Class {
 bitflags::bitflags! {
     /// Enabled classes flags.
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
     #[cfg_attr(feature = "serde", serde(default))]
     #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
     #[BitFlags(u16)]
@@ -289,6 +307,7 @@ bitflags::bitflags! {
 
 /// Character class level.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Copy, Clone, PartialEq, HelperReadWrite)]
 pub struct ClassLevel {
@@ -301,6 +320,7 @@ pub struct ClassLevel {
 
 /// Info about the character classes.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct ClassInfo {
@@ -360,6 +380,15 @@ impl HelperReadWrite for Character {
         packet_type: PacketType,
         xor: u32,
         sub: u32,
+    ) -> Result<Self, PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, &mut ReadContext::default())
+    }
+    fn read_limited(
+        reader: &mut (impl Read + Seek),
+        packet_type: PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
     ) -> Result<Self, PacketError> {
         let character_id =
             reader
@@ -422,7 +451,7 @@ impl HelperReadWrite for Character {
                 })?;
         }
 
-        let look = Look::read(reader, packet_type, xor, sub).map_err(|e| {
+        let look = Look::read_limited(reader, packet_type, xor, sub, ctx).map_err(|e| {
             PacketError::CompositeFieldError {
                 packet_name: "Character",
                 field_name: "look",
@@ -436,7 +465,7 @@ impl HelperReadWrite for Character {
                 field_name: "unk3",
                 error: e,
             })?;
-        let classes = ClassInfo::read(reader, packet_type, xor, sub).map_err(|e| {
+        let classes = ClassInfo::read_limited(reader, packet_type, xor, sub, ctx).map_err(|e| {
             PacketError::CompositeFieldError {
                 packet_name: "Character",
                 field_name: "classes",
@@ -488,6 +517,16 @@ impl HelperReadWrite for Character {
         xor: u32,
         sub: u32,
     ) -> Result<(), PacketError> {
+        self.write_versioned(writer, &ProtocolVersion::latest(packet_type), xor, sub)
+    }
+    fn write_versioned(
+        &self,
+        writer: &mut impl Write,
+        version: &ProtocolVersion,
+        xor: u32,
+        sub: u32,
+    ) -> Result<(), PacketError> {
+        let packet_type = version.packet_type;
         writer
             .write_u32::<LittleEndian>(self.character_id)
             .map_err(|e| PacketError::FieldError {
@@ -548,7 +587,7 @@ impl HelperReadWrite for Character {
                 })?;
         }
         self.look
-            .write(writer, packet_type, xor, sub)
+            .write_versioned(writer, version, xor, sub)
             .map_err(|e| PacketError::CompositeFieldError {
                 packet_name: "Character",
                 field_name: "look",
@@ -562,7 +601,7 @@ impl HelperReadWrite for Character {
                 error: e,
             })?;
         self.classes
-            .write(writer, packet_type, xor, sub)
+            .write_versioned(writer, version, xor, sub)
             .map_err(|e| PacketError::CompositeFieldError {
                 packet_name: "Character",
                 field_name: "classes",
@@ -595,6 +634,43 @@ impl HelperReadWrite for Character {
 
         Ok(())
     }
+
+    fn schema() -> Option<&'static TypeSchema> {
+        static SCHEMA: OnceLock<TypeSchema> = OnceLock::new();
+        Some(SCHEMA.get_or_init(|| TypeSchema {
+            name: "Character",
+            kind: TypeKind::Struct(vec![
+                FieldSchema::new("character_id", "u32", u32::type_desc),
+                FieldSchema::new("player_id", "u32", u32::type_desc),
+                FieldSchema::new("unk1", "u32", u32::type_desc),
+                FieldSchema::new("voice_type", "u32", u32::type_desc),
+                FieldSchema::new("unk2", "u16", u16::type_desc),
+                FieldSchema::new("voice_pitch", "i16", i16::type_desc),
+                FieldSchema::new("name", "FixedString<16>", FixedString::<16>::type_desc),
+                FieldSchema {
+                    condition: FieldCondition::OnlyOn(vec![PacketType::Vita]),
+                    ..FieldSchema::new("padding", "[u8; 4]", || TypeDesc::FixedBytes {
+                        n: 4,
+                        pad: false,
+                    })
+                },
+                FieldSchema::new("look", "Look", Look::type_desc),
+                FieldSchema::new("unk3", "u32", u32::type_desc),
+                FieldSchema::new("classes", "ClassInfo", ClassInfo::type_desc),
+                FieldSchema {
+                    seek_after: 0x56,
+                    ..FieldSchema::new("unk4", "FixedString<32>", FixedString::<32>::type_desc)
+                },
+                FieldSchema {
+                    condition: FieldCondition::OnlyOn(vec![PacketType::NA]),
+                    ..FieldSchema::new("padding", "[u8; 4]", || TypeDesc::FixedBytes {
+                        n: 4,
+                        pad: false,
+                    })
+                },
+            ]),
+        }))
+    }
 }
 
 // ----------------------------------------------------------------
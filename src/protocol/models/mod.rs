@@ -4,7 +4,7 @@ pub mod character;
 #[cfg_attr(docsrs, doc(cfg(feature = "item_attrs")))]
 pub mod item_attrs;
 
-use super::{PacketError, PacketType};
+use super::{PacketError, PacketType, TypeDesc};
 use crate::protocol::HelperReadWrite;
 use half::f16;
 
@@ -14,6 +14,7 @@ use half::f16;
 
 /// Generic object position. Almost always followed by one [`u16`] padding.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Copy, Clone, PartialEq, HelperReadWrite)]
 pub struct Position {
@@ -35,6 +36,7 @@ pub struct Position {
 
 /// Euler type position. Not used in game, just for printing.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct EulerPosition {
@@ -55,12 +57,14 @@ pub struct EulerPosition {
 // For implementation details look at the HelperReadWrite impl.
 /// SG currency value.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct SGValue(pub f32);
 
 // For implementation details look at the HelperReadWrite impl.
 /// Fun currency value.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct FunValue(pub u32);
 
@@ -89,6 +93,10 @@ impl HelperReadWrite for EulerPosition {
         let pos: Position = (*self).into();
         pos.write(writer, packet_type, xor, sub)
     }
+
+    fn type_desc() -> TypeDesc {
+        Position::type_desc()
+    }
 }
 
 impl HelperReadWrite for SGValue {
@@ -139,6 +147,14 @@ impl HelperReadWrite for SGValue {
             })?;
         Ok(())
     }
+
+    // stored as a u32 with swapped halves
+    fn type_desc() -> TypeDesc {
+        TypeDesc::Opaque {
+            name: "SGValue",
+            size: Some(4),
+        }
+    }
 }
 
 impl HelperReadWrite for FunValue {
@@ -187,6 +203,14 @@ impl HelperReadWrite for FunValue {
             })?;
         Ok(())
     }
+
+    // stored as a u32 with swapped halves
+    fn type_desc() -> TypeDesc {
+        TypeDesc::Opaque {
+            name: "FunValue",
+            size: Some(4),
+        }
+    }
 }
 
 // ----------------------------------------------------------------
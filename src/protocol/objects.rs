@@ -24,6 +24,7 @@ use std::{
 ///
 /// Response to: [`crate::protocol::Packet::Interact`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x02)]
@@ -43,6 +44,7 @@ pub struct TeleportTransferPacket {
 ///
 /// Response to: [`crate::protocol::Packet::ItemPickupRequest`] and ???
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x06)]
@@ -61,6 +63,7 @@ pub struct DespawnObjectPacket {
 /// Response to: [`crate::protocol::Packet::Movement`] (C->S)
 /// Respond with: [`crate::protocol::Packet::Movement`] (S->C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct MovementPacket {
@@ -102,6 +105,7 @@ pub struct MovementPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::MovementActionServer`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x08)]
@@ -130,6 +134,7 @@ pub struct MovementActionPacket {
 /// Followed by: [`crate::protocol::Packet::GainedEXP`]
 // literally the same as DamageReceive
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x0F)]
@@ -168,6 +173,7 @@ pub struct EnemyKilledPacket {
 ///
 /// (C -> S)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x13)]
@@ -185,6 +191,7 @@ pub struct Unk0413Packet {
 ///
 /// Respond with: [`crate::protocol::Packet::SetTag`] (optional)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x14)]
@@ -207,6 +214,7 @@ pub struct InteractPacket {
 ///
 /// Response to: [`crate::protocol::Packet::Interact`] (optional)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x15)]
@@ -234,6 +242,7 @@ pub struct SetTagPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::ChangeClass`], [`crate::protocol::Packet::Unk042C`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x20)]
@@ -258,6 +267,7 @@ pub struct ChangeClassRequestPacket {
 ///
 /// Follow with: [`crate::protocol::Packet::Unk0E21`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x21)]
@@ -276,6 +286,7 @@ pub struct ChangeClassPacket {
 ///
 /// (S -> C) Sent when an enemy performs an action (i.e. spawns or dies).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x22)]
@@ -292,6 +303,7 @@ pub struct EnemyActionPacket {
 ///
 /// (C -> S)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x23)]
@@ -307,6 +319,7 @@ pub struct Unk0423Packet {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x24)]
@@ -325,6 +338,7 @@ pub struct Unk0424Packet {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x25)]
@@ -341,6 +355,7 @@ pub struct Unk0425Packet {
 ///
 /// (C -> S)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x2B)]
@@ -356,6 +371,7 @@ pub struct Unk042BPacket {
 ///
 /// Response to: [`crate::protocol::Packet::ChangeClassRequest`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x2C)]
@@ -373,6 +389,7 @@ pub struct Unk042CPacket {
 ///
 /// (S -> C) Sent on any character spawning to list learned photon arts.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x2E)]
@@ -391,6 +408,7 @@ pub struct LoadPAsPacket {
 ///
 /// (S -> C) Sent when a player leaves a map (e.g player disconnects).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x3B)]
@@ -408,6 +426,7 @@ pub struct DespawnPlayerPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::ActionUpdateServer`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x3C)]
@@ -425,6 +444,7 @@ pub struct ActionUpdatePacket {
 ///
 /// Response to: [`crate::protocol::Packet::DealDamage`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x52)]
@@ -465,6 +485,7 @@ pub struct DamageReceivePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::SetTitle`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x5F)]
@@ -485,6 +506,7 @@ pub struct SetTitleRequestPacket {
 /// Response to: [`crate::protocol::Packet::MovementEnd`] (C->S)
 /// Respond with: [`crate::protocol::Packet::MovementEnd`] (S->C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x71)]
@@ -509,6 +531,7 @@ pub struct MovementEndPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SetTitleRequest`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x72)]
@@ -532,6 +555,7 @@ pub struct SetTitlePacket {
 /// Response to: [`crate::protocol::Packet::ActionEnd`] (C->S)
 /// Respond with: [`crate::protocol::Packet::ActionEnd`] (S->C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x75)]
@@ -553,6 +577,7 @@ pub struct ActionEndPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x79)]
@@ -569,6 +594,7 @@ pub struct Unk0479Packet {
 ///
 /// Response to: [`crate::protocol::Packet::MovementAction`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x80)]
@@ -597,6 +623,7 @@ pub struct MovementActionServerPacket {
 ///
 /// Response to: [`crate::protocol::Packet::ActionUpdate`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x81)]
@@ -613,6 +640,7 @@ pub struct ActionUpdateServerPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0x86)]
@@ -626,6 +654,7 @@ pub struct Unk0486Packet {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0xB0)]
@@ -641,6 +670,7 @@ pub struct Unk04B0Packet {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0xBA)]
@@ -659,6 +689,7 @@ pub struct Unk04BAPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x04, 0xEA)]
@@ -1316,4 +1347,34 @@ impl PacketReadWrite for MovementPacket {
         buf.append(&mut tmp_buf);
         Ok(buf)
     }
+
+    fn flags(&self) -> Flags {
+        // packets with all fields present don't have the field flags
+        let is_full = self.unk4.is_none()
+            && self.ent1_id.is_some()
+            && self.ent1_type.is_some()
+            && self.ent1_unk.is_some()
+            && self.ent2_id.is_some()
+            && self.ent2_type.is_some()
+            && self.ent2_unk.is_some()
+            && self.timestamp.is_some()
+            && self.rot_x.is_some()
+            && self.rot_y.is_some()
+            && self.rot_z.is_some()
+            && self.rot_w.is_some()
+            && self.cur_x.is_some()
+            && self.cur_y.is_some()
+            && self.cur_z.is_some()
+            && self.unk1.is_some()
+            && self.unk_x.is_some()
+            && self.unk_y.is_some()
+            && self.unk_z.is_some()
+            && self.unk2.is_some()
+            && self.unk3.is_some();
+        if is_full {
+            Flags::OBJECT_RELATED | Flags::FLAG_10 | Flags::FULL_MOVEMENT
+        } else {
+            Flags::OBJECT_RELATED | Flags::FLAG_10
+        }
+    }
 }
@@ -12,6 +12,7 @@ use crate::{fixed_types::FixedVec, AsciiString};
 ///
 /// Respond with: [`crate::protocol::Packet::TakenOrders`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x1F, 0x01)]
@@ -28,6 +29,7 @@ pub struct TakenOrdersRequestPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::OrderList`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x1F, 0x02)]
@@ -49,6 +51,7 @@ pub struct OrderListRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::OrderListRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x1F, 0x03)]
@@ -67,6 +70,7 @@ pub struct OrderListPacket {
 ///
 /// Response to: [`crate::protocol::Packet::TakenOrdersRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x1F, 0x08)]
@@ -86,6 +90,7 @@ pub struct TakenOrdersPacket {
 ///
 /// (C -> S)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x1F, 0x0F)]
@@ -100,6 +105,7 @@ pub struct Unk1F0FPacket {
 
 /// Daily order definition.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Copy, Clone, Default, PartialEq, HelperReadWrite)]
 pub struct ClientOrder {
@@ -114,6 +120,7 @@ pub struct ClientOrder {
 
 /// Taken daily order status.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Copy, Clone, Default, PartialEq, HelperReadWrite)]
 pub struct OrderStatus {
@@ -15,6 +15,7 @@ use super::{HelperReadWrite, PacketReadWrite};
 /// [`crate::protocol::Packet::StartGame`],
 /// [`crate::protocol::Packet::UpdatePalette`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x21, 0x01)]
@@ -39,6 +40,7 @@ pub struct LoadPalettePacket {
 /// Response to:
 /// [`crate::protocol::Packet::FullPaletteInfoRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x21, 0x03)]
@@ -69,6 +71,7 @@ pub struct FullPaletteInfoPacket {
 /// [`crate::protocol::Packet::EquipedWeapon`],
 /// [`crate::protocol::Packet::ChangeWeaponPalette`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x21, 0x04)]
@@ -85,6 +88,7 @@ pub struct SetPalettePacket {
 /// Respond with:
 /// [`crate::protocol::Packet::LoadPalette`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x21, 0x05)]
@@ -105,6 +109,7 @@ pub struct UpdateSubPalettePacket {
 /// Respond with:
 /// [`crate::protocol::Packet::LoadPalette`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x21, 0x06)]
@@ -119,6 +124,7 @@ pub struct UpdatePalettePacket {
 ///
 /// (C -> S) Sent when a player changes their active subpalette.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x21, 0x08)]
@@ -133,6 +139,7 @@ pub struct SetSubPalettePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::NewDefaultPAs`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x21, 0x0A)]
@@ -146,6 +153,7 @@ pub struct SetDefaultPAsPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SetDefaultPAs`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x21, 0x0F)]
@@ -160,6 +168,7 @@ pub struct NewDefaultPAsPacket {
 
 /// Weapon in the palette.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct WeaponPalette {
@@ -178,6 +187,7 @@ pub struct WeaponPalette {
 
 /// Subpalette definition.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct SubPalette {
@@ -187,6 +197,7 @@ pub struct SubPalette {
 
 /// Photon Art in the palette.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct PalettePA {
@@ -0,0 +1,177 @@
+use super::{PacketError, PacketHeader, PacketView};
+use std::io::Seek;
+
+/// Packet that failed to parse completely.
+///
+/// Returned by [`ProtocolRW::read_view_partial`](super::ProtocolRW::read_view_partial).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PartialPacket {
+    /// Packet header.
+    pub header: PacketHeader,
+    /// Name of the packet variant (empty if the packet ID is unknown).
+    pub packet_name: String,
+    /// Fields that were successfully read.
+    pub fields: Vec<PartialField>,
+    /// Path of the field that failed to read (e.g. `LoadLevelPacket.thing5[3].unk2`).
+    pub failed_field: String,
+    /// Reading error.
+    pub error: String,
+    /// Offset of the first byte that wasn't consumed by [`PartialPacket::fields`] (from the start
+    /// of the packet).
+    pub offset: usize,
+    /// Packet data after the header.
+    pub body: Vec<u8>,
+}
+
+/// Single successfully read field of a [`PartialPacket`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PartialField {
+    /// Field name.
+    pub name: String,
+    /// Offset of the field from the start of the packet.
+    pub offset: usize,
+    /// Raw field data.
+    pub data: Vec<u8>,
+    /// Debug representation of the field (if the field type implements [`std::fmt::Debug`]).
+    pub value: Option<String>,
+}
+
+/// Fields read with a tracing [`ReadContext`](super::ReadContext).
+#[derive(Debug, Default, Clone)]
+pub struct FieldTrace {
+    fields: Vec<TracedField>,
+}
+
+#[derive(Debug, Clone)]
+struct TracedField {
+    name: &'static str,
+    start: u64,
+    end: u64,
+    value: Option<String>,
+}
+
+impl PartialPacket {
+    /// Creates a partial packet from the view, collected trace and the reading error.
+    pub fn new(
+        view: &PacketView<'_>,
+        packet_name: &str,
+        trace: FieldTrace,
+        error: &PacketError,
+    ) -> Self {
+        let data = view.data();
+        // trace positions don't include the length field
+        let mut offset = 8;
+        let fields = trace
+            .fields
+            .into_iter()
+            .map(|f| {
+                let start = (f.start as usize + 4).min(data.len());
+                let end = (f.end as usize + 4).clamp(start, data.len());
+                offset = end;
+                PartialField {
+                    name: f.name.to_string(),
+                    offset: start,
+                    data: data[start..end].to_vec(),
+                    value: f.value,
+                }
+            })
+            .collect();
+        let error_source = match error {
+            PacketError::ReadContextError { error, .. } => error,
+            _ => error,
+        };
+        Self {
+            header: view.header().clone(),
+            packet_name: packet_name.to_string(),
+            fields,
+            failed_field: error.field_path(),
+            error: error_source.to_string(),
+            offset,
+            body: view.body().to_vec(),
+        }
+    }
+
+    /// Returns the data that wasn't consumed by the successfully read fields.
+    pub fn remaining(&self) -> &[u8] {
+        self.body
+            .get(self.offset.saturating_sub(8)..)
+            .unwrap_or_default()
+    }
+}
+
+impl FieldTrace {
+    /// Creates an empty trace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        name: &'static str,
+        start: u64,
+        reader: &mut impl Seek,
+        value: Option<String>,
+    ) {
+        let end = reader.stream_position().unwrap_or(start);
+        self.fields.push(TracedField {
+            name,
+            start,
+            end,
+            value,
+        })
+    }
+
+    /// Returns the number of fields read.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns `true` if no fields were read.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+// Debug representation of traced values without requiring `Debug` on all field types.
+#[doc(hidden)]
+pub struct TraceValue<'a, T>(pub &'a T);
+
+#[doc(hidden)]
+pub trait TraceDebug {
+    fn trace_value(&self) -> Option<String>;
+}
+
+#[doc(hidden)]
+pub trait TraceOpaque {
+    fn trace_value(&self) -> Option<String>;
+}
+
+impl<T: std::fmt::Debug> TraceDebug for &TraceValue<'_, T> {
+    fn trace_value(&self) -> Option<String> {
+        Some(format!("{:?}", self.0))
+    }
+}
+
+impl<T> TraceOpaque for TraceValue<'_, T> {
+    fn trace_value(&self) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TraceDebug, TraceOpaque, TraceValue};
+
+    #[test]
+    // autoref is what selects the implementation
+    #[allow(clippy::needless_borrow)]
+    fn trace_value() {
+        struct Opaque;
+        assert_eq!((&&TraceValue(&5u8)).trace_value().as_deref(), Some("5"));
+        assert_eq!((&&TraceValue(&Opaque)).trace_value(), None);
+    }
+}
@@ -17,6 +17,7 @@ use super::questlist::{Quest, QuestDifficulty, QuestType};
 /// [`crate::protocol::Packet::SetPartyColor`] (for all players in the party),
 /// [`crate::protocol::Packet::PartySetupFinish`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x00)]
@@ -70,6 +71,7 @@ pub struct AddMemberPacket {
 /// [`crate::protocol::Packet::SetPartyColor`] (for all players in the party),
 /// [`crate::protocol::Packet::PartySetupFinish`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x01)]
@@ -89,6 +91,7 @@ pub struct RemoveMemberPacket {
 /// [`crate::protocol::Packet::SetPartyColor`] (for all players in the party),
 /// [`crate::protocol::Packet::PartySetupFinish`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x02)]
@@ -112,6 +115,7 @@ pub struct PartyInitPacket {
 ///
 /// Response to: [`crate::protocol::Packet::PartyInviteRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x04)]
@@ -126,6 +130,7 @@ pub struct PartyInviteResultPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::PartyInviteResult`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x05)]
@@ -138,6 +143,7 @@ pub struct PartyInviteRequestPacket {
 ///
 /// (S -> C) Sent when someone invites the receiver to the party.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x06)]
@@ -162,6 +168,7 @@ pub struct NewInvitePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::PartyInit`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x07)]
@@ -178,6 +185,7 @@ pub struct AcceptInvitePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::PartySettings`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x0C)]
@@ -211,6 +219,7 @@ pub struct NewPartySettingsPacket {
 ///
 /// Following: [`crate::protocol::Packet::PartyInit`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x0D)]
@@ -240,6 +249,7 @@ pub struct PartySettingsPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::NewLeader`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x0E)]
@@ -256,6 +266,7 @@ pub struct TransferLeaderPacket {
 ///
 /// Following: [`crate::protocol::Packet::RemoveMember`] (?)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x0F)]
@@ -270,6 +281,7 @@ pub struct NewLeaderPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::KickedMember`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x10)]
@@ -286,6 +298,7 @@ pub struct KickMemberPacket {
 ///
 /// Follow with: [`crate::protocol::Packet::RemovedFromParty`] (if being kicked)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x11)]
@@ -300,6 +313,7 @@ pub struct KickedMemberPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::PartyDisbandedMarker`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x17)]
@@ -316,6 +330,7 @@ pub struct DisbandPartyPacket {
 ///
 /// Response to: [`crate::protocol::Packet::ChatStatus`] (C -> S)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x19)]
@@ -331,6 +346,7 @@ pub struct ChatStatusPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x1A)]
@@ -349,6 +365,7 @@ pub struct Unk0E1APacket {
 /// Follow with: [`crate::protocol::Packet::PartyInfo`] (if more infos are available),
 /// [`crate::protocol::Packet::PartyInfoStopper`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x1B)]
@@ -367,6 +384,7 @@ pub struct PartyInfoPacket {
 ///
 /// Following: [`crate::protocol::Packet::PartyInfo`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x1C)]
@@ -380,6 +398,7 @@ pub struct PartyInfoStopperPacker {
 ///
 /// Respond with: [`crate::protocol::Packet::PartyDetails`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x1D)]
@@ -399,6 +418,7 @@ pub struct GetPartyDetailsPacket {
 /// Follow with: [`crate::protocol::Packet::PartyDetails`] (if more infos are available),
 /// [`crate::protocol::Packet::PartyDetailsStopper`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x1E)]
@@ -415,6 +435,7 @@ pub struct PartyDetailsPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x21)]
@@ -433,6 +454,7 @@ pub struct Unk0E21Packet {
 ///
 /// Follow with: [`crate::protocol::Packet::SetPartyQuest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x25)]
@@ -462,6 +484,7 @@ pub struct SetQuestInfoPacket {
 /// Response to: [`crate::protocol::Packet::SetBusy`],
 /// [`crate::protocol::Packet::SetNotBusy`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x2B)]
@@ -476,6 +499,7 @@ pub struct NewBusyStatePacket {
 ///
 /// (C -> S) Sent when a player changes their invite decline state.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x2C)]
@@ -490,6 +514,7 @@ pub struct InviteDeclinePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::PartyInfo`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x2E)]
@@ -506,6 +531,7 @@ pub struct GetPartyInfoPacket {
 ///
 /// Following: [`crate::protocol::Packet::SetQuestInfo`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x31)]
@@ -525,6 +551,7 @@ pub struct SetPartyQuestPacket {
 ///
 /// (S -> C) Sent when the player joins or leaves the party.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x4F)]
@@ -540,6 +567,7 @@ pub struct SetPartyColorPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x52)]
@@ -552,6 +580,7 @@ pub struct Unk0E52Packet {
 ///
 /// (S -> C) Sent when all of the party's info is sent.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0E, 0x67)]
@@ -565,6 +594,7 @@ pub struct PartySetupFinishPacket {
 
 /// Player entry in party.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, HelperReadWrite)]
 pub struct PartyEntry {
@@ -608,6 +638,7 @@ pub struct PartyEntry {
 
 /// Player party colors.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Copy, Default, PartialEq, HelperReadWrite)]
 #[repr(u8)]
 pub enum Color {
@@ -621,6 +652,7 @@ pub enum Color {
 
 /// Text language of the player.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Copy, Default, PartialEq, HelperReadWrite)]
 #[repr(u8)]
 pub enum ShortLanguage {
@@ -635,6 +667,7 @@ pub enum ShortLanguage {
 bitflags::bitflags! {
     /// Party flags.
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
     #[cfg_attr(feature = "serde", serde(default))]
     #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
     #[BitFlags(u8)]
@@ -659,6 +692,7 @@ bitflags::bitflags! {
 
 /// Party info.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, HelperReadWrite)]
 pub struct PartyInfo {
@@ -677,6 +711,7 @@ pub struct PartyInfo {
 
 /// Party invite status.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Copy, Default, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum RejectStatus {
@@ -688,6 +723,7 @@ pub enum RejectStatus {
 
 /// Party details.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, HelperReadWrite)]
 pub struct PartyDetails {
@@ -708,6 +744,7 @@ pub struct PartyDetails {
 
 /// Party member (short variant).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, HelperReadWrite)]
 pub struct PartyMember {
@@ -731,6 +768,7 @@ pub struct PartyMember {
 
 /// Player busy state.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Clone, Copy, Default, PartialEq, HelperReadWrite)]
 #[repr(u32)]
 pub enum BusyState {
@@ -13,6 +13,7 @@ use super::{items::Item, HelperReadWrite, ObjectHeader, PacketReadWrite};
 ///
 /// Respond with: [`crate::protocol::Packet::ProductSearchResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2D, 0x02)]
@@ -36,6 +37,7 @@ pub struct ProductSearchRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::ProductSearchRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2D, 0x03)]
@@ -52,6 +54,7 @@ pub struct ProductSearchResponsePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::PlayerShopDetailsResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2D, 0x0B)]
@@ -66,6 +69,7 @@ pub struct PlayerShopDetailsRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::PlayerShopDetailsRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2D, 0x0C)]
@@ -90,6 +94,7 @@ pub struct PlayerShopDetailsResponsePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::CharacterSearchResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2D, 0x0D)]
@@ -106,6 +111,7 @@ pub struct CharacterSearchRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::CharacterSearchRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2D, 0x0E)]
@@ -124,6 +130,7 @@ pub struct CharacterSearchResponsePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::RecruitingAlliancesResponse`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2D, 0x12)]
@@ -137,6 +144,7 @@ pub struct RecruitingAlliancesRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::RecruitingAlliancesRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2D, 0x13)]
@@ -155,6 +163,7 @@ pub struct RecruitingAlliancesResponsePacket {
 
 /// Item listed in a player shop.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct SoldItem {
@@ -172,6 +181,7 @@ pub struct SoldItem {
 
 /// Character entry in a character search results.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct CharacterSearchEntry {
@@ -185,6 +195,7 @@ pub struct CharacterSearchEntry {
 
 /// Recruiting alliance entry.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct RecruitingAlliance {
@@ -12,6 +12,7 @@ use half::f16;
 ///
 /// Following: [`crate::protocol::Packet::LoadLevel`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x06, 0x00)]
@@ -27,6 +28,7 @@ pub struct SetPlayerIDPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::DamageReceive`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x06, 0x01)]
@@ -55,6 +57,7 @@ pub struct DealDamagePacket {
 ///
 /// (S -> C) Sent when the players earn EXP.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x06, 0x05)]
@@ -73,6 +76,7 @@ pub struct GainedEXPPacket {
 
 /// Information about EXP receiving player.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct EXPReceiver {
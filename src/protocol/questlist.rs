@@ -20,6 +20,7 @@ use half::f16;
 ///
 /// (S -> C) Sent in order to start a cutscene.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x06)]
@@ -47,6 +48,7 @@ pub struct StartCutscenePacket {
 ///
 /// Respond with: [`crate::protocol::Packet::MinimapReveal`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x09)]
@@ -66,6 +68,7 @@ pub struct MinimapRevealRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::MinimapRevealRequest`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x13)]
@@ -86,6 +89,7 @@ pub struct MinimapRevealPacket {
 ///
 /// Respond with: (0x0B, 0xF1), [`crate::protocol::Packet::AvailableQuests`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x15)]
@@ -99,6 +103,7 @@ pub struct AvailableQuestsRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::AvailableQuestsRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x16)]
@@ -178,6 +183,7 @@ pub struct AvailableQuestsPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::QuestCategory`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x17)]
@@ -197,6 +203,7 @@ pub struct QuestCategoryRequestPacket {
 /// Follow with: [`crate::protocol::Packet::QuestCategory`] (if there are more quests),
 /// [`crate::protocol::Packet::QuestCategoryStopper`] (if all quests are sent)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x18)]
@@ -213,6 +220,7 @@ pub struct QuestCategoryPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::QuestDifficulty`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x19)]
@@ -232,6 +240,7 @@ pub struct QuestDifficultyRequestPacket {
 /// Follow with: [`crate::protocol::Packet::QuestDifficulty`] (if there are more quests),
 /// [`crate::protocol::Packet::QuestDifficultyStopper`] (if all quests are sent)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x1A)]
@@ -246,6 +255,7 @@ pub struct QuestDifficultyPacket {
 ///
 /// (S -> C) Sent when quest points are changed (usually due to an emergency).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x1F)]
@@ -266,6 +276,7 @@ pub struct SetQuestPointsPacket {
 ///
 /// Respond with: setup quest.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x20)]
@@ -286,6 +297,7 @@ pub struct AcceptQuestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::QuestCounterRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x22)]
@@ -298,6 +310,7 @@ pub struct NewUnlockedQuestsPacket {
 ///
 /// (S -> C) Sent when quest points are increase (usually due to killing an enemy).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x28)]
@@ -320,6 +333,7 @@ pub struct QuestPointsAddedPacket {
 ///
 /// Respond with: setup quest.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x2F)]
@@ -336,6 +350,7 @@ pub struct AcceptQuestOtherPacket {
 ///
 /// (S -> C) Sent when the EQ's ARKS Level is changed.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0x62)]
@@ -348,6 +363,7 @@ pub struct EQARKSLevelPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0xAF)]
@@ -362,6 +378,7 @@ pub struct Unk0BAFPacket {
 ///
 /// Respond with: setup quest.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0xCD)]
@@ -374,6 +391,7 @@ pub struct AcceptStoryQuestPacket {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0xD0)]
@@ -388,6 +406,7 @@ pub struct Unk0BD0Packet {
 ///
 /// (C -> S)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0xD4)]
@@ -399,6 +418,7 @@ pub struct Unk0BD4Packet {
 ///
 /// (S -> C)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x0B, 0xF1)]
@@ -414,6 +434,7 @@ pub struct Unk0BF1Packet {
 
 /// Information about a quest.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct Quest {
@@ -458,6 +479,7 @@ pub struct Quest {
 
 /// Amount of parties that can join a quest.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u8)]
 pub enum PartyType {
@@ -473,6 +495,7 @@ pub enum PartyType {
 
 /// Information about a quest difficulty.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct QuestDifficulty {
@@ -494,6 +517,7 @@ pub struct QuestDifficulty {
 
 /// Quest difficulty entry.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct QuestDifficultyEntry {
@@ -523,6 +547,7 @@ pub struct QuestDifficultyEntry {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct QuestThing {
@@ -534,6 +559,7 @@ pub struct QuestThing {
 bitflags::bitflags! {
     /// Available quest types flags.
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
     #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
     #[BitFlags(u128)]
     pub struct AvailableQuestType: u128 {
@@ -633,6 +659,7 @@ bitflags::bitflags! {
 
 /// Type of the quest.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u8)]
 pub enum QuestType {
@@ -710,6 +737,7 @@ pub enum QuestType {
 bitflags::bitflags! {
     /// Available quest difficulties.
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
     #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
     #[BitFlags(u8)]
     pub struct QuestDifficultyType: u8 {
@@ -724,6 +752,7 @@ bitflags::bitflags! {
 
 /// Unlocked quest entry
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, HelperReadWrite)]
 pub struct UnlockedQuest {
@@ -822,6 +851,10 @@ impl HelperReadWrite for RevealedRegions {
                 error: Box::new(e),
             })
     }
+
+    fn type_desc() -> super::TypeDesc {
+        <[u8; 10]>::type_desc()
+    }
 }
 
 // ----------------------------------------------------------------
@@ -845,6 +878,13 @@ impl From<[u8; 10]> for RevealedRegions {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for RevealedRegions {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        <[u8; 10]>::arbitrary(u).map(Self::new)
+    }
+}
+
 impl Debug for RevealedRegions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut list = f.debug_list();
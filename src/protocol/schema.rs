@@ -0,0 +1,524 @@
+use super::{Flags, PacketCategory, PacketType, ProtocolVersion};
+
+/// Layout of a packet struct.
+///
+/// Generated by the `PacketRW` derive and returned by
+/// [`PacketReadWrite::schema`](super::PacketReadWrite::schema).
+#[derive(Debug, Clone)]
+pub struct PacketSchema {
+    /// Name of the packet struct.
+    pub name: &'static str,
+    /// Packet ID.
+    pub id: u8,
+    /// Packet subID.
+    pub subid: u16,
+    /// Packet flags.
+    pub flags: Flags,
+    /// `(xor, sub)` used to obfuscate variable length fields (if the packet is packed).
+    pub magic: Option<(u32, u32)>,
+    /// Packet fields in reading order.
+    pub fields: Vec<FieldSchema>,
+}
+
+/// Layout of a helper type.
+///
+/// Generated by the `HelperRW` derive and returned by
+/// [`HelperReadWrite::schema`](super::HelperReadWrite::schema).
+#[derive(Debug, Clone)]
+pub struct TypeSchema {
+    /// Name of the type.
+    pub name: &'static str,
+    /// Kind of the type.
+    pub kind: TypeKind,
+}
+
+/// Kind of a helper type.
+#[derive(Debug, Clone)]
+pub enum TypeKind {
+    /// Struct with fields.
+    Struct(Vec<FieldSchema>),
+    /// Enum stored as an integer.
+    Enum {
+        /// Integer type of the enum (e.g. `u8`).
+        repr: &'static str,
+        /// Enum variants.
+        variants: Vec<VariantSchema>,
+    },
+    /// Struct of booleans stored as an integer.
+    Flags {
+        /// Integer type of the flags (e.g. `u8`).
+        repr: &'static str,
+        /// Flag fields.
+        flags: Vec<FlagSchema>,
+    },
+    /// [`bitflags`] container.
+    BitFlags {
+        /// Integer type of the flags (e.g. `u8`).
+        repr: &'static str,
+        /// Named flags.
+        flags: Vec<FlagSchema>,
+    },
+}
+
+/// Single field of a packet or helper struct.
+#[derive(Debug, Clone)]
+pub struct FieldSchema {
+    /// Field name.
+    pub name: &'static str,
+    /// Rust type of the field.
+    pub type_name: &'static str,
+    /// Padding before the field.
+    pub seek: i64,
+    /// Padding after the field.
+    pub seek_after: i64,
+    /// Constant u16 before the field.
+    pub const_u16: Option<u16>,
+    /// Packet types on which the field is present.
+    pub condition: FieldCondition,
+    /// First game build containing the field (if set by `#[Since(..)]`).
+    pub since: Option<u32>,
+    /// First game build no longer containing the field (if set by `#[Until(..)]`).
+    pub until: Option<u32>,
+    /// Read/write functions of the field (if set manually).
+    pub manual_rw: Option<(&'static str, &'static str)>,
+    /// Encoding of the field type (see [`HelperReadWrite::type_desc`]).
+    ///
+    /// [`HelperReadWrite::type_desc`]: super::HelperReadWrite::type_desc
+    pub ty: fn() -> TypeDesc,
+}
+
+/// Encoding of a field type.
+///
+/// Returned by [`HelperReadWrite::type_desc`](super::HelperReadWrite::type_desc).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum TypeDesc {
+    /// Little endian number (e.g. `u32` or `f16`).
+    Primitive(&'static str),
+    /// Magic length prefixed UTF-16 string.
+    String,
+    /// Magic length prefixed ASCII string.
+    AsciiString,
+    /// UTF-16 string of `n` characters.
+    FixedString {
+        /// Number of characters.
+        n: usize,
+    },
+    /// ASCII string of `n` characters.
+    FixedAsciiString {
+        /// Number of characters.
+        n: usize,
+    },
+    /// Magic length prefixed bytes.
+    Bytes {
+        /// Data is padded to 4 bytes.
+        pad: bool,
+    },
+    /// `n` bytes.
+    FixedBytes {
+        /// Number of bytes.
+        n: usize,
+        /// Data is padded to 4 bytes.
+        pad: bool,
+    },
+    /// Magic length prefixed elements padded to 4 bytes.
+    Vec(Box<TypeDesc>),
+    /// Elements prefixed by an unobfuscated length.
+    VecUSize {
+        /// Size of the length in bytes.
+        size: usize,
+        /// Element type.
+        of: Box<TypeDesc>,
+    },
+    /// `n` elements.
+    FixedVec {
+        /// Number of elements.
+        n: usize,
+        /// Element type.
+        of: Box<TypeDesc>,
+    },
+    /// Array of `n` elements.
+    Array {
+        /// Number of elements.
+        n: usize,
+        /// Element type.
+        of: Box<TypeDesc>,
+    },
+    /// Windows filetime stored as `u64`.
+    WinTime,
+    /// Duration in seconds stored as `u32`.
+    Duration,
+    /// IPv4 address stored as 4 bytes.
+    Ipv4Addr,
+    /// Helper type with a schema.
+    Schema(&'static TypeSchema),
+    /// Type with an undescribed layout (e.g. a bit packed value or a field read by
+    /// `#[ManualRW]` functions).
+    Opaque {
+        /// Name of the type.
+        name: &'static str,
+        /// Size of the type in bytes (if it is fixed).
+        size: Option<usize>,
+    },
+}
+
+/// Packet types on which a field is present.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldCondition {
+    /// Field is always present.
+    Always,
+    /// Field is present only on the listed packet types.
+    OnlyOn(Vec<PacketType>),
+    /// Field is present on all packet types except the listed ones.
+    NotOn(Vec<PacketType>),
+}
+
+/// Single variant of a helper enum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantSchema {
+    /// Variant name.
+    pub name: &'static str,
+    /// Stored value of the variant.
+    pub value: u128,
+    /// Is this variant returned for unknown values.
+    pub is_default: bool,
+}
+
+/// Single flag of a flags struct.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlagSchema {
+    /// Flag name.
+    pub name: &'static str,
+    /// Bit mask of the flag.
+    pub mask: u128,
+}
+
+/// All packets of a protocol enum.
+///
+/// Generated by the `ProtocolRW` derive and returned by
+/// [`ProtocolRW::registry`](super::ProtocolRW::registry).
+///
+/// # Usage
+/// ```
+/// # use pso2packetlib::protocol::{Packet, PacketType, ProtocolRW};
+/// let entry = Packet::registry()
+///     .find(0x03, 0x0B, PacketType::NGS)
+///     .expect("Packet not found");
+/// assert_eq!(entry.name, "ServerPing");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct PacketRegistry {
+    entries: Vec<PacketEntry>,
+}
+
+/// Single packet of a [`PacketRegistry`].
+#[derive(Debug, Clone)]
+pub struct PacketEntry {
+    /// Name of the enum variant.
+    pub name: &'static str,
+    /// Packet ID.
+    pub id: u8,
+    /// Packet subID.
+    pub subid: u16,
+    /// Packet types on which the packet is defined.
+    pub packet_types: Vec<PacketType>,
+    /// First game build containing the packet (if set by `#[Since(..)]`).
+    pub since: Option<u32>,
+    /// First game build no longer containing the packet (if set by `#[Until(..)]`).
+    pub until: Option<u32>,
+    /// Packet category.
+    pub category: PacketCategory,
+    /// Schema of the packet struct (if the packet has any data).
+    pub schema: Option<&'static PacketSchema>,
+}
+
+/// All packet types that can be parsed.
+pub(crate) const ALL_TYPES: [PacketType; 5] = [
+    PacketType::NGS,
+    PacketType::Classic,
+    PacketType::NA,
+    PacketType::JP,
+    PacketType::Vita,
+];
+
+impl FieldSchema {
+    /// Creates a schema of a field that is present on all packet types, for use in manual
+    /// [`HelperReadWrite::schema`](super::HelperReadWrite::schema) implementations.
+    pub fn new(name: &'static str, type_name: &'static str, ty: fn() -> TypeDesc) -> Self {
+        Self {
+            name,
+            type_name,
+            seek: 0,
+            seek_after: 0,
+            const_u16: None,
+            condition: FieldCondition::Always,
+            since: None,
+            until: None,
+            manual_rw: None,
+            ty,
+        }
+    }
+
+    /// Returns `true` if the field is present on the specified packet type.
+    pub fn is_present(&self, packet_type: PacketType) -> bool {
+        match &self.condition {
+            FieldCondition::Always => true,
+            FieldCondition::OnlyOn(types) => types.contains(&packet_type),
+            FieldCondition::NotOn(types) => !types.contains(&packet_type),
+        }
+    }
+
+    /// Returns `true` if the field is present in the specified protocol version.
+    pub fn is_present_in(&self, version: &ProtocolVersion) -> bool {
+        self.is_present(version.packet_type) && version.in_builds(self.since, self.until)
+    }
+}
+
+impl TypeDesc {
+    /// Returns the encoded size of the type in the specified protocol version (if it is fixed).
+    pub fn fixed_size(&self, version: &ProtocolVersion) -> Option<usize> {
+        match self {
+            Self::Primitive(name) => primitive_size(name),
+            Self::FixedString { n } => Some(n * 2),
+            Self::FixedAsciiString { n } => Some(*n),
+            Self::FixedBytes { n, pad: true } => Some(n.next_multiple_of(4)),
+            Self::FixedBytes { n, pad: false } => Some(*n),
+            Self::FixedVec { n, of } | Self::Array { n, of } => Some(n * of.fixed_size(version)?),
+            Self::WinTime => Some(8),
+            Self::Duration | Self::Ipv4Addr => Some(4),
+            Self::Schema(schema) => schema.fixed_size(version),
+            Self::Opaque { size, .. } => *size,
+            Self::String
+            | Self::AsciiString
+            | Self::Bytes { .. }
+            | Self::Vec(_)
+            | Self::VecUSize { .. } => None,
+        }
+    }
+}
+
+impl TypeSchema {
+    /// Returns the encoded size of the type in the specified protocol version (if it is fixed).
+    pub fn fixed_size(&self, version: &ProtocolVersion) -> Option<usize> {
+        match &self.kind {
+            TypeKind::Struct(fields) => {
+                let mut size = 0i64;
+                for field in fields.iter().filter(|f| f.is_present_in(version)) {
+                    size += field.seek + field.seek_after;
+                    size += field.const_u16.map_or(0, |_| 2);
+                    size += (field.ty)().fixed_size(version)? as i64;
+                }
+                usize::try_from(size).ok()
+            }
+            TypeKind::Enum { repr, .. }
+            | TypeKind::Flags { repr, .. }
+            | TypeKind::BitFlags { repr, .. } => primitive_size(repr),
+        }
+    }
+}
+
+fn primitive_size(name: &str) -> Option<usize> {
+    Some(match name {
+        "u8" | "i8" => 1,
+        "u16" | "i16" | "f16" => 2,
+        "u32" | "i32" | "f32" => 4,
+        "u64" | "i64" | "f64" => 8,
+        "u128" | "i128" => 16,
+        _ => return None,
+    })
+}
+
+impl FieldCondition {
+    #[doc(hidden)]
+    pub fn only_on(matches: impl Fn(PacketType) -> bool) -> Self {
+        Self::OnlyOn(ALL_TYPES.into_iter().filter(|&t| matches(t)).collect())
+    }
+
+    #[doc(hidden)]
+    pub fn not_on(matches: impl Fn(PacketType) -> bool) -> Self {
+        Self::NotOn(ALL_TYPES.into_iter().filter(|&t| matches(t)).collect())
+    }
+}
+
+impl PacketRegistry {
+    /// Creates a registry from the entries.
+    pub const fn new(entries: Vec<PacketEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Returns all entries.
+    pub fn entries(&self) -> &[PacketEntry] {
+        &self.entries
+    }
+
+    /// Finds the packet with the specified ID for the packet type.
+    pub fn find(&self, id: u8, subid: u16, packet_type: PacketType) -> Option<&PacketEntry> {
+        self.entries
+            .iter()
+            .find(|e| e.id == id && e.subid == subid && e.packet_types.contains(&packet_type))
+    }
+
+    /// Finds the packet with the specified ID for the protocol version.
+    pub fn find_versioned(
+        &self,
+        id: u8,
+        subid: u16,
+        version: &ProtocolVersion,
+    ) -> Option<&PacketEntry> {
+        self.entries.iter().find(|e| {
+            e.id == id
+                && e.subid == subid
+                && e.packet_types.contains(&version.packet_type)
+                && version.in_builds(e.since, e.until)
+        })
+    }
+
+    /// Finds the packet by the variant name.
+    pub fn find_name(&self, name: &str) -> Option<&PacketEntry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protocol::{
+        HelperReadWrite, Packet, PacketReadWrite, PacketType, ProtocolRW, ProtocolVersion,
+        TypeDesc, TypeKind, TypeSchema,
+    };
+
+    #[test]
+    fn registry() {
+        let registry = Packet::registry();
+        let entry = registry.find_name("EncryptionRequest").unwrap();
+        assert_eq!((entry.id, entry.subid), (0x11, 0x0B));
+        assert!(entry.schema.is_none());
+        assert!(registry.find(0x11, 0x0B, PacketType::Vita).is_some());
+
+        let entry = registry.find_name("SegaIDLogin").unwrap();
+        let schema = entry.schema.unwrap();
+        assert_eq!(schema.name, "SegaIDLoginPacket");
+        assert!(schema.magic.is_some());
+        let text_lang = schema
+            .fields
+            .iter()
+            .find(|f| f.name == "text_lang")
+            .unwrap();
+        assert_eq!(text_lang.type_name, "Language");
+        let TypeDesc::Schema(TypeSchema {
+            kind: TypeKind::Enum { repr, variants },
+            ..
+        }) = (text_lang.ty)()
+        else {
+            panic!("Expected an enum schema");
+        };
+        assert_eq!(*repr, "u32");
+        assert!(variants.iter().any(|v| v.name == "English"));
+        assert!(std::ptr::eq(
+            schema,
+            crate::protocol::login::SegaIDLoginPacket::schema().unwrap()
+        ));
+
+        assert!(crate::protocol::Flags::schema().is_some());
+    }
+
+    #[test]
+    fn manual_schemas() {
+        use crate::protocol::{
+            items::UnitItem,
+            models::{character::Character, FunValue, SGValue},
+            questlist::RevealedRegions,
+            Item,
+        };
+
+        fn check<T: HelperReadWrite>(packet_type: PacketType) {
+            let mut reader = std::io::Cursor::new(vec![0u8; 0x400]);
+            T::read(&mut reader, packet_type, 0, 0).unwrap();
+            let version = ProtocolVersion::latest(packet_type);
+            assert_eq!(
+                T::type_desc().fixed_size(&version),
+                Some(reader.position() as usize),
+                "{} on {packet_type:?}",
+                std::any::type_name::<T>()
+            );
+        }
+
+        for packet_type in super::ALL_TYPES {
+            check::<Character>(packet_type);
+            check::<Item>(packet_type);
+            check::<FunValue>(packet_type);
+            check::<SGValue>(packet_type);
+            check::<RevealedRegions>(packet_type);
+            check::<UnitItem>(packet_type);
+        }
+    }
+
+    #[test]
+    fn identity() {
+        use crate::protocol::{items::LoadItemPacket, objects::MovementPacket, PacketHeader};
+        use half::f16;
+
+        let full = MovementPacket {
+            ent1_id: Some(1),
+            ent1_type: Some(2),
+            ent1_unk: Some(3),
+            ent2_id: Some(4),
+            ent2_type: Some(5),
+            ent2_unk: Some(6),
+            timestamp: Some(Default::default()),
+            rot_x: Some(f16::ONE),
+            rot_y: Some(f16::ONE),
+            rot_z: Some(f16::ONE),
+            rot_w: Some(f16::ONE),
+            cur_x: Some(f16::ONE),
+            cur_y: Some(f16::ONE),
+            cur_z: Some(f16::ONE),
+            unk1: Some(f16::ONE),
+            unk_x: Some(f16::ONE),
+            unk_y: Some(f16::ONE),
+            unk_z: Some(f16::ONE),
+            unk2: Some(f16::ONE),
+            unk3: Some(7),
+            ..Default::default()
+        };
+        let packets = [
+            Packet::ServerPing,
+            Packet::Movement(Default::default()),
+            Packet::Movement(full),
+            Packet::LoadItem(LoadItemPacket::default()),
+        ];
+        for packet in packets {
+            for packet_type in [PacketType::NGS, PacketType::JP] {
+                let data = packet.write(packet_type);
+                let header =
+                    PacketHeader::read(&mut std::io::Cursor::new(&data[4..]), packet_type).unwrap();
+                assert_eq!(packet.header(packet_type), Some(header.clone()));
+                assert_eq!(
+                    Packet::name_for(header.id, header.subid, packet_type),
+                    Some(packet.name())
+                );
+            }
+        }
+
+        let packet = Packet::CharacterSpawn(Default::default());
+        assert_eq!(packet.name(), "CharacterSpawn");
+        assert!(packet.is_supported_on(PacketType::Vita));
+        assert!(!packet.is_supported_on(PacketType::NGS));
+        assert_eq!(packet.header(PacketType::NGS), None);
+        assert_eq!(
+            Packet::name_for(0x08, 0x04, PacketType::Vita),
+            Some("CharacterSpawn")
+        );
+        assert_eq!(Packet::None.header(PacketType::NGS), None);
+        assert_eq!(Packet::None.name(), "None");
+        assert_eq!(Packet::name_for(0xFF, 0xFF, PacketType::NGS), None);
+
+        for entry in Packet::registry().entries() {
+            for &packet_type in &entry.packet_types {
+                assert_eq!(
+                    Packet::name_for(entry.id, entry.subid, packet_type),
+                    Some(entry.name)
+                );
+            }
+        }
+    }
+}
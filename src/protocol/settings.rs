@@ -10,6 +10,7 @@ use crate::AsciiString;
 ///
 /// (C -> S) Sent when the client wants to save the settings.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2B, 0x01)]
@@ -26,6 +27,7 @@ pub struct SaveSettingsPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SettingsRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2B, 0x02)]
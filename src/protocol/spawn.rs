@@ -13,6 +13,7 @@ use crate::{fixed_types::{FixedAsciiString, FixedBytes, FixedString, VecUSize},
 ///
 /// (S -> C) Sent when a new character is spawned.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite)]
 #[Id(0x08, 0x04)]
@@ -52,6 +53,7 @@ pub struct CharacterSpawnPacket {
 #[cfg(feature = "ngs_packets")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ngs_packets")))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, PartialEq, PacketReadWrite, Default)]
 #[Id(0x08, 0x04)]
@@ -89,6 +91,7 @@ pub struct CharacterSpawnNGSPacket {
 ///
 /// (S -> C) Sent to spawn a new transporter. (only campship telepool?)
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x08, 0x05)]
@@ -113,6 +116,7 @@ pub struct TransporterSpawnPacket {
 ///
 /// (S -> C) Sent to spawn a new event (e.g. camera lock).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x08, 0x09)]
@@ -145,6 +149,7 @@ pub struct EventSpawnPacket {
 ///
 /// (S -> C) Sent to spawn a new object.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x08, 0x0B)]
@@ -167,6 +172,7 @@ pub struct ObjectSpawnPacket {
 ///
 /// (S -> C) Sent to spawn a new NPC.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x08, 0x0C)]
@@ -198,6 +204,7 @@ pub struct NPCSpawnPacket {
 ///
 /// (S -> C) Sent when a new enemy is spawned.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x08, 0x0D)]
@@ -235,6 +242,7 @@ pub struct EnemySpawnPacket {
 
 /// Character spawn type.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[derive(Debug, Default, Clone, Copy, PartialEq, HelperReadWrite)]
 #[repr(u8)]
 pub enum CharacterSpawnType {
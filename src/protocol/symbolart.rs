@@ -13,6 +13,7 @@ use super::{HelperReadWrite, MessageChannel, ObjectHeader, PacketReadWrite};
 ///
 /// Respond with: [`crate::protocol::Packet::SymbolArtClientData`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2F, 0x00)]
@@ -27,6 +28,7 @@ pub struct SymbolArtClientDataRequestPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::SymbolArtData`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2F, 0x01)]
@@ -41,6 +43,7 @@ pub struct SymbolArtDataRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SymbolArtDataRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2F, 0x02)]
@@ -61,6 +64,7 @@ pub struct SymbolArtDataPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SymbolArtClientDataRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2F, 0x03)]
@@ -79,6 +83,7 @@ pub struct SymbolArtClientDataPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::SymbolArtResult`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2F, 0x04)]
@@ -95,6 +100,7 @@ pub struct ChangeSymbolArtPacket {
 ///
 /// Response to: [`crate::protocol::Packet::ChangeSymbolArt`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2F, 0x05)]
@@ -111,6 +117,7 @@ pub struct SymbolArtResultPacket {
 ///
 /// Response to: [`crate::protocol::Packet::SymbolArtListRequest`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2F, 0x07)]
@@ -131,6 +138,7 @@ pub struct SymbolArtListPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::ReceiveSymbolArt`]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2F, 0x08)]
@@ -151,6 +159,7 @@ pub struct SendSymbolArtPacket {
 ///
 /// (S -> C) Sent when a client receives a symbol art.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x2F, 0x09)]
@@ -172,6 +181,7 @@ pub struct ReceiveSymbolArtPacket {
 
 /// Symbol Art in a saved SA list.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Copy, Default, PartialEq, HelperReadWrite)]
 pub struct SlottedSymbolArt {
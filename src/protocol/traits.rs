@@ -1,4 +1,7 @@
-use super::{Flags, PacketCategory, PacketError, PacketType};
+use super::{
+    Flags, PacketCategory, PacketError, PacketHeader, PacketRegistry, PacketSchema, PacketType,
+    PacketView, ParseLimits, PartialPacket, ProtocolVersion, ReadContext, TypeDesc, TypeSchema,
+};
 use std::io::{Read, Seek, Write};
 
 /// Trait for manipulating encryption data.
@@ -15,10 +18,149 @@ pub trait PacketEncryption {
 pub trait ProtocolRW: PacketEncryption + Sized {
     /// Reads packets from an input slice.
     fn read(input: &[u8], packet_type: PacketType) -> Result<Vec<Self>, PacketError>;
+    /// Reads packets from an input slice, failing if any packet exceeds `limits`.
+    fn read_limited(
+        input: &[u8],
+        packet_type: PacketType,
+        limits: &ParseLimits,
+    ) -> Result<Vec<Self>, PacketError> {
+        PacketView::iter(input, packet_type)
+            .map(|view| Self::read_view_limited(&view?, limits))
+            .collect()
+    }
+    /// Reads packets sent by a specific game build from an input slice.
+    fn read_versioned(input: &[u8], version: ProtocolVersion) -> Result<Vec<Self>, PacketError> {
+        PacketView::iter_versioned(input, version)
+            .map(|view| Self::read_view(&view?))
+            .collect()
+    }
+    /// Reads packets sent by a specific game build from an input slice, failing if any packet
+    /// exceeds `limits`.
+    fn read_versioned_limited(
+        input: &[u8],
+        version: ProtocolVersion,
+        limits: &ParseLimits,
+    ) -> Result<Vec<Self>, PacketError> {
+        PacketView::iter_versioned(input, version)
+            .map(|view| Self::read_view_limited(&view?, limits))
+            .collect()
+    }
+    /// Reads a single packet from a borrowed view.
+    ///
+    /// The default implementation reads the packet data with [`ProtocolRW::read`].
+    fn read_view(view: &PacketView<'_>) -> Result<Self, PacketError> {
+        Self::read(view.data(), view.packet_type())?
+            .into_iter()
+            .next()
+            .ok_or_else(|| PacketError::PacketLengthError {
+                error: std::io::ErrorKind::UnexpectedEof.into(),
+            })
+    }
+    /// Reads a single packet from a borrowed view, failing if the packet exceeds `limits`.
+    fn read_view_limited(view: &PacketView<'_>, limits: &ParseLimits) -> Result<Self, PacketError> {
+        limits.check_packet_size(view.len())?;
+        Self::read_view(view)
+    }
+    /// Reads a single packet from a borrowed view using the provided read context.
+    ///
+    /// The default implementation only applies the limits of the context and doesn't record any
+    /// deviations.
+    fn read_view_with(view: &PacketView<'_>, ctx: &mut ReadContext) -> Result<Self, PacketError> {
+        Self::read_view_limited(view, ctx.budget().limits())
+    }
+    /// Reads a single packet from a borrowed view. If the packet fails to parse, returns the
+    /// successfully read fields instead.
+    ///
+    /// Fields are only recorded for packets implementing [`ProtocolRW::read_view_with`].
+    fn read_view_partial(view: &PacketView<'_>) -> Result<Self, Box<PartialPacket>> {
+        let header = view.header();
+        let mut ctx = ReadContext::default().with_trace();
+        Self::read_view_with(view, &mut ctx).map_err(|error| {
+            let packet_name = Self::name_for(header.id, header.subid, view.packet_type());
+            Box::new(PartialPacket::new(
+                view,
+                packet_name.unwrap_or_default(),
+                ctx.take_trace().unwrap_or_default(),
+                &error,
+            ))
+        })
+    }
     /// Writes a packet to a byte vector.
     fn write(&self, packet_type: PacketType) -> Vec<u8>;
+    /// Writes a packet to a stream, returning the number of written bytes.
+    fn write_into(
+        &self,
+        writer: &mut impl Write,
+        packet_type: PacketType,
+    ) -> Result<usize, PacketError> {
+        let data = self.write(packet_type);
+        writer
+            .write_all(&data)
+            .map_err(|error| PacketError::FieldError {
+                packet_name: self.name(),
+                field_name: "data",
+                error,
+            })?;
+        Ok(data.len())
+    }
+    /// Writes a packet as it would be sent by a specific game build, returning the number of
+    /// written bytes.
+    fn write_versioned(
+        &self,
+        writer: &mut impl Write,
+        version: &ProtocolVersion,
+    ) -> Result<usize, PacketError> {
+        self.write_into(writer, version.packet_type)
+    }
+    /// Returns the estimated size of the packet (including the length field).
+    ///
+    /// The default implementation writes the packet to get its size.
+    fn serialized_len(&self, packet_type: PacketType) -> usize {
+        self.write(packet_type).len()
+    }
     /// Returns category of the packet.
     fn get_category(&self) -> PacketCategory;
+    /// Returns category of the packet with the specified ID without parsing it.
+    fn category_for(id: u8, subid: u16, packet_type: PacketType) -> PacketCategory {
+        let _ = (id, subid, packet_type);
+        PacketCategory::Unknown
+    }
+    /// Returns the header of the packet as it would be written for the specified packet type.
+    ///
+    /// Returns `None` if the packet doesn't produce a header (e.g. [`Packet::None`]) or isn't
+    /// defined for the packet type. The header of [`Packet::Raw`] is read from its data.
+    ///
+    /// The default implementation writes the packet to read its header.
+    ///
+    /// [`Packet::None`]: super::Packet::None
+    /// [`Packet::Raw`]: super::Packet::Raw
+    fn header(&self, packet_type: PacketType) -> Option<PacketHeader> {
+        let data = self.write(packet_type);
+        let view = PacketView::new(&data, packet_type).ok()?;
+        Some(view.header().clone())
+    }
+    /// Returns the name of the packet variant.
+    ///
+    /// The default implementation returns the type name.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+    /// Returns `true` if the packet is defined for the specified packet type.
+    ///
+    /// The default implementation checks if the packet has a header for the packet type.
+    fn is_supported_on(&self, packet_type: PacketType) -> bool {
+        self.header(packet_type).is_some()
+    }
+    /// Returns the name of the packet variant with the specified ID without parsing it.
+    fn name_for(id: u8, subid: u16, packet_type: PacketType) -> Option<&'static str> {
+        let _ = (id, subid, packet_type);
+        None
+    }
+    /// Returns the registry of all packets.
+    fn registry() -> &'static PacketRegistry {
+        static EMPTY: PacketRegistry = PacketRegistry::new(vec![]);
+        &EMPTY
+    }
 }
 
 /// Read/Write trait for packet data containing structs.
@@ -29,8 +171,59 @@ pub trait PacketReadWrite: Sized {
         flags: &Flags,
         packet_type: PacketType,
     ) -> Result<Self, PacketError>;
+    /// Reads a packet from a stream, charging variable length fields to the budget of `ctx`.
+    fn read_limited(
+        reader: &mut (impl Read + Seek),
+        flags: &Flags,
+        packet_type: PacketType,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, PacketError> {
+        let _ = ctx;
+        Self::read(reader, flags, packet_type)
+    }
     /// Writes a packet to a Vec.
     fn write(&self, packet_type: PacketType) -> Result<Vec<u8>, PacketError>;
+    /// Writes a packet to a stream, returning the number of written bytes.
+    fn write_into(
+        &self,
+        writer: &mut impl Write,
+        packet_type: PacketType,
+    ) -> Result<usize, PacketError> {
+        let data = self.write(packet_type)?;
+        writer
+            .write_all(&data)
+            .map_err(|error| PacketError::FieldError {
+                packet_name: std::any::type_name::<Self>(),
+                field_name: "data",
+                error,
+            })?;
+        Ok(data.len())
+    }
+    /// Writes a packet as it would be sent by a specific game build, returning the number of
+    /// written bytes.
+    fn write_versioned(
+        &self,
+        writer: &mut impl Write,
+        version: &ProtocolVersion,
+    ) -> Result<usize, PacketError> {
+        self.write_into(writer, version.packet_type)
+    }
+    /// Returns the estimated size of the packet (including the header, but not the length
+    /// field).
+    ///
+    /// The default implementation writes the packet to get its size.
+    fn serialized_len(&self, packet_type: PacketType) -> usize {
+        self.write_into(&mut std::io::sink(), packet_type)
+            .unwrap_or(4)
+    }
+    /// Returns the header flags the packet is written with.
+    fn flags(&self) -> Flags {
+        Self::schema().map(|s| s.flags.clone()).unwrap_or_default()
+    }
+    /// Returns the layout of the packet (if known).
+    fn schema() -> Option<&'static PacketSchema> {
+        None
+    }
 }
 
 /// Read/Write trait for aditional data structs/enums.
@@ -42,6 +235,31 @@ pub trait HelperReadWrite: Sized {
         xor: u32,
         sub: u32,
     ) -> Result<Self, PacketError>;
+    /// Reads data from a stream, charging variable length fields to the budget of `ctx`.
+    ///
+    /// Types containing collections should override this method.
+    fn read_limited(
+        reader: &mut (impl Read + Seek),
+        packet_type: PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, PacketError> {
+        let _ = ctx;
+        Self::read(reader, packet_type, xor, sub)
+    }
+    /// Skips the data in a stream without storing it.
+    ///
+    /// Types containing collections should override this method.
+    fn skip(
+        reader: &mut (impl Read + Seek),
+        packet_type: PacketType,
+        xor: u32,
+        sub: u32,
+        ctx: &mut ReadContext,
+    ) -> Result<(), PacketError> {
+        Self::read_limited(reader, packet_type, xor, sub, ctx).map(|_| ())
+    }
     /// Writes data to a stream.
     fn write(
         &self,
@@ -50,4 +268,42 @@ pub trait HelperReadWrite: Sized {
         xor: u32,
         sub: u32,
     ) -> Result<(), PacketError>;
+    /// Writes data as it would be sent by a specific game build to a stream.
+    ///
+    /// Types containing other types should override this method.
+    fn write_versioned(
+        &self,
+        writer: &mut impl Write,
+        version: &ProtocolVersion,
+        xor: u32,
+        sub: u32,
+    ) -> Result<(), PacketError> {
+        self.write(writer, version.packet_type, xor, sub)
+    }
+    /// Returns the estimated number of bytes written by [`HelperReadWrite::write`].
+    ///
+    /// The default implementation writes the data to get its size.
+    fn serialized_len(&self, packet_type: PacketType) -> usize {
+        let mut writer = super::WriteCounter::new(std::io::sink());
+        self.write(&mut writer, packet_type, 0, 0)
+            .map_or(0, |_| writer.count())
+    }
+    /// Returns the layout of the type (if known).
+    fn schema() -> Option<&'static TypeSchema> {
+        None
+    }
+    /// Returns the encoding of the type.
+    ///
+    /// The default implementation refers to the [schema](HelperReadWrite::schema) of the type
+    /// (or returns [`TypeDesc::Opaque`] of an unknown size if there is none). Manual
+    /// implementations should override either method.
+    fn type_desc() -> TypeDesc {
+        match Self::schema() {
+            Some(schema) => TypeDesc::Schema(schema),
+            None => TypeDesc::Opaque {
+                name: std::any::type_name::<Self>(),
+                size: None,
+            },
+        }
+    }
 }
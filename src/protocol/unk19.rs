@@ -12,6 +12,7 @@ use std::time::Duration;
 /// (S -> C) Sent when an important server message needs to be broadcast
 /// (e.g. ship going for maintenance).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x19, 0x01)]
@@ -30,6 +31,7 @@ pub struct SystemMessagePacket {
 ///
 /// (S -> C) Sent when an new lobby event has started/ended (e.g. emergency quest).
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x19, 0x09)]
@@ -53,6 +55,7 @@ pub struct SetLobbyEventPacket {
 ///
 /// (S -> C) Sent when an new lobby video must be played or on logon.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x19, 0x0F)]
@@ -65,6 +68,7 @@ pub struct LobbyMonitorPacket {
 ///
 /// (C -> S) 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x19, 0x1C)]
@@ -84,6 +88,7 @@ pub struct Unk191CPacket {
 
 /// System message type.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, Default, PartialEq, HelperReadWrite)]
 pub enum MessageType {
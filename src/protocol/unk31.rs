@@ -1,5 +1,5 @@
 //! Unknown \[0x31\] packets.
-use super::{HelperReadWrite, Item, ItemId, PacketError, PacketReadWrite, PacketType};
+use super::{HelperReadWrite, Item, ItemId, PacketError, PacketReadWrite, PacketType, ReadContext};
 use crate::AsciiString;
 
 // ----------------------------------------------------------------
@@ -12,6 +12,7 @@ use crate::AsciiString;
 ///
 /// Response to: [`crate::protocol::Packet::NewTitlesRequest`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x31, 0x02)]
@@ -28,6 +29,7 @@ pub struct NewTitlesPacket {
 ///
 /// Response to: [`crate::protocol::Packet::TitleListRequest`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x31, 0x04)]
@@ -45,6 +47,7 @@ pub struct TitleListPacket {
 //
 // See internal repr for real structure.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct LoadTitlesPacket {
@@ -59,6 +62,7 @@ pub struct LoadTitlesPacket {
 ///
 /// Respond with: [`crate::protocol::Packet::LoadTitleCondition`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x31, 0x06)]
@@ -73,6 +77,7 @@ pub struct GetTitleConditionPacket {
 ///
 /// Response to: [`crate::protocol::Packet::GetTitleCondition`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x31, 0x07)]
@@ -92,6 +97,7 @@ pub struct LoadTitleConditionPacket {
 /// Response to: [`crate::protocol::Packet::PlayAchievementsRequest`]
 // See internal repr for more info.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct PlayAchievementsResponsePacket {
@@ -132,6 +138,7 @@ pub struct PlayAchievementsResponsePacket {
 /// Respond with: [`crate::protocol::Packet::AddedItem`],
 /// [`crate::protocol::Packet::ReceiveTitleReward`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x31, 0x0A)]
@@ -146,6 +153,7 @@ pub struct ReceiveTitleRewardRequestPacket {
 ///
 /// Response to: [`crate::protocol::Packet::ReceiveTitleRewardRequest`].
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x31, 0x0B)]
@@ -161,6 +169,7 @@ pub struct ReceiveTitleRewardPacket {
 // ----------------------------------------------------------------
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x31, 0x05)]
@@ -173,6 +182,7 @@ struct LoadTitlesInternal {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Clone, Default, PartialEq, PacketReadWrite)]
 #[Id(0x31, 0x09)]
@@ -213,6 +223,7 @@ struct PlayAchievementsInternal {
 
 /// Internal representation of the enemy record.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 struct EnemyRecordInternal {
@@ -230,6 +241,7 @@ struct EnemyRecordInternal {
 
 /// Title name + ID pair.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct NamedTitleId {
@@ -241,6 +253,7 @@ pub struct NamedTitleId {
 
 /// Title information.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct TitleInfo {
@@ -256,6 +269,7 @@ pub struct TitleInfo {
 
 /// Quest record entry.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq, HelperReadWrite)]
 pub struct QuestRecord {
@@ -272,6 +286,7 @@ pub struct QuestRecord {
 
 /// Enemy record entry.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct EnemyRecord {
@@ -291,19 +306,29 @@ impl PacketReadWrite for LoadTitlesPacket {
         flags: &super::Flags,
         packet_type: PacketType,
     ) -> Result<Self, PacketError> {
-        let packet = LoadTitlesInternal::read(reader, flags, packet_type).map_err(|e| {
-            PacketError::CompositeFieldError {
-                packet_name: "LoadTitlesPacket",
-                field_name: "internal",
-                error: Box::new(e),
-            }
-        })?;
+        Self::read_limited(reader, flags, packet_type, &mut Default::default())
+    }
+
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        flags: &super::Flags,
+        packet_type: PacketType,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, PacketError> {
+        let packet =
+            LoadTitlesInternal::read_limited(reader, flags, packet_type, ctx).map_err(|e| {
+                PacketError::CompositeFieldError {
+                    packet_name: "LoadTitlesPacket",
+                    field_name: "internal",
+                    error: Box::new(e),
+                }
+            })?;
         let mut names = packet.names.chars();
         let mut items = vec![];
         for (title_id, name_length) in packet
             .title_ids
             .into_iter()
-            .zip(packet.name_lens.into_iter())
+            .zip(packet.name_lens)
         {
             let name = names.by_ref().take(name_length as usize).collect();
             items.push(NamedTitleId { name, title_id });
@@ -332,6 +357,10 @@ impl PacketReadWrite for LoadTitlesPacket {
             error: Box::new(e),
         })
     }
+
+    fn flags(&self) -> super::Flags {
+        super::Flags::PACKED
+    }
 }
 
 impl PacketReadWrite for PlayAchievementsResponsePacket {
@@ -340,13 +369,21 @@ impl PacketReadWrite for PlayAchievementsResponsePacket {
         flags: &super::Flags,
         packet_type: super::PacketType,
     ) -> Result<Self, PacketError> {
-        let packet = PlayAchievementsInternal::read(reader, flags, packet_type).map_err(|e| {
-            PacketError::CompositeFieldError {
+        Self::read_limited(reader, flags, packet_type, &mut Default::default())
+    }
+
+    fn read_limited(
+        reader: &mut (impl std::io::Read + std::io::Seek),
+        flags: &super::Flags,
+        packet_type: super::PacketType,
+        ctx: &mut super::ReadContext,
+    ) -> Result<Self, PacketError> {
+        let packet = PlayAchievementsInternal::read_limited(reader, flags, packet_type, ctx)
+            .map_err(|e| PacketError::CompositeFieldError {
                 packet_name: "PlayAchievementsResponsePacket",
                 field_name: "internal",
                 error: Box::new(e),
-            }
-        })?;
+            })?;
         let mut names = packet.enemy_ids.chars();
         let mut boss_enemies = vec![];
         let mut rare_enemies = vec![];
@@ -437,4 +474,8 @@ impl PacketReadWrite for PlayAchievementsResponsePacket {
             error: Box::new(e),
         })
     }
+
+    fn flags(&self) -> super::Flags {
+        super::Flags::PACKED
+    }
 }
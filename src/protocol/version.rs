@@ -0,0 +1,65 @@
+use super::PacketType;
+
+/// Protocol version of a packet, i.e. the region and the game build it was sent by.
+///
+/// Packet layouts change between game patches. Fields and packets marked with `#[Since(build)]`
+/// or `#[Until(build)]` are only read and written if the build matches. Everything else only
+/// depends on the [`PacketType`].
+///
+/// # Usage
+/// ```
+/// # use pso2packetlib::protocol::{Packet, PacketType, PacketView, ProtocolVersion, ProtocolRW};
+/// # fn main() -> Result<(), pso2packetlib::protocol::PacketError> {
+/// let version = ProtocolVersion::new(PacketType::NGS, 1000);
+/// let mut data = vec![];
+/// Packet::ServerPing.write_versioned(&mut data, &version)?;
+/// let view = PacketView::new_versioned(&data, version)?;
+/// assert_eq!(view.version(), version);
+/// assert_eq!(Packet::read_versioned(&data, version)?, vec![Packet::ServerPing]);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProtocolVersion {
+    /// Region of the packet.
+    pub packet_type: PacketType,
+    /// Build number of the game. [`ProtocolVersion::LATEST_BUILD`] selects the newest known
+    /// layout.
+    pub build: u32,
+}
+
+impl ProtocolVersion {
+    /// Build number of the newest layout.
+    pub const LATEST_BUILD: u32 = u32::MAX;
+
+    /// Creates a new version.
+    pub const fn new(packet_type: PacketType, build: u32) -> Self {
+        Self { packet_type, build }
+    }
+
+    /// Creates a version with the newest layout of the specified packet type.
+    pub const fn latest(packet_type: PacketType) -> Self {
+        Self::new(packet_type, Self::LATEST_BUILD)
+    }
+
+    /// Returns `true` if a field or packet marked with `#[Since(since)]` and `#[Until(until)]`
+    /// is present in this version (i.e. `since <= build < until`).
+    pub fn in_builds(&self, since: Option<u32>, until: Option<u32>) -> bool {
+        since.map_or(true, |since| self.build >= since)
+            && until.map_or(true, |until| self.build < until)
+    }
+}
+
+impl Default for ProtocolVersion {
+    fn default() -> Self {
+        Self::latest(PacketType::default())
+    }
+}
+
+impl From<PacketType> for ProtocolVersion {
+    fn from(packet_type: PacketType) -> Self {
+        Self::latest(packet_type)
+    }
+}
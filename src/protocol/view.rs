@@ -0,0 +1,348 @@
+use super::{
+    HelperReadWrite, PacketError, PacketHeader, PacketReadWrite, PacketType, PartialPacket,
+    ProtocolRW, ProtocolVersion, ReadContext,
+};
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::{
+    fmt::Write,
+    io::{Cursor, Read, Seek, SeekFrom},
+};
+
+/// Size of the length prefix and the header of a packet.
+const HEADER_LEN: usize = 8;
+
+/// Borrowed view of a single packet.
+///
+/// The view only decodes the packet header. Everything else is read on demand from the borrowed
+/// buffer, so inspecting the header or a few fixed fields doesn't allocate.
+///
+/// # Usage
+/// ```
+/// # use pso2packetlib::protocol::{PacketType, PacketView, Packet, ProtocolRW};
+/// # fn main() -> Result<(), pso2packetlib::protocol::PacketError> {
+/// let data = Packet::ServerPing.write(PacketType::Classic);
+/// for view in PacketView::iter(&data, PacketType::Classic) {
+///     let view = view?;
+///     assert_eq!((view.header().id, view.header().subid), (0x03, 0x0B));
+///     assert_eq!(view.parse::<Packet>()?, Packet::ServerPing);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PacketView<'a> {
+    data: &'a [u8],
+    header: PacketHeader,
+    version: ProtocolVersion,
+}
+
+/// Packets that can read individual fields from a [`PacketView`].
+///
+/// This trait is implemented by the [`PacketReadWrite`] derive macro.
+pub trait PacketFields {
+    /// Field accessors of the packet.
+    type Fields<'a>;
+
+    /// Returns the field accessors for the packet in `view`.
+    fn fields<'a>(view: &PacketView<'a>) -> Self::Fields<'a>;
+}
+
+/// Iterator over packets in a buffer. Created by [`PacketView::iter`].
+///
+/// Iteration stops after the first error.
+#[derive(Debug, Clone)]
+pub struct PacketViews<'a> {
+    input: &'a [u8],
+    version: ProtocolVersion,
+}
+
+/// Reader over the packet data that remembers where the last read started.
+///
+/// Created by [`PacketView::read_with_context`].
+#[derive(Debug, Clone)]
+pub struct ContextReader<'a> {
+    reader: Cursor<&'a [u8]>,
+    last_read: u64,
+}
+
+impl<'a> PacketView<'a> {
+    /// Creates a view of the first packet in the buffer.
+    ///
+    /// Any data after the packet is ignored.
+    pub fn new(input: &'a [u8], packet_type: PacketType) -> Result<Self, PacketError> {
+        Self::new_versioned(input, ProtocolVersion::latest(packet_type))
+    }
+
+    /// Creates a view of the first packet in the buffer sent by a specific game build.
+    ///
+    /// Any data after the packet is ignored.
+    pub fn new_versioned(input: &'a [u8], version: ProtocolVersion) -> Result<Self, PacketError> {
+        let len = (&input[..])
+            .read_u32::<LittleEndian>()
+            .map_err(|e| PacketError::PacketLengthError { error: e })? as usize;
+        if len < HEADER_LEN || input.len() < len {
+            return Err(PacketError::PacketLengthError {
+                error: std::io::ErrorKind::UnexpectedEof.into(),
+            });
+        }
+        let data = &input[..len];
+        let header = PacketHeader::read(&mut Cursor::new(&data[4..]), version.packet_type)
+            .map_err(|e| PacketError::CompositeFieldError {
+                packet_name: "PacketView",
+                field_name: "header",
+                error: Box::new(e),
+            })?;
+        Ok(Self {
+            data,
+            header,
+            version,
+        })
+    }
+
+    /// Returns an iterator over all packets in the buffer.
+    pub fn iter(input: &'a [u8], packet_type: PacketType) -> PacketViews<'a> {
+        Self::iter_versioned(input, ProtocolVersion::latest(packet_type))
+    }
+
+    /// Returns an iterator over all packets in the buffer sent by a specific game build.
+    pub fn iter_versioned(input: &'a [u8], version: ProtocolVersion) -> PacketViews<'a> {
+        PacketViews { input, version }
+    }
+
+    /// Returns the packet header.
+    pub fn header(&self) -> &PacketHeader {
+        &self.header
+    }
+
+    /// Returns the packet type used to decode the header.
+    pub fn packet_type(&self) -> PacketType {
+        self.version.packet_type
+    }
+
+    /// Returns the protocol version used to parse the packet.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// Returns the length of the packet (including the length field and the header).
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the full packet data (including the length field and the header).
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the packet data after the header.
+    pub fn body(&self) -> &'a [u8] {
+        &self.data[HEADER_LEN..]
+    }
+
+    /// Returns a reader over the packet data (excluding the length field) positioned right after
+    /// the header.
+    pub fn reader(&self) -> Cursor<&'a [u8]> {
+        let mut reader = Cursor::new(&self.data[4..]);
+        reader.set_position((HEADER_LEN - 4) as u64);
+        reader
+    }
+
+    /// Reads a single value located at `offset` bytes after the header.
+    ///
+    /// Only fixed layout values should be read this way, as no magic is applied. Fields of derived
+    /// packets can be read by name with [`PacketView::fields`].
+    pub fn read_at<T: HelperReadWrite>(&self, offset: usize) -> Result<T, PacketError> {
+        let mut reader = self.reader();
+        reader
+            .seek(SeekFrom::Current(offset as i64))
+            .map_err(|e| PacketError::PaddingError {
+                packet_name: "PacketView",
+                field_name: "offset",
+                error: e,
+            })?;
+        T::read(&mut reader, self.version.packet_type, 0, 0)
+    }
+
+    /// Returns the field accessors of a specific packet struct.
+    ///
+    /// Each accessor reads a single field, skipping over the preceding ones without allocating.
+    /// This doesn't check the packet ID.
+    ///
+    /// # Usage
+    /// ```
+    /// # use pso2packetlib::protocol::{PacketType, PacketView, Packet, ProtocolRW};
+    /// # use pso2packetlib::protocol::login::LoginResponsePacket;
+    /// # fn main() -> Result<(), pso2packetlib::protocol::PacketError> {
+    /// let packet = LoginResponsePacket {
+    ///     error: "Server is full".to_string(),
+    ///     blockname: "Block 01".to_string().into(),
+    ///     ..Default::default()
+    /// };
+    /// let data = Packet::LoginResponse(packet).write(PacketType::NGS);
+    /// let view = PacketView::new(&data, PacketType::NGS)?;
+    /// // the error message is skipped without being decoded
+    /// let fields = view.fields::<LoginResponsePacket>();
+    /// assert_eq!(fields.blockname()?.as_str(), "Block 01");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fields<T: PacketFields>(&self) -> T::Fields<'a> {
+        T::fields(self)
+    }
+
+    /// Parses the packet data as a specific packet struct.
+    ///
+    /// This doesn't check the packet ID.
+    pub fn parse_as<T: PacketReadWrite>(&self) -> Result<T, PacketError> {
+        self.read_with_context(|reader| {
+            T::read_limited(
+                reader,
+                &self.header.flag,
+                self.version.packet_type,
+                &mut ReadContext::default().with_build(self.version.build),
+            )
+        })
+    }
+
+    /// Calls `f` with a reader positioned right after the header. If `f` fails, the error is
+    /// wrapped in [`PacketError::ReadContextError`] with the packet ID and the offset of the
+    /// failed read.
+    pub fn read_with_context<T>(
+        &self,
+        f: impl FnOnce(&mut ContextReader<'a>) -> Result<T, PacketError>,
+    ) -> Result<T, PacketError> {
+        let reader = self.reader();
+        let mut reader = ContextReader {
+            last_read: reader.position(),
+            reader,
+        };
+        f(&mut reader).map_err(|error| {
+            if let PacketError::ReadContextError { .. } = error {
+                return error;
+            }
+            // the reader skips the length field
+            let offset = reader.last_read as usize + 4;
+            PacketError::ReadContextError {
+                id: self.header.id,
+                subid: self.header.subid,
+                packet_type: self.version.packet_type,
+                offset,
+                remaining: self.data.len().saturating_sub(offset),
+                error: Box::new(error),
+            }
+        })
+    }
+
+    /// Parses the packet.
+    pub fn parse<P: ProtocolRW>(&self) -> Result<P, PacketError> {
+        P::read_view(self)
+    }
+
+    /// Parses the packet, returning the partially parsed packet on failure.
+    ///
+    /// See [`ProtocolRW::read_view_partial`].
+    pub fn parse_partial<P: ProtocolRW>(&self) -> Result<P, Box<PartialPacket>> {
+        P::read_view_partial(self)
+    }
+}
+
+impl<'a> Iterator for PacketViews<'a> {
+    type Item = Result<PacketView<'a>, PacketError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // trailing data that can't even fit a length is ignored
+        if self.input.len() <= 4 {
+            return None;
+        }
+        match PacketView::new_versioned(self.input, self.version) {
+            Ok(view) => {
+                self.input = &self.input[view.len()..];
+                Some(Ok(view))
+            }
+            Err(e) => {
+                self.input = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl std::iter::FusedIterator for PacketViews<'_> {}
+
+impl Read for ContextReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.last_read = self.reader.position();
+        self.reader.read(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+        // the default implementation would move the marker on partial reads
+        self.last_read = self.reader.position();
+        self.reader.read_exact(buf)
+    }
+}
+
+impl Seek for ContextReader<'_> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.reader.seek(pos)
+    }
+
+    fn stream_position(&mut self) -> std::io::Result<u64> {
+        Ok(self.reader.position())
+    }
+}
+
+/// Renders a hexdump of `data`, 16 bytes per line. If `marker` is set, the byte at that offset is
+/// marked on the line below.
+///
+/// # Usage
+/// ```
+/// # use pso2packetlib::protocol::hexdump;
+/// let dump = hexdump(b"\x08\x00\x00\x00ABCD", Some(5));
+/// let mut lines = dump.lines();
+/// assert!(lines.next().unwrap().starts_with("00000000  08 00 00 00 41 42 43 44"));
+/// assert_eq!(lines.next().unwrap(), format!("{:25}^^", ""));
+/// ```
+pub fn hexdump(data: &[u8], marker: Option<usize>) -> String {
+    let mut out = String::new();
+    // a marker at the end of the data still needs a line to point to
+    let lines = data
+        .len()
+        .max(marker.map(|m| m.min(data.len()) + 1).unwrap_or(0))
+        .div_ceil(16);
+    for line in 0..lines {
+        let start = line * 16;
+        let chunk = data.get(start..).unwrap_or_default();
+        let chunk = &chunk[..chunk.len().min(16)];
+        let _ = write!(out, "{start:08X} ");
+        for i in 0..16 {
+            if i == 8 {
+                out.push(' ');
+            }
+            match chunk.get(i) {
+                Some(byte) => {
+                    let _ = write!(out, " {byte:02X}");
+                }
+                None => out.push_str("   "),
+            }
+        }
+        out.push_str("  |");
+        out.extend(chunk.iter().map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        }));
+        out.push_str("|\n");
+        if let Some(marker) = marker.map(|m| m.min(data.len())) {
+            if (start..start + 16).contains(&marker) {
+                let column = marker - start;
+                let padding = 10 + column * 3 + usize::from(column >= 8);
+                let _ = writeln!(out, "{:padding$}^^", "");
+            }
+        }
+    }
+    out
+}
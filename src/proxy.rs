@@ -0,0 +1,363 @@
+//! Man-in-the-middle proxy between a client and a server.
+//!
+//! The proxy accepts a client connection, connects to the upstream server and forwards packets
+//! between them. Encryption is handled on both legs:
+//! - the client encrypts [`Packet::EncryptionRequest`] with the proxy's public key, so the proxy
+//!   decrypts it with the matching private key;
+//! - the proxy reencrypts it with the server's public key before forwarding it.
+//!
+//! Every forwarded packet passes through a [`ProxyHook`] that can modify, drop or inject packets.
+//!
+//! # Usage
+//! ```no_run
+//! # use pso2packetlib::proxy::{Action, Injector, Proxy, ProxyHook};
+//! # use pso2packetlib::protocol::{Packet, PacketType};
+//! # use pso2packetlib::{PrivateKey, PublicKey};
+//! struct Logger;
+//! impl ProxyHook for Logger {
+//!     fn on_client_packet(&mut self, packet: &mut Packet, _: &mut Injector) -> Action {
+//!         println!("C -> S: {packet:?}");
+//!         Action::Forward
+//!     }
+//! }
+//!
+//! # async fn run() -> Result<(), pso2packetlib::connection::ConnectionError> {
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:12199").await?;
+//! let proxy = Proxy::new(
+//!     PacketType::NGS,
+//!     PrivateKey::Path("proxy_key.pem".into()),
+//!     PublicKey::Path("server_pubkey.pem".into()),
+//! );
+//! let mut session = proxy.accept(&listener, "40.91.76.146:12199").await?;
+//! session.run(&mut Logger).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "ppac")]
+use crate::ppac::Direction;
+use crate::{
+    connection::{
+        transport::{AsyncTransportRead, AsyncTransportWrite, DefaultStream},
+        Connection, ConnectionError,
+    },
+    protocol::{Packet, ProtocolVersion},
+    PrivateKey, PublicKey,
+};
+
+/// Action to take with the intercepted packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Action {
+    /// Forward the (possibly modified) packet.
+    #[default]
+    Forward,
+    /// Drop the packet.
+    Drop,
+}
+
+/// Packets to inject into the connection.
+///
+/// Injected packets are sent after the intercepted packet is handled.
+#[derive(Debug, Default)]
+pub struct Injector {
+    to_client: Vec<Packet>,
+    to_server: Vec<Packet>,
+}
+
+/// Hook that is called for every packet passing through the proxy.
+pub trait ProxyHook: Send {
+    /// Called when the client sends a packet.
+    fn on_client_packet(&mut self, packet: &mut Packet, inject: &mut Injector) -> Action {
+        let _ = (packet, inject);
+        Action::Forward
+    }
+    /// Called when the server sends a packet.
+    fn on_server_packet(&mut self, packet: &mut Packet, inject: &mut Injector) -> Action {
+        let _ = (packet, inject);
+        Action::Forward
+    }
+}
+
+/// Hook that forwards all packets unchanged.
+impl ProxyHook for () {}
+
+/// Proxy settings.
+#[derive(Debug, Clone)]
+pub struct Proxy {
+    version: ProtocolVersion,
+    in_keyfile: PrivateKey,
+    out_keyfile: PublicKey,
+    #[cfg(feature = "ppac")]
+    capture_dir: Option<std::path::PathBuf>,
+}
+
+/// Single proxied client.
+#[derive(Debug)]
+pub struct ProxySession<C = DefaultStream, S = DefaultStream> {
+    client: Connection<Packet, C>,
+    server: Connection<Packet, S>,
+}
+
+impl Injector {
+    /// Queues a packet to be sent to the client.
+    pub fn to_client(&mut self, packet: Packet) {
+        self.to_client.push(packet);
+    }
+    /// Queues a packet to be sent to the server.
+    pub fn to_server(&mut self, packet: Packet) {
+        self.to_server.push(packet);
+    }
+}
+
+impl Proxy {
+    /// Creates new proxy settings.
+    /// `in_keyfile` is the RSA key to decrypt client's encryption request.
+    /// `out_keyfile` is the RSA key of the server to reencrypt encryption request.
+    pub fn new(
+        version: impl Into<ProtocolVersion>,
+        in_keyfile: PrivateKey,
+        out_keyfile: PublicKey,
+    ) -> Self {
+        Self {
+            version: version.into(),
+            in_keyfile,
+            out_keyfile,
+            #[cfg(feature = "ppac")]
+            capture_dir: None,
+        }
+    }
+
+    /// Enables capturing of both legs of every session into the specified directory.
+    ///
+    /// Each session creates two files: `<timestamp>_client.pak` with packets as seen by the
+    /// client and `<timestamp>_server.pak` with packets as seen by the server.
+    #[cfg(feature = "ppac")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ppac")))]
+    pub fn capture_to<T: Into<std::path::PathBuf>>(mut self, dir: T) -> Self {
+        self.capture_dir = Some(dir.into());
+        self
+    }
+
+    /// Accepts a single client and connects it to the upstream server.
+    pub async fn accept(
+        &self,
+        listener: &tokio::net::TcpListener,
+        upstream: impl tokio::net::ToSocketAddrs,
+    ) -> Result<ProxySession, ConnectionError> {
+        let (client, _) = listener.accept().await?;
+        let server = tokio::net::TcpStream::connect(upstream).await?;
+        self.connect(client, server)
+    }
+
+    /// Creates a session from already established transports.
+    pub fn connect<C, S>(&self, client: C, server: S) -> Result<ProxySession<C, S>, ConnectionError>
+    where
+        C: AsyncTransportRead + AsyncTransportWrite,
+        S: AsyncTransportRead + AsyncTransportWrite,
+    {
+        #[allow(unused_mut)]
+        let mut client = Connection::with_transport(
+            client,
+            self.version,
+            self.in_keyfile.clone(),
+            PublicKey::None,
+        );
+        #[allow(unused_mut)]
+        let mut server = Connection::with_transport(
+            server,
+            self.version,
+            PrivateKey::None,
+            self.out_keyfile.clone(),
+        );
+        #[cfg(feature = "ppac")]
+        if let Some(dir) = &self.capture_dir {
+            let time = crate::ppac::get_now().as_nanos();
+            client.create_ppac(dir.join(format!("{time}_client.pak")), Direction::ToClient)?;
+            server.create_ppac(dir.join(format!("{time}_server.pak")), Direction::ToServer)?;
+        }
+        Ok(ProxySession { client, server })
+    }
+}
+
+impl<C, S> ProxySession<C, S>
+where
+    C: AsyncTransportRead + AsyncTransportWrite,
+    S: AsyncTransportRead + AsyncTransportWrite,
+{
+    /// Returns the client side connection.
+    pub fn client(&mut self) -> &mut Connection<Packet, C> {
+        &mut self.client
+    }
+
+    /// Returns the server side connection.
+    pub fn server(&mut self) -> &mut Connection<Packet, S> {
+        &mut self.server
+    }
+
+    /// Forwards packets until either side closes the connection.
+    pub async fn run(&mut self, hook: &mut impl ProxyHook) -> Result<(), ConnectionError> {
+        loop {
+            let mut inject = Injector::default();
+            tokio::select! {
+                packet = self.client.read_packet_async() => {
+                    let Some(mut packet) = closed_to_none(packet)? else {
+                        return Ok(());
+                    };
+                    if hook.on_client_packet(&mut packet, &mut inject) == Action::Forward {
+                        self.server.write_packet_async(&packet).await?;
+                    }
+                }
+                packet = self.server.read_packet_async() => {
+                    let Some(mut packet) = closed_to_none(packet)? else {
+                        return Ok(());
+                    };
+                    if hook.on_server_packet(&mut packet, &mut inject) == Action::Forward {
+                        self.client.write_packet_async(&packet).await?;
+                    }
+                }
+            }
+            for packet in inject.to_server {
+                self.server.write_packet_async(&packet).await?;
+            }
+            for packet in inject.to_client {
+                self.client.write_packet_async(&packet).await?;
+            }
+        }
+    }
+}
+
+fn closed_to_none(
+    result: Result<Packet, ConnectionError>,
+) -> Result<Option<Packet>, ConnectionError> {
+    match result {
+        Ok(packet) => Ok(Some(packet)),
+        Err(ConnectionError::Io(e))
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::UnexpectedEof
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, Injector, Proxy, ProxyHook};
+    use crate::{
+        connection::Connection,
+        protocol::{Packet, PacketType},
+        PrivateKey, PublicKey,
+    };
+
+    struct Hook;
+    impl ProxyHook for Hook {
+        fn on_client_packet(&mut self, packet: &mut Packet, inject: &mut Injector) -> Action {
+            match packet {
+                Packet::ClientPing(_) => {
+                    inject.to_client(Packet::ServerPong);
+                    Action::Drop
+                }
+                _ => Action::Forward,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn hook_actions() {
+        let (client, proxy_client) = tokio::io::duplex(0x1000);
+        let (proxy_server, server) = tokio::io::duplex(0x1000);
+        let proxy = Proxy::new(PacketType::NGS, PrivateKey::None, PublicKey::None);
+        let mut session = proxy.connect(proxy_client, proxy_server).unwrap();
+        let mut client: Connection<Packet, _> =
+            Connection::with_transport(client, PacketType::NGS, PrivateKey::None, PublicKey::None);
+        let mut server: Connection<Packet, _> =
+            Connection::with_transport(server, PacketType::NGS, PrivateKey::None, PublicKey::None);
+
+        let mut hook = Hook;
+        let (result, _) = tokio::join!(session.run(&mut hook), async move {
+            let ping = Packet::ClientPing(Default::default());
+            client.write_packet_async(&ping).await.unwrap();
+            client
+                .write_packet_async(&Packet::ServerPing)
+                .await
+                .unwrap();
+            assert_eq!(
+                client.read_packet_async().await.unwrap(),
+                Packet::ServerPong
+            );
+            assert_eq!(
+                server.read_packet_async().await.unwrap(),
+                Packet::ServerPing
+            );
+        });
+        result.unwrap();
+    }
+
+    #[cfg(feature = "ngs_enc")]
+    #[tokio::test]
+    async fn reencrypt_request() {
+        use crate::crypto::SessionKey;
+
+        let proxy_key = rsa::RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let server_key = rsa::RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap();
+        let (client, proxy_client) = tokio::io::duplex(0x1000);
+        let (proxy_server, server) = tokio::io::duplex(0x1000);
+        let proxy = Proxy::new(
+            PacketType::NGS,
+            PrivateKey::Key(proxy_key.clone()),
+            PublicKey::Key(server_key.to_public_key()),
+        );
+        let mut session = proxy.connect(proxy_client, proxy_server).unwrap();
+        let mut client: Connection<Packet, _> = Connection::with_transport(
+            client,
+            PacketType::NGS,
+            PrivateKey::None,
+            PublicKey::Key(proxy_key.to_public_key()),
+        );
+        let mut server: Connection<Packet, _> = Connection::with_transport(
+            server,
+            PacketType::NGS,
+            PrivateKey::Key(server_key),
+            PublicKey::None,
+        );
+
+        let key = SessionKey::generate(PacketType::NGS).unwrap();
+        let secret = key.secret().unwrap();
+        let mut hook = Hook;
+        let (result, _) = tokio::join!(session.run(&mut hook), async move {
+            let request = Packet::EncryptionRequest(key.request());
+            client.write_packet_async(&request).await.unwrap();
+            assert_eq!(client.get_key(), secret);
+            // the server decrypts the key reencrypted by the proxy
+            let Packet::EncryptionRequest(received) = server.read_packet_async().await.unwrap()
+            else {
+                panic!("Expected an encryption request");
+            };
+            assert_eq!(&received.rsa_data[..], key.dec_data());
+            assert_eq!(server.get_key(), secret);
+
+            server
+                .write_packet_async(&Packet::ServerPing)
+                .await
+                .unwrap();
+            assert_eq!(
+                client.read_packet_async().await.unwrap(),
+                Packet::ServerPing
+            );
+            client
+                .write_packet_async(&Packet::ServerPong)
+                .await
+                .unwrap();
+            assert_eq!(
+                server.read_packet_async().await.unwrap(),
+                Packet::ServerPong
+            );
+        });
+        result.unwrap();
+    }
+}
@@ -0,0 +1,390 @@
+//! Packet dispatching.
+//!
+//! [`Router`] (and [`AsyncRouter`]) calls registered handlers based on the received packet. A
+//! handler can be registered for:
+//! - a specific variant ([`Router::on_variant`]);
+//! - a specific packet ID ([`Router::on_id`]). This also matches [`Packet::Unknown`] and
+//!   [`Packet::Raw`] packets with the same ID;
+//! - a whole [`PacketCategory`] ([`Router::on_category`]);
+//! - all remaining packets ([`Router::fallback`]).
+//!
+//! Handlers are searched in the order above and only the first matching handler is called.
+//! Every handler receives a mutable reference to the router's state, so each subsystem can
+//! register its own handlers on a shared router.
+//!
+//! # Usage
+//! ```
+//! # use pso2packetlib::router::Router;
+//! # use pso2packetlib::protocol::{Packet, PacketCategory, PacketType};
+//! #[derive(Default)]
+//! struct Session {
+//!     pings: u32,
+//!     other: u32,
+//! }
+//!
+//! let mut router = Router::<Session, std::io::Error>::new(PacketType::NGS, Session::default());
+//! router
+//!     .on_variant(&Packet::ServerPing, |session, _| {
+//!         session.pings += 1;
+//!         Ok(())
+//!     })
+//!     .fallback(|session, _| {
+//!         session.other += 1;
+//!         Ok(())
+//!     });
+//!
+//! // in a session loop: `router.dispatch(conn.read_packet()?)?;`
+//! assert!(router.dispatch(Packet::ServerPing).unwrap());
+//! assert!(router.dispatch(Packet::ServerPong).unwrap());
+//! assert_eq!(router.state().pings, 1);
+//! assert_eq!(router.state().other, 1);
+//! ```
+//!
+//! [`Packet::Unknown`]: crate::protocol::Packet::Unknown
+//! [`Packet::Raw`]: crate::protocol::Packet::Raw
+
+use crate::protocol::{Packet, PacketCategory, PacketType, ProtocolRW};
+use std::{
+    collections::HashMap,
+    future::Future,
+    mem::{discriminant, Discriminant},
+    pin::Pin,
+};
+
+/// Handler used by [`Router`].
+pub type Handler<S, E, P = Packet> = Box<dyn FnMut(&mut S, P) -> Result<(), E> + Send>;
+
+/// Future returned by [`AsyncHandler`].
+pub type HandlerFuture<E> = Pin<Box<dyn Future<Output = Result<(), E>> + Send>>;
+
+/// Handler used by [`AsyncRouter`].
+pub type AsyncHandler<S, E, P = Packet> = Box<dyn FnMut(&mut S, P) -> HandlerFuture<E> + Send>;
+
+/// Synchronous packet router.
+pub struct Router<S, E, P: ProtocolRW = Packet> {
+    state: S,
+    routes: Routes<Handler<S, E, P>, P>,
+}
+
+/// Asynchronous packet router.
+///
+/// Handlers are called with a mutable reference to the state and return a future that is
+/// awaited by [`AsyncRouter::dispatch`]. Because the future can't borrow the state, any data
+/// required by it must be cloned (e.g. an [`Arc`](std::sync::Arc) or a channel sender).
+pub struct AsyncRouter<S, E, P: ProtocolRW = Packet> {
+    state: S,
+    routes: Routes<AsyncHandler<S, E, P>, P>,
+}
+
+struct Routes<H, P> {
+    packet_type: PacketType,
+    variants: HashMap<Discriminant<P>, H>,
+    ids: HashMap<(u8, u16), H>,
+    categories: HashMap<PacketCategory, H>,
+    fallback: Option<H>,
+}
+
+impl<H, P: ProtocolRW> Routes<H, P> {
+    fn new(packet_type: PacketType) -> Self {
+        Self {
+            packet_type,
+            variants: HashMap::new(),
+            ids: HashMap::new(),
+            categories: HashMap::new(),
+            fallback: None,
+        }
+    }
+
+    fn find(&mut self, packet: &P) -> Option<&mut H> {
+        if self.variants.contains_key(&discriminant(packet)) {
+            return self.variants.get_mut(&discriminant(packet));
+        }
+        if !self.ids.is_empty() {
+            if let Some(header) = packet.header(self.packet_type) {
+                let id = (header.id, header.subid);
+                if self.ids.contains_key(&id) {
+                    return self.ids.get_mut(&id);
+                }
+            }
+        }
+        let category = packet.get_category();
+        if self.categories.contains_key(&category) {
+            return self.categories.get_mut(&category);
+        }
+        self.fallback.as_mut()
+    }
+}
+
+impl<S, E, P: ProtocolRW> Router<S, E, P> {
+    /// Creates a new router. `packet_type` is used to determine IDs of the packets.
+    pub fn new(packet_type: PacketType, state: S) -> Self {
+        Self {
+            state,
+            routes: Routes::new(packet_type),
+        }
+    }
+
+    /// Changes the packet type used to determine IDs of the packets.
+    pub fn change_packet_type(&mut self, packet_type: PacketType) {
+        self.routes.packet_type = packet_type;
+    }
+
+    /// Returns a reference to the state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns a mutable reference to the state.
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    /// Consumes the router, returning the state.
+    pub fn into_state(self) -> S {
+        self.state
+    }
+
+    /// Registers a handler for the same variant as `variant`. The contents of the variant are
+    /// ignored.
+    pub fn on_variant(
+        &mut self,
+        variant: &P,
+        handler: impl FnMut(&mut S, P) -> Result<(), E> + Send + 'static,
+    ) -> &mut Self {
+        self.routes
+            .variants
+            .insert(discriminant(variant), Box::new(handler));
+        self
+    }
+
+    /// Registers a handler for the packet ID.
+    pub fn on_id(
+        &mut self,
+        id: u8,
+        subid: u16,
+        handler: impl FnMut(&mut S, P) -> Result<(), E> + Send + 'static,
+    ) -> &mut Self {
+        self.routes.ids.insert((id, subid), Box::new(handler));
+        self
+    }
+
+    /// Registers a handler for the packet category.
+    pub fn on_category(
+        &mut self,
+        category: PacketCategory,
+        handler: impl FnMut(&mut S, P) -> Result<(), E> + Send + 'static,
+    ) -> &mut Self {
+        self.routes.categories.insert(category, Box::new(handler));
+        self
+    }
+
+    /// Registers a handler for packets that are not matched by any other handler.
+    pub fn fallback(
+        &mut self,
+        handler: impl FnMut(&mut S, P) -> Result<(), E> + Send + 'static,
+    ) -> &mut Self {
+        self.routes.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Calls the handler for the packet. Returns `false` if no handler was found.
+    pub fn dispatch(&mut self, packet: P) -> Result<bool, E> {
+        match self.routes.find(&packet) {
+            Some(handler) => handler(&mut self.state, packet).map(|_| true),
+            None => Ok(false),
+        }
+    }
+}
+
+impl<S, E, P: ProtocolRW> AsyncRouter<S, E, P> {
+    /// Creates a new router. `packet_type` is used to determine IDs of the packets.
+    pub fn new(packet_type: PacketType, state: S) -> Self {
+        Self {
+            state,
+            routes: Routes::new(packet_type),
+        }
+    }
+
+    /// Changes the packet type used to determine IDs of the packets.
+    pub fn change_packet_type(&mut self, packet_type: PacketType) {
+        self.routes.packet_type = packet_type;
+    }
+
+    /// Returns a reference to the state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns a mutable reference to the state.
+    pub fn state_mut(&mut self) -> &mut S {
+        &mut self.state
+    }
+
+    /// Consumes the router, returning the state.
+    pub fn into_state(self) -> S {
+        self.state
+    }
+
+    /// Registers a handler for the same variant as `variant`. The contents of the variant are
+    /// ignored.
+    pub fn on_variant<F>(
+        &mut self,
+        variant: &P,
+        mut handler: impl FnMut(&mut S, P) -> F + Send + 'static,
+    ) -> &mut Self
+    where
+        F: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        self.routes.variants.insert(
+            discriminant(variant),
+            Box::new(move |s, p| Box::pin(handler(s, p))),
+        );
+        self
+    }
+
+    /// Registers a handler for the packet ID.
+    pub fn on_id<F>(
+        &mut self,
+        id: u8,
+        subid: u16,
+        mut handler: impl FnMut(&mut S, P) -> F + Send + 'static,
+    ) -> &mut Self
+    where
+        F: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        self.routes
+            .ids
+            .insert((id, subid), Box::new(move |s, p| Box::pin(handler(s, p))));
+        self
+    }
+
+    /// Registers a handler for the packet category.
+    pub fn on_category<F>(
+        &mut self,
+        category: PacketCategory,
+        mut handler: impl FnMut(&mut S, P) -> F + Send + 'static,
+    ) -> &mut Self
+    where
+        F: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        self.routes
+            .categories
+            .insert(category, Box::new(move |s, p| Box::pin(handler(s, p))));
+        self
+    }
+
+    /// Registers a handler for packets that are not matched by any other handler.
+    pub fn fallback<F>(
+        &mut self,
+        mut handler: impl FnMut(&mut S, P) -> F + Send + 'static,
+    ) -> &mut Self
+    where
+        F: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        self.routes.fallback = Some(Box::new(move |s, p| Box::pin(handler(s, p))));
+        self
+    }
+
+    /// Calls the handler for the packet. Returns `false` if no handler was found.
+    pub fn dispatch(&mut self, packet: P) -> impl Future<Output = Result<bool, E>> + Send {
+        let future = self
+            .routes
+            .find(&packet)
+            .map(|handler| handler(&mut self.state, packet));
+        async move {
+            match future {
+                Some(future) => future.await.map(|_| true),
+                None => Ok(false),
+            }
+        }
+    }
+}
+
+impl<S: std::fmt::Debug, E, P: ProtocolRW> std::fmt::Debug for Router<S, E, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Router")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: std::fmt::Debug, E, P: ProtocolRW> std::fmt::Debug for AsyncRouter<S, E, P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncRouter")
+            .field("state", &self.state)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AsyncRouter, Router};
+    use crate::protocol::{
+        login::ClientPingPacket, Packet, PacketCategory, PacketHeader, PacketType, ProtocolRW,
+    };
+
+    #[test]
+    fn route_order() {
+        let mut router = Router::<Vec<&str>, ()>::new(PacketType::NGS, vec![]);
+        router
+            .on_variant(&Packet::ServerPing, |s, _| {
+                s.push("variant");
+                Ok(())
+            })
+            .on_id(0x11, 0x0D, |s, _| {
+                s.push("id");
+                Ok(())
+            })
+            .on_category(PacketCategory::Server, |s, _| {
+                s.push("category");
+                Ok(())
+            })
+            .fallback(|s, _| {
+                s.push("fallback");
+                Ok(())
+            });
+        router.dispatch(Packet::ServerPing).unwrap();
+        router
+            .dispatch(Packet::ClientPing(ClientPingPacket::default()))
+            .unwrap();
+        router
+            .dispatch(Packet::Unknown((
+                PacketHeader::new(0x11, 0x0D, Default::default()),
+                vec![0; 8],
+            )))
+            .unwrap();
+        router
+            .dispatch(Packet::Raw(
+                Packet::ClientPing(ClientPingPacket::default()).write(PacketType::NGS),
+            ))
+            .unwrap();
+        router.dispatch(Packet::ServerPong).unwrap();
+        router.dispatch(Packet::None).unwrap();
+        assert_eq!(
+            router.into_state(),
+            ["variant", "id", "id", "id", "category", "fallback"]
+        );
+    }
+
+    #[test]
+    fn no_handler() {
+        let mut router = Router::<(), ()>::new(PacketType::NGS, ());
+        assert!(!router.dispatch(Packet::ServerPing).unwrap());
+    }
+
+    #[tokio::test]
+    async fn async_router() {
+        let (send, mut recv) = tokio::sync::mpsc::unbounded_channel();
+        let mut router = AsyncRouter::<_, ()>::new(PacketType::NGS, send);
+        router.on_category(PacketCategory::Server, |send, packet| {
+            let send = send.clone();
+            async move {
+                send.send(packet).unwrap();
+                Ok(())
+            }
+        });
+        assert!(router.dispatch(Packet::ServerPing).await.unwrap());
+        assert!(!router.dispatch(Packet::None).await.unwrap());
+        assert_eq!(recv.recv().await, Some(Packet::ServerPing));
+    }
+}
@@ -1,6 +1,10 @@
 use pso2packetlib::{
     fixed_types::{FixedString, VecUSize},
-    protocol::{PacketType, ProtocolRW},
+    protocol::{
+        DeviationKind, FieldCondition, HelperReadWrite, Lenient, LimitKind, PacketError,
+        PacketReadWrite, PacketType, PacketView, ParseLimits, ProtocolRW, ProtocolVersion,
+        ReadContext, TypeDesc, TypeKind, TypeSchema,
+    },
 };
 
 // we use pso2packetlib_impl instead of pso2packetlib to bypass `derive` feature requirement
@@ -18,6 +22,14 @@ enum Packet {
     Attributes(Attributes),
     #[Id(1, 5)]
     Helpers(Helpers),
+    #[Id(1, 6)]
+    Nested(Nested),
+    #[Id(1, 7)]
+    #[Until(100)]
+    VersionedOld(VersionedOld),
+    #[Id(1, 7)]
+    #[Since(100)]
+    Versioned(Versioned),
     #[Unknown]
     Unknown((pso2packetlib::protocol::PacketHeader, Vec<u8>)),
 }
@@ -86,6 +98,42 @@ struct Helpers {
     e: Enum,
 }
 
+#[derive(Debug, PartialEq, pso2packetlib_impl::PacketRW)]
+#[Id(1, 6)]
+struct Nested {
+    items: Vec<NestedItem>,
+}
+
+#[derive(Debug, PartialEq, pso2packetlib_impl::HelperRW)]
+struct NestedItem {
+    a: u8,
+    b: u32,
+}
+
+#[derive(Debug, PartialEq, pso2packetlib_impl::PacketRW)]
+#[Id(1, 7)]
+struct VersionedOld {
+    a: u8,
+}
+
+#[derive(Debug, PartialEq, pso2packetlib_impl::PacketRW)]
+#[Id(1, 7)]
+struct Versioned {
+    a: u8,
+    #[Since(100)]
+    b: u8,
+    #[Until(200)]
+    c: u8,
+    items: Vec<VersionedItem>,
+}
+
+#[derive(Debug, Default, PartialEq, pso2packetlib_impl::HelperRW)]
+struct VersionedItem {
+    a: u8,
+    #[Since(150)]
+    b: u8,
+}
+
 #[derive(Debug, PartialEq, pso2packetlib_impl::HelperRW)]
 #[Flags(u8)]
 struct HelperFlags {
@@ -193,10 +241,9 @@ fn test_variable() {
         0x41, 0x42, 0x43, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // fixed_astr
         1, 0,  // len
         14, // var_1
-        0, 0, 0, // padding
         2, 0, 0, 0, // len
         15, 16, // var_2
-        0, 0, 0, 0, // padding
+        0, 0, 0, // padding
     ];
     let len = data.len() as u32;
     data[..4].copy_from_slice(&len.to_le_bytes());
@@ -220,6 +267,21 @@ fn test_variable() {
     assert_eq!(packet, expected_packet);
     let data2 = Packet::Variables(packet).write(PacketType::Classic);
     assert_eq!(data, data2);
+
+    // characters outside of the BMP take two UTF-16 units
+    let packet = Variables {
+        str: String::from("A\u{1F600}B"),
+        ..expected_packet
+    };
+    let data = Packet::Variables(packet).write(PacketType::Classic);
+    let Some(Packet::Variables(packet)) = Packet::read(&data, PacketType::Classic)
+        .expect("Failed to read the packet")
+        .pop()
+    else {
+        panic!("Got incorrect packet")
+    };
+    assert_eq!(packet.str, "A\u{1F600}B");
+    assert_eq!(packet.var_2, vec![15, 16].into());
 }
 
 #[test]
@@ -324,3 +386,625 @@ fn test_helpers() {
     let data2 = Packet::Helpers(packet).write(PacketType::Classic);
     assert_eq!(data, data2);
 }
+
+#[test]
+fn test_view() {
+    let mut data = vec![
+        0, 0, 0, 0, // len
+        1, 3, 0, 0, // id
+        127, 0, 0, 1, // ip
+        0, 0, 0, 0, // time,
+        0x64, 0x88, 0x64, 0xE9, 0x95, 0x02, 0, 0, // pso2time
+    ];
+    let len = data.len() as u32;
+    data[..4].copy_from_slice(&len.to_le_bytes());
+    data.extend_from_slice(&[
+        12, 0, 0, 0, // len
+        2, 1, 0, 0, // id
+        1, 2, 3, 4, // data
+    ]);
+    let views: Vec<_> = PacketView::iter(&data, PacketType::Classic)
+        .collect::<Result<_, _>>()
+        .expect("Failed to read views");
+    assert_eq!(views.len(), 2);
+
+    let view = &views[0];
+    assert_eq!((view.header().id, view.header().subid), (1, 3));
+    assert_eq!(view.len(), len as usize);
+    let ip: std::net::Ipv4Addr = view.read_at(0).expect("Failed to read ip");
+    assert_eq!(ip, std::net::Ipv4Addr::LOCALHOST);
+    let misc: Misc = view.parse_as().expect("Failed to parse the packet");
+    assert_eq!(misc.ip, ip);
+    let Packet::Misc(packet) = view.parse().expect("Failed to parse the packet") else {
+        panic!("Got incorrect packet")
+    };
+    assert_eq!(packet, misc);
+    let fields = view.fields::<Misc>();
+    assert_eq!(fields.ip().expect("Failed to read ip"), misc.ip);
+    assert_eq!(
+        fields.pso2_time().expect("Failed to read pso2_time"),
+        misc.pso2_time
+    );
+
+    let view = &views[1];
+    assert_eq!(view.body(), &[1, 2, 3, 4]);
+    let Packet::Unknown((header, body)) = view.parse().expect("Failed to parse the packet") else {
+        panic!("Got incorrect packet")
+    };
+    assert_eq!((header.id, header.subid), (2, 1));
+    assert_eq!(body, vec![1, 2, 3, 4]);
+
+    // truncated packet
+    let mut iter = PacketView::iter(&data[..20], PacketType::Classic);
+    assert!(iter.next().expect("Expected an error").is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn test_view_fields() {
+    // fields after variable length fields
+    let data = Packet::Variables(Variables {
+        vec: vec![1, 2, 3].into(),
+        fixed_vec: vec![4, 5, 6].into(),
+        str: String::from("ABC"),
+        fixed_str: String::from("ABC").into(),
+        astr: String::from("ABCDE").into(),
+        fixed_astr: String::from("ABC").into(),
+        var_1: vec![14].into(),
+        var_2: vec![15, 16].into(),
+    })
+    .write(PacketType::Classic);
+    let view = PacketView::new(&data, PacketType::Classic).expect("Failed to read the view");
+    let fields = view.fields::<Variables>();
+    assert_eq!(fields.astr().expect("Failed to read astr"), "ABCDE");
+    assert_eq!(
+        fields.var_2().expect("Failed to read var_2"),
+        vec![15, 16].into()
+    );
+
+    // conditional fields and nested helper structs
+    let packet = Versioned {
+        a: 1,
+        b: 2,
+        c: 3,
+        items: vec![VersionedItem { a: 4, b: 5 }, VersionedItem { a: 6, b: 7 }],
+    };
+    let version = ProtocolVersion::new(PacketType::NGS, 150);
+    let mut data = vec![];
+    Packet::Versioned(packet)
+        .write_versioned(&mut data, &version)
+        .expect("Failed to write the packet");
+    let view = PacketView::new_versioned(&data, version).expect("Failed to read the view");
+    let fields = view.fields::<Versioned>();
+    assert_eq!(fields.c().expect("Failed to read c"), 3);
+    assert_eq!(
+        fields.items().expect("Failed to read items"),
+        vec![VersionedItem { a: 4, b: 5 }, VersionedItem { a: 6, b: 7 }]
+    );
+    let version = ProtocolVersion::new(PacketType::NGS, 250);
+    let view = PacketView::new_versioned(&data, version).expect("Failed to read the view");
+    assert_eq!(view.fields::<Versioned>().c().expect("Failed to read c"), 0);
+
+    // truncated packet
+    let mut data = Packet::Nested(Nested {
+        items: vec![NestedItem { a: 1, b: 2 }],
+    })
+    .write(PacketType::Classic);
+    data.truncate(data.len() - 5);
+    let len = data.len() as u32;
+    data[..4].copy_from_slice(&len.to_le_bytes());
+    let view = PacketView::new(&data, PacketType::Classic).expect("Failed to read the view");
+    assert!(matches!(
+        view.fields::<Nested>().items(),
+        Err(PacketError::ReadContextError { .. })
+    ));
+}
+
+#[test]
+fn test_error_context() {
+    let data = [
+        20, 0, 0, 0, // len
+        1, 6, 0, 0, // id
+        2, 0, 0, 0, // len
+        1, 2, 0, 0, 0, // items[0]
+        3, 4, 0, // items[1] (truncated)
+    ];
+    let error = Packet::read(&data, PacketType::Classic)
+        .err()
+        .expect("Read should fail");
+    let PacketError::ReadContextError {
+        id,
+        subid,
+        packet_type,
+        offset,
+        remaining,
+        ..
+    } = &error
+    else {
+        panic!("Got incorrect error: {error}")
+    };
+    assert_eq!((*id, *subid), (1, 6));
+    assert_eq!(*packet_type, PacketType::Classic);
+    assert_eq!((*offset, *remaining), (18, 2));
+    assert_eq!(error.field_path(), "Nested.items[1].b");
+    assert_eq!(error.offset(), Some(18));
+
+    let dump = error.hexdump(&data);
+    let lines: Vec<_> = dump.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("00000010  00 03 04 00"));
+    assert_eq!(lines[2], format!("{:16}^^", ""));
+}
+
+#[test]
+fn test_partial() {
+    let data = [
+        11, 0, 0, 0, // len
+        1, 1, 0, 0,    // id
+        1,    // uint8
+        0xFF, // int8
+        0x34, // uint16 (truncated)
+    ];
+    let view = PacketView::new(&data, PacketType::Classic).expect("Failed to read view");
+    assert!(view.parse::<Packet>().is_err());
+    let Err(packet) = view.parse_partial::<Packet>() else {
+        panic!("Packet was parsed completely")
+    };
+    assert_eq!(packet.packet_name, "Numbers");
+    assert_eq!(packet.fields.len(), 2);
+    assert_eq!(packet.fields[0].name, "uint8");
+    assert_eq!(packet.fields[0].offset, 8);
+    assert_eq!(packet.fields[0].data, [1]);
+    assert_eq!(packet.fields[1].name, "int8");
+    assert_eq!(packet.fields[1].value.as_deref(), Some("-1"));
+    assert_eq!(packet.failed_field, "Numbers.uint16");
+    assert_eq!(packet.offset, 10);
+    assert_eq!(packet.remaining(), [0x34]);
+}
+
+#[test]
+fn test_schema() {
+    let schema = Attributes::schema().expect("No schema");
+    assert_eq!((schema.id, schema.subid), (1, 4));
+    assert_eq!(schema.magic, None);
+    let [a, b, c, d] = &schema.fields[..] else {
+        panic!("Got incorrect fields")
+    };
+    assert_eq!((a.name, a.type_name), ("a", "u8"));
+    assert_eq!((a.seek, a.seek_after), (2, 2));
+    assert_eq!(b.const_u16, Some(5));
+    assert_eq!(c.condition, FieldCondition::OnlyOn(vec![PacketType::JP]));
+    assert!(c.is_present(PacketType::JP) && !c.is_present(PacketType::NA));
+    assert!(!d.is_present(PacketType::JP) && d.is_present(PacketType::NGS));
+
+    let schema = Variables::schema().expect("No schema");
+    assert_eq!(schema.magic, Some((0x10, 0x10)));
+    assert_eq!(schema.fields[6].type_name, "VecUSize<u16, u8>");
+    let types: Vec<_> = schema.fields.iter().map(|f| (f.ty)()).collect();
+    assert!(matches!(types[0], TypeDesc::Bytes { pad: true }));
+    assert!(matches!(
+        types[1],
+        TypeDesc::FixedBytes { n: 10, pad: true }
+    ));
+    assert!(matches!(types[3], TypeDesc::FixedString { n: 5 }));
+    let TypeDesc::VecUSize { size: 2, of } = &types[6] else {
+        panic!("Expected a sized vec")
+    };
+    assert!(matches!(**of, TypeDesc::Primitive("u8")));
+
+    let schema = Helpers::schema().expect("No schema");
+    let TypeDesc::Schema(TypeSchema {
+        kind: TypeKind::Flags { repr, flags },
+        ..
+    }) = (schema.fields[0].ty)()
+    else {
+        panic!("Expected a flags schema")
+    };
+    assert_eq!(*repr, "u8");
+    assert_eq!((flags[0].name, flags[0].mask), ("a", 2));
+    assert_eq!((flags[1].name, flags[1].mask), ("b", 4));
+    let Some(TypeKind::BitFlags { flags, .. }) = HelperBitFlags::schema().map(|s| &s.kind) else {
+        panic!("Expected a bitflags schema")
+    };
+    assert_eq!(flags.len(), 3);
+    let Some(TypeKind::Enum { variants, .. }) = Enum::schema().map(|s| &s.kind) else {
+        panic!("Expected an enum schema")
+    };
+    assert!(variants[0].is_default);
+    assert_eq!((variants[1].name, variants[1].value), ("B", 1));
+
+    let registry = Packet::registry();
+    assert_eq!(registry.entries().len(), 8);
+    let entry = registry
+        .find(1, 2, PacketType::NGS)
+        .expect("Packet not found");
+    assert_eq!(entry.name, "Variables");
+    assert_eq!(entry.schema.map(|s| s.name), Some("Variables"));
+}
+
+#[test]
+fn test_identity() {
+    use pso2packetlib::protocol::{Flags, PacketHeader};
+
+    let packet = Packet::Misc(Misc {
+        ip: std::net::Ipv4Addr::LOCALHOST,
+        time: Default::default(),
+        pso2_time: Default::default(),
+    });
+    assert_eq!(packet.name(), "Misc");
+    assert!(packet.is_supported_on(PacketType::NGS));
+    assert!(!packet.is_supported_on(PacketType::Raw));
+    assert_eq!(
+        packet.header(PacketType::NGS),
+        Some(PacketHeader::new(1, 3, Flags::default()))
+    );
+    assert_eq!(Packet::name_for(1, 3, PacketType::Vita), Some("Misc"));
+    assert_eq!(Packet::name_for(1, 3, PacketType::Raw), None);
+    assert_eq!(Packet::name_for(2, 1, PacketType::NGS), None);
+
+    let header = PacketHeader::new(2, 1, Flags::PACKED);
+    let packet = Packet::Unknown((header.clone(), vec![]));
+    assert_eq!(packet.name(), "Unknown");
+    assert_eq!(packet.header(PacketType::Classic), Some(header));
+    assert_eq!(Packet::None.header(PacketType::Classic), None);
+}
+
+#[test]
+fn test_write_into() {
+    let packets = [
+        Packet::Attributes(Attributes {
+            a: 1,
+            b: 2,
+            c: 3,
+            d: 4,
+        }),
+        Packet::Helpers(Helpers {
+            flags: HelperFlags { a: true, b: true },
+            bitflags: HelperBitFlags::A | HelperBitFlags::C,
+            e: Enum::B,
+        }),
+        Packet::Nested(Nested {
+            items: vec![NestedItem { a: 1, b: 2 }, NestedItem { a: 3, b: 4 }],
+        }),
+        Packet::Variables(Variables {
+            vec: vec![1, 2, 3].into(),
+            fixed_vec: vec![4; 10].into(),
+            str: String::from("ABC"),
+            fixed_str: String::from("ABC").into(),
+            astr: "ABCD".into(),
+            fixed_astr: String::from("ABC").into(),
+            var_1: vec![14].into(),
+            var_2: vec![15, 16].into(),
+        }),
+        Packet::None,
+    ];
+    let mut buf = vec![];
+    let mut expected = vec![];
+    for packet in &packets {
+        let data = packet.write(PacketType::JP);
+        assert_eq!(packet.serialized_len(PacketType::JP), data.len());
+        let written = packet
+            .write_into(&mut buf, PacketType::JP)
+            .expect("Failed to write the packet");
+        assert_eq!(written, data.len());
+        expected.extend(data);
+    }
+    assert_eq!(buf, expected);
+    assert_eq!(Packet::read(&buf, PacketType::JP).unwrap().len(), 4);
+
+    // write errors are returned instead of panicking
+    let mut buf = [0u8; 8];
+    assert!(packets[0]
+        .write_into(&mut &mut buf[..], PacketType::JP)
+        .is_err());
+}
+
+#[test]
+fn test_manual_serialized_len() {
+    use pso2packetlib::protocol::{items, login, models::character::Character, objects, unk31};
+
+    fn check<P: PacketReadWrite>(packet: P) {
+        for packet_type in [PacketType::NGS, PacketType::JP] {
+            let data = packet
+                .write(packet_type)
+                .expect("Failed to write the packet");
+            assert_eq!(
+                packet.serialized_len(packet_type),
+                data.len(),
+                "{} on {packet_type:?}",
+                std::any::type_name::<P>()
+            );
+        }
+    }
+
+    check(objects::MovementPacket::default());
+    check(login::CharacterListPacket {
+        characters: vec![Character::default()],
+        equiped_items: vec![Default::default()],
+        ..Default::default()
+    });
+    check(login::EncryptionRequestPacket {
+        rsa_data: vec![0; 0x80].into(),
+    });
+    check(login::CharacterCreatePacket::default());
+    check(items::LoadItemPacket {
+        items: vec![Default::default()],
+    });
+    check(unk31::LoadTitlesPacket {
+        names: vec![Default::default()],
+    });
+    check(unk31::PlayAchievementsResponsePacket::default());
+}
+
+#[test]
+fn test_limits() {
+    fn root(mut error: &PacketError) -> &PacketError {
+        while let PacketError::ReadContextError { error: e, .. }
+        | PacketError::CompositeFieldError { error: e, .. }
+        | PacketError::ElementError { error: e, .. } = error
+        {
+            error = e;
+        }
+        error
+    }
+
+    // huge length
+    let data = [
+        16, 0, 0, 0, // len
+        1, 2, 4, 0, // id
+        0xFF, 0xFF, 0xFF, 0xFF, // vec len
+        0, 0, 0, 0, // vec data
+    ];
+    let error = Packet::read(&data, PacketType::Classic)
+        .err()
+        .expect("Read should fail");
+    let PacketError::LimitError { kind, .. } = root(&error) else {
+        panic!("Got incorrect error: {error}")
+    };
+    assert_eq!(*kind, LimitKind::Elements);
+
+    // length below the magic
+    let mut data = data;
+    data[8..12].copy_from_slice(&0x10u32.to_le_bytes());
+    let error = Packet::read(&data, PacketType::Classic)
+        .err()
+        .expect("Read should fail");
+    assert!(matches!(root(&error), PacketError::FieldLengthError { .. }));
+
+    let nested = Packet::Nested(Nested {
+        items: vec![NestedItem { a: 1, b: 2 }, NestedItem { a: 3, b: 4 }],
+    });
+    let data = nested.write(PacketType::Classic);
+    let limits = ParseLimits {
+        max_elements: 1,
+        ..Default::default()
+    };
+    let error = Packet::read_limited(&data, PacketType::Classic, &limits)
+        .err()
+        .expect("Read should fail");
+    assert!(matches!(
+        root(&error),
+        PacketError::LimitError {
+            kind: LimitKind::Elements,
+            value: 2,
+            max: 1
+        }
+    ));
+    let limits = ParseLimits {
+        max_allocation: std::mem::size_of::<NestedItem>(),
+        ..Default::default()
+    };
+    let error = Packet::read_limited(&data, PacketType::Classic, &limits)
+        .err()
+        .expect("Read should fail");
+    assert!(matches!(
+        root(&error),
+        PacketError::LimitError {
+            kind: LimitKind::Allocation,
+            ..
+        }
+    ));
+    let limits = ParseLimits {
+        max_packet_size: data.len() - 1,
+        ..Default::default()
+    };
+    let error = Packet::read_limited(&data, PacketType::Classic, &limits)
+        .err()
+        .expect("Read should fail");
+    assert!(matches!(
+        error,
+        PacketError::LimitError {
+            kind: LimitKind::PacketSize,
+            ..
+        }
+    ));
+    let limits = ParseLimits {
+        max_packet_size: data.len(),
+        max_elements: 2,
+        max_allocation: 2 * std::mem::size_of::<NestedItem>(),
+    };
+    let packets = Packet::read_limited(&data, PacketType::Classic, &limits).expect("Read failed");
+    assert!(matches!(&packets[..], [Packet::Nested(n)] if n.items.len() == 2));
+}
+
+#[test]
+fn test_versioned() {
+    let packet = || {
+        Packet::Versioned(Versioned {
+            a: 1,
+            b: 2,
+            c: 3,
+            items: vec![VersionedItem { a: 4, b: 5 }],
+        })
+    };
+    let version = |build| ProtocolVersion::new(PacketType::NGS, build);
+
+    // the latest build omits the removed fields
+    let data = packet().write(PacketType::NGS);
+    assert_eq!(data[8..16], [1, 2, 1, 0, 0, 0, 4, 5]);
+    assert_eq!(packet().serialized_len(PacketType::NGS), data.len());
+
+    let mut data = vec![];
+    packet()
+        .write_versioned(&mut data, &version(120))
+        .expect("Failed to write the packet");
+    assert_eq!(data[8..16], [1, 2, 3, 1, 0, 0, 0, 4]);
+    let packets = Packet::read_versioned(&data, version(120)).expect("Failed to read the packet");
+    let [Packet::Versioned(read)] = &packets[..] else {
+        panic!("Got incorrect packet")
+    };
+    assert_eq!((read.b, read.c), (2, 3));
+    assert_eq!(read.items, [VersionedItem { a: 4, b: 0 }]);
+    let view = PacketView::new_versioned(&data, version(120)).expect("Failed to read view");
+    assert_eq!(view.version(), version(120));
+    assert_eq!(view.parse_as::<Versioned>().unwrap().c, 3);
+    // the build is taken from the view
+    let mut ctx = ReadContext::default();
+    let read = Packet::read_view_with(&view, &mut ctx).expect("Failed to read the packet");
+    assert!(matches!(read, Packet::Versioned(Versioned { c: 3, .. })));
+    assert_eq!(ctx.build(), 120);
+    let packets =
+        Lenient::<Packet>::read_versioned(&data, version(120)).expect("Failed to read the packet");
+    let mut lenient_data = vec![];
+    packets[0]
+        .write_versioned(&mut lenient_data, &version(120))
+        .expect("Failed to write the packet");
+    assert_eq!(lenient_data, data);
+
+    // packets with the same ID are selected by the build
+    let mut data = vec![];
+    Packet::VersionedOld(VersionedOld { a: 1 })
+        .write_versioned(&mut data, &version(50))
+        .expect("Failed to write the packet");
+    let packets = Packet::read_versioned(&data, version(50)).expect("Failed to read the packet");
+    assert!(matches!(
+        packets[..],
+        [Packet::VersionedOld(VersionedOld { a: 1 })]
+    ));
+    assert!(Packet::read(&data, PacketType::NGS).is_err());
+
+    let registry = Packet::registry();
+    let entry = registry
+        .find_versioned(1, 7, &version(50))
+        .expect("Packet not found");
+    assert_eq!((entry.name, entry.until), ("VersionedOld", Some(100)));
+    let entry = registry
+        .find_versioned(1, 7, &version(100))
+        .expect("Packet not found");
+    assert_eq!((entry.name, entry.since), ("Versioned", Some(100)));
+    let schema = entry.schema.expect("No schema");
+    assert!(schema.fields[1].is_present_in(&version(100)));
+    assert!(!schema.fields[1].is_present_in(&version(99)));
+    assert!(!schema.fields[2].is_present_in(&ProtocolVersion::latest(PacketType::NGS)));
+}
+
+#[test]
+fn test_lenient() {
+    let mut data = vec![
+        0, 0, 0, 0, // len
+        1, 2, 4, 0, // id
+        3, 0, 0, 0, // len
+        1, 2, 3,    // vec
+        0xFF, // unzeroed padding
+        4, 5, 6, 7, 8, 9, 10, 11, 12, 13, // fixed_vec
+        0, 0, // padding
+        4, 0, 0, 0, // len
+        0x41, 0x00, 0x42, 0x00, 0x00, 0x00, 0x43, 0x00, // str with an uncleared buffer
+        0x41, 0x00, 0x42, 0x00, 0x43, 0x00, 0x00, 0x00, 0x44, 0x00, // dirty fixed_str
+        4, 0, 0, 0, // len
+        0x41, 0x42, 0x43, 0x00, // astr
+        0x41, 0x42, 0x43, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // fixed_astr
+        1, 0,  // len
+        14, // var_1
+        2, 0, 0, 0, // len
+        15, 16, // var_2
+        0, 0, 0, // padding
+        1, 2, 3, 4, // trailing data
+    ];
+    let len = data.len() as u32;
+    data[..4].copy_from_slice(&len.to_le_bytes());
+    let mut packet = Lenient::<Packet>::read(&data, PacketType::Classic)
+        .expect("Failed to read the packet")
+        .pop()
+        .expect("Failed to extract the packet");
+    let Packet::Variables(variables) = &*packet else {
+        panic!("Got incorrect packet")
+    };
+    assert_eq!(
+        (variables.str.as_str(), variables.fixed_str.as_str()),
+        ("AB", "ABC")
+    );
+    let deviations: Vec<_> = packet
+        .deviations()
+        .iter()
+        .map(|d| (d.kind, d.offset))
+        .collect();
+    assert_eq!(
+        deviations,
+        [
+            (DeviationKind::Padding, 0x0F),
+            (DeviationKind::String, 0x1C),
+            (DeviationKind::String, 0x28),
+        ]
+    );
+    assert_eq!(packet.trailing(), [1, 2, 3, 4]);
+    assert_eq!(packet.write(PacketType::Classic), data);
+
+    // only the modified field is written canonically
+    let Packet::Variables(variables) = &mut *packet else {
+        unreachable!()
+    };
+    variables.str = String::from("CD");
+    let mut expected = data.clone();
+    expected[0x1C..0x28]
+        .copy_from_slice(&[3, 0, 0, 0, 0x43, 0x00, 0x44, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(packet.write(PacketType::Classic), expected);
+}
+
+#[test]
+fn test_manual_protocol() {
+    // protocol that only implements the required methods
+    struct Manual(Packet);
+    impl pso2packetlib::protocol::PacketEncryption for Manual {
+        fn is_enc_data(&self) -> bool {
+            false
+        }
+        fn as_enc_data(&self) -> Option<&[u8]> {
+            None
+        }
+        fn mut_enc_data(&mut self) -> Option<&mut Vec<u8>> {
+            None
+        }
+    }
+    impl ProtocolRW for Manual {
+        fn read(input: &[u8], packet_type: PacketType) -> Result<Vec<Self>, PacketError> {
+            Ok(Packet::read(input, packet_type)?
+                .into_iter()
+                .map(Self)
+                .collect())
+        }
+        fn write(&self, packet_type: PacketType) -> Vec<u8> {
+            self.0.write(packet_type)
+        }
+        fn get_category(&self) -> pso2packetlib::protocol::PacketCategory {
+            self.0.get_category()
+        }
+    }
+
+    let packet = Manual(Packet::Attributes(Attributes {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+    }));
+    let header = packet.header(PacketType::JP).unwrap();
+    assert_eq!((header.id, header.subid), (1, 4));
+    assert!(packet.is_supported_on(PacketType::JP));
+    assert!(!Manual(Packet::None).is_supported_on(PacketType::JP));
+    assert!(packet.name().ends_with("Manual"));
+
+    let data = packet.write(PacketType::JP);
+    let view = PacketView::new(&data, PacketType::JP).unwrap();
+    let read = Manual::read_view(&view).unwrap();
+    assert_eq!(read.write(PacketType::JP), data);
+}